@@ -0,0 +1,105 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use cl3::context::{create_context, release_context};
+use cl3::device::{CL_DEVICE_TYPE_ALL, get_device_ids};
+use cl3::kernel_parser::parse_kernel_signatures;
+use cl3::kernel_signature::{KernelSignature, generate_launchers, get_program_signatures};
+use cl3::platform::get_platform_ids;
+use cl3::program::{
+    CL_PROGRAM_BUILD_LOG, build_program, create_program_with_source, get_program_build_info,
+    release_program,
+};
+use cl3::types::cl_int;
+use std::{env, fs, process, ptr};
+
+/// Generates typed Rust kernel launchers for the kernels in an `OpenCL` C file.
+///
+/// Usage: `cargo run --example kernel_launchers -- [--offline] kernels.cl > src/kernels.rs`
+///
+/// The program is built with `-cl-kernel-arg-info` for the first device of the
+/// first platform and the launchers are written to stdout.
+/// With `--offline` the source is parsed without an `OpenCL` runtime.
+fn main() -> Result<(), cl_int> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let offline = args.first().is_some_and(|arg| "--offline" == arg);
+    if offline {
+        args.remove(0);
+    }
+    let Some(path) = args.first().cloned() else {
+        eprintln!("Usage: kernel_launchers [--offline] <kernels.cl>");
+        process::exit(2);
+    };
+    let source = fs::read_to_string(&path).unwrap_or_else(|error| {
+        eprintln!("Failed to read {path}: {error}");
+        process::exit(1);
+    });
+
+    if offline {
+        let signatures = parse_kernel_signatures(&source).unwrap_or_else(|error| {
+            eprintln!("Failed to parse {path}: {error}");
+            process::exit(1);
+        });
+        print_launchers(&signatures);
+        return Ok(());
+    }
+
+    let platforms = get_platform_ids()?;
+    let Some(&platform) = platforms.first() else {
+        eprintln!("No OpenCL platforms found");
+        process::exit(1);
+    };
+    let devices = get_device_ids(platform, CL_DEVICE_TYPE_ALL)?;
+    let Some(&device) = devices.first() else {
+        eprintln!("No OpenCL devices found");
+        process::exit(1);
+    };
+
+    let context = create_context(&[device], ptr::null(), None, ptr::null_mut())?;
+    let program = create_program_with_source(context, &[&source])?;
+    let result = build_program(
+        program,
+        &[device],
+        c"-cl-kernel-arg-info",
+        None,
+        ptr::null_mut(),
+    )
+    .and_then(|()| get_program_signatures(program));
+
+    if let Ok(signatures) = &result {
+        print_launchers(signatures);
+    } else {
+        let log = get_program_build_info(program, device, CL_PROGRAM_BUILD_LOG)
+            .map(String::from)
+            .unwrap_or_default();
+        eprintln!("Failed to build {path}:\n{log}");
+    }
+
+    unsafe {
+        release_program(program)?;
+        release_context(context)?;
+    }
+    result.map(|_| ())
+}
+
+/// Print the launchers for the kernel signatures, or exit if they cannot be generated.
+fn print_launchers(signatures: &[KernelSignature]) {
+    match generate_launchers(signatures) {
+        Ok(code) => print!("{code}"),
+        Err(error) => {
+            eprintln!("Failed to generate launchers: {error}");
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,461 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Structured diagnostics from `OpenCL` program build logs.
+//!
+//! The format of the build log returned by `clGetProgramBuildInfo` with
+//! `CL_PROGRAM_BUILD_LOG` is implementation defined. [`parse_build_log`]
+//! recognises the common formats:
+//! * Clang based compilers, e.g. Intel, NVIDIA, `PoCL` and Mesa:
+//!   `<source>:12:5: error: message`,
+//! * EDG based compilers, e.g. older AMD compilers:
+//!   `"/tmp/OCL1234.cl", line 12: error: message`,
+//! * messages without a location, e.g. `error: message`.
+//!
+//! Source and caret lines echoed by the compiler are skipped and notes are
+//! attached to the preceding diagnostic.
+//!
+//! If the program source was expanded before it was built, e.g. with
+//! [`ResolvedSource::expand`](super::source_loader::ResolvedSource::expand),
+//! a [`LineMap`] maps the diagnostic locations back to the original files.
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{CL_PROGRAM_BUILD_LOG, cl_device_id, cl_int, cl_program};
+
+use super::program::get_program_build_info;
+use std::fmt;
+
+/// The severity of a [`Diagnostic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Remark,
+    Note,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    /// The name of the severity as written by Clang.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Remark => "remark",
+            Self::Note => "note",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The severity keywords of the supported compilers, longest first.
+const SEVERITIES: &[(&str, Severity)] = &[
+    ("catastrophic error", Severity::Error),
+    ("fatal error", Severity::Error),
+    ("error", Severity::Error),
+    ("warning", Severity::Warning),
+    ("remark", Severity::Remark),
+    ("note", Severity::Note),
+];
+
+/// A compiler diagnostic from a build log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// The file name as reported by the compiler, e.g. "<source>" or a header name.
+    pub file: Option<String>,
+    /// The line number, starting from 1.
+    pub line: Option<u32>,
+    /// The column number, starting from 1.
+    pub column: Option<u32>,
+    pub severity: Severity,
+    pub message: String,
+    /// The notes attached to the diagnostic.
+    pub notes: Vec<Self>,
+}
+
+impl Diagnostic {
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        Severity::Error == self.severity
+    }
+}
+
+/// Format the diagnostic and its notes in the Clang/GCC format:
+/// `file:line:column: severity: message`, one per line.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "{line}:")?;
+            if let Some(column) = self.column {
+                write!(f, "{column}:")?;
+            }
+        }
+        if self.file.is_some() || self.line.is_some() {
+            write!(f, " ")?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)?;
+        for note in &self.notes {
+            write!(f, "\n{note}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a severity keyword and the message that follows it,
+/// e.g. "error: message" or "warning #177-D: message".
+fn parse_severity(text: &str) -> Option<(Severity, &str)> {
+    SEVERITIES.iter().find_map(|&(keyword, severity)| {
+        let rest = text.strip_prefix(keyword)?;
+        let message = if let Some(message) = rest.strip_prefix(':') {
+            message
+        } else {
+            // An EDG diagnostic number, e.g. " #177-D:"
+            let rest = rest.strip_prefix(" #")?;
+            let (number, message) = rest.split_once(':')?;
+            if number.contains(char::is_whitespace) {
+                return None;
+            }
+            message
+        };
+        Some((severity, message.trim()))
+    })
+}
+
+/// The location of a diagnostic: file, line and column.
+type Location = (Option<String>, Option<u32>, Option<u32>);
+
+/// Parse a diagnostic location, e.g. `file:12:5`, `file:12`, `:12:5`
+/// or `"file", line 12`.
+fn parse_location(text: &str) -> Option<Location> {
+    let file = |name: &str| {
+        let name = name.trim().trim_matches('"');
+        (!name.is_empty()).then(|| name.to_owned())
+    };
+
+    if let Some((name, line)) = text.rsplit_once(", line ") {
+        return Some((file(name), Some(line.trim().parse().ok()?), None));
+    }
+
+    let mut parts = text.rsplitn(3, ':');
+    let last = parts.next()?.parse::<u32>().ok()?;
+    let name = parts.next()?;
+    if let (Ok(line), Some(name)) = (name.parse::<u32>(), parts.next()) {
+        Some((file(name), Some(line), Some(last)))
+    } else {
+        // Only a line number, the file name may contain ':'
+        let (name, _) = text.rsplit_once(':')?;
+        Some((file(name), Some(last), None))
+    }
+}
+
+/// Parse a diagnostic from a line of a build log.
+fn parse_line(line: &str) -> Option<Diagnostic> {
+    let line = line.trim_end();
+    let diagnostic = |location: Location, severity, message: &str| {
+        let (file, line, column) = location;
+        Diagnostic {
+            file,
+            line,
+            column,
+            severity,
+            message: message.to_owned(),
+            notes: Vec::new(),
+        }
+    };
+
+    if let Some((severity, message)) = parse_severity(line.trim_start()) {
+        return Some(diagnostic((None, None, None), severity, message));
+    }
+
+    // A diagnostic with a location, the location may contain ": " e.g. "C: "
+    let mut unlocated = None;
+    for (index, _) in line.match_indices(": ") {
+        let (prefix, rest) = (&line[..index], &line[index + 2..]);
+        let Some((severity, message)) = parse_severity(rest) else {
+            continue;
+        };
+        if let Some(location) = parse_location(prefix) {
+            return Some(diagnostic(location, severity, message));
+        }
+        // A tool name, e.g. "clang: error: message"
+        if unlocated.is_none() && !prefix.contains(char::is_whitespace) {
+            unlocated = Some(diagnostic((None, None, None), severity, message));
+        }
+    }
+    unlocated
+}
+
+/// Parse an `OpenCL` program build log into diagnostics, see the module documentation.
+///
+/// * `log` - the build log.
+///
+/// returns the diagnostics in the order that they appear in the log,
+/// with notes attached to the diagnostic that they follow.
+#[must_use]
+pub fn parse_build_log(log: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for diagnostic in log.lines().filter_map(parse_line) {
+        match diagnostics.last_mut() {
+            Some(previous) if Severity::Note == diagnostic.severity => {
+                previous.notes.push(diagnostic);
+            }
+            _ => diagnostics.push(diagnostic),
+        }
+    }
+    diagnostics
+}
+
+/// Get the diagnostics from the build log of a program for a device.
+/// Calls clGetProgramBuildInfo with `CL_PROGRAM_BUILD_LOG`.
+///
+/// * `program` - the `OpenCL` program.
+/// * `device` - the device for which the program was built.
+///
+/// returns a Result containing the diagnostics
+/// or the error code from the `OpenCL` C API function.
+pub fn get_build_diagnostics(
+    program: cl_program,
+    device: cl_device_id,
+) -> Result<Vec<Diagnostic>, cl_int> {
+    let log: String = get_program_build_info(program, device, CL_PROGRAM_BUILD_LOG)?.into();
+    Ok(parse_build_log(&log))
+}
+
+/// A segment of a [`LineMap`]: the expanded lines from `start` are from `file`
+/// starting at `line`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct LineSegment {
+    start: u32,
+    file: String,
+    line: u32,
+}
+
+/// The file names that compilers report for the program source.
+const SOURCE_FILES: &[&str] = &["<source>", "<kernel>", "<stdin>"];
+
+/// Maps the lines of an expanded program source back to the files
+/// that they came from.
+///
+/// Only the diagnostics in the program source are mapped: diagnostics
+/// without a file name or with one of the names that compilers report for
+/// the program source, e.g. "<source>", or a name added with
+/// [`add_source_file`](Self::add_source_file).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LineMap {
+    segments: Vec<LineSegment>,
+    source_files: Vec<String>,
+}
+
+impl LineMap {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a segment: the expanded lines from `expanded_line` are from
+    /// `file` starting at `line`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `expanded_line` is before the start of the previous segment.
+    pub fn push(&mut self, expanded_line: u32, file: impl Into<String>, line: u32) {
+        let segment = LineSegment {
+            start: expanded_line,
+            file: file.into(),
+            line,
+        };
+        match self.segments.last_mut() {
+            Some(last) if last.start == expanded_line => *last = segment,
+            Some(last) => {
+                assert!(last.start < expanded_line, "LineMap segments out of order");
+                self.segments.push(segment);
+            }
+            None => self.segments.push(segment),
+        }
+    }
+
+    /// Add a file name that the compiler reports for the program source,
+    /// e.g. the temporary file name of an EDG based compiler.
+    pub fn add_source_file(&mut self, file: impl Into<String>) {
+        self.source_files.push(file.into());
+    }
+
+    /// Whether a diagnostic file name is the program source.
+    fn is_source_file(&self, file: Option<&str>) -> bool {
+        file.is_none_or(|file| {
+            SOURCE_FILES.contains(&file) || self.source_files.iter().any(|name| name == file)
+        })
+    }
+
+    /// Map a line of the expanded source to its file and line.
+    ///
+    /// * `expanded_line` - the line number in the expanded source, starting from 1.
+    ///
+    /// returns the file name and line number or None if the line is not mapped.
+    #[must_use]
+    pub fn map(&self, expanded_line: u32) -> Option<(&str, u32)> {
+        let index = self
+            .segments
+            .partition_point(|segment| segment.start <= expanded_line);
+        let segment = &self.segments[index.checked_sub(1)?];
+        Some((
+            segment.file.as_str(),
+            segment.line + (expanded_line - segment.start),
+        ))
+    }
+
+    /// Map the location of a diagnostic and its notes from the expanded source
+    /// to the original files. Diagnostics in other files, e.g. headers that
+    /// were included by the compiler, are not changed.
+    pub fn apply(&self, diagnostic: &mut Diagnostic) {
+        if self.is_source_file(diagnostic.file.as_deref())
+            && let Some((file, line)) = diagnostic.line.and_then(|line| self.map(line))
+        {
+            diagnostic.file = Some(file.to_owned());
+            diagnostic.line = Some(line);
+        }
+        for note in &mut diagnostic.notes {
+            self.apply(note);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_clang_build_log() {
+        let log = r"<source>:12:5: error: use of undeclared identifier 'x'
+    x = 1;
+    ^
+<source>:3:6: note: previous declaration is here
+void f(int);
+     ^
+In file included from <source>:1:
+common.h:7:1: warning: unused variable 'y' [-Wunused-variable]
+:4:2: fatal error: 'missing.h' file not found
+clang: error: unknown argument: '-cl-bogus'
+2 errors generated.
+";
+        let diagnostics = parse_build_log(log);
+        assert_eq!(4, diagnostics.len());
+
+        let error = &diagnostics[0];
+        assert_eq!(Some("<source>"), error.file.as_deref());
+        assert_eq!((Some(12), Some(5)), (error.line, error.column));
+        assert!(error.is_error());
+        assert_eq!("use of undeclared identifier 'x'", error.message);
+        assert_eq!(1, error.notes.len());
+        assert_eq!(Some(3), error.notes[0].line);
+
+        assert_eq!(Severity::Warning, diagnostics[1].severity);
+        assert_eq!(Some("common.h"), diagnostics[1].file.as_deref());
+
+        assert_eq!(None, diagnostics[2].file);
+        assert_eq!(Some(4), diagnostics[2].line);
+        assert!(diagnostics[2].is_error());
+
+        assert_eq!(None, diagnostics[3].line);
+        assert_eq!("unknown argument: '-cl-bogus'", diagnostics[3].message);
+
+        assert_eq!(
+            "<source>:12:5: error: use of undeclared identifier 'x'\n<source>:3:6: note: previous declaration is here",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_edg_build_log() {
+        let log = r#""/tmp/OCL1234.cl", line 12: error: identifier "x" is undefined
+      x = 1;
+      ^
+
+"C:\kernels\k.cl", line 3: warning #177-D: variable "y" was declared but never referenced
+C:\kernels\k.cl:8:2: error: expected ';'
+error: Compilation failed
+"#;
+        let diagnostics = parse_build_log(log);
+        assert_eq!(4, diagnostics.len());
+        assert_eq!(Some("/tmp/OCL1234.cl"), diagnostics[0].file.as_deref());
+        assert_eq!(
+            (Some(12), None),
+            (diagnostics[0].line, diagnostics[0].column)
+        );
+        assert_eq!("identifier \"x\" is undefined", diagnostics[0].message);
+
+        assert_eq!(Severity::Warning, diagnostics[1].severity);
+        assert_eq!(Some("C:\\kernels\\k.cl"), diagnostics[1].file.as_deref());
+        assert_eq!(Some(3), diagnostics[1].line);
+
+        assert_eq!(Some("C:\\kernels\\k.cl"), diagnostics[2].file.as_deref());
+        assert_eq!(
+            (Some(8), Some(2)),
+            (diagnostics[2].line, diagnostics[2].column)
+        );
+
+        assert_eq!(None, diagnostics[3].file);
+        assert_eq!("Compilation failed", diagnostics[3].message);
+
+        assert!(parse_build_log("").is_empty());
+    }
+
+    #[test]
+    fn test_line_map() {
+        let mut map = LineMap::new();
+        assert_eq!(None, map.map(1));
+        map.push(1, "main.cl", 1);
+        map.push(2, "common.h", 1);
+        map.push(5, "main.cl", 3);
+        assert_eq!(Some(("main.cl", 1)), map.map(1));
+        assert_eq!(Some(("common.h", 3)), map.map(4));
+        assert_eq!(Some(("main.cl", 6)), map.map(8));
+
+        let mut diagnostics = parse_build_log(
+            "<source>:4:1: error: unknown type name 'real'\n<source>:8:2: note: here\n",
+        );
+        map.apply(&mut diagnostics[0]);
+        assert_eq!(Some("common.h"), diagnostics[0].file.as_deref());
+        assert_eq!(
+            (Some(3), Some(1)),
+            (diagnostics[0].line, diagnostics[0].column)
+        );
+        assert_eq!(Some("main.cl"), diagnostics[0].notes[0].file.as_deref());
+        assert_eq!(Some(6), diagnostics[0].notes[0].line);
+
+        // Only diagnostics in the program source are mapped
+        let mut diagnostics = parse_build_log(
+            "/usr/include/clc.h:4:1: error: here\n:4:2: error: there\n\"/tmp/OCL1.cl\", line 4: error: elsewhere\n",
+        );
+        map.add_source_file("/tmp/OCL1.cl");
+        for diagnostic in &mut diagnostics {
+            map.apply(diagnostic);
+        }
+        assert_eq!(Some("/usr/include/clc.h"), diagnostics[0].file.as_deref());
+        assert_eq!(Some(4), diagnostics[0].line);
+        assert_eq!(Some("common.h"), diagnostics[1].file.as_deref());
+        assert_eq!(Some(3), diagnostics[1].line);
+        assert_eq!(Some("common.h"), diagnostics[2].file.as_deref());
+        assert_eq!(Some(3), diagnostics[2].line);
+    }
+}
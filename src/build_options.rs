@@ -0,0 +1,782 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed `OpenCL` program build options.
+//!
+//! [`BuildOptions`] builds the options string for `clBuildProgram` and
+//! `clCompileProgram` from typed preprocessor, language version, optimization,
+//! math and kernel argument options. The options can be validated against
+//! the `OpenCL` C versions supported by a device before building, so that
+//! an unsupported `-cl-std` or an option that is not available in the target
+//! `OpenCL` C version is reported by name instead of `CL_INVALID_BUILD_OPTIONS`.
+//!
+//! See: [Compiler Options](https://registry.khronos.org/OpenCL/specs/3.0-unified/html/OpenCL_API.html#compiler-options).
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{
+    CL_DEVICE_EXTENSIONS, CL_DEVICE_NUMERIC_VERSION, CL_DEVICE_OPENCL_C_ALL_VERSIONS,
+    CL_DEVICE_OPENCL_C_VERSION, CL_DEVICE_VERSION, CL_INVALID_BUILD_OPTIONS, cl_device_id, cl_int,
+    cl_program, cl_version,
+};
+
+use super::device::get_device_info;
+use super::error_codes::{ClError, error_text};
+use super::program::build_program;
+use opencl_sys::{make_version, version_major, version_minor};
+use std::ffi::CString;
+use std::fmt;
+use std::path::PathBuf;
+use std::ptr;
+use thiserror::Error;
+
+/// An `OpenCL` C language version for the `-cl-std` option.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClStd {
+    CL1_1,
+    CL1_2,
+    CL2_0,
+    CL3_0,
+}
+
+impl ClStd {
+    /// The `OpenCL` C version.
+    #[must_use]
+    pub const fn version(self) -> cl_version {
+        match self {
+            Self::CL1_1 => make_version(1, 1, 0),
+            Self::CL1_2 => make_version(1, 2, 0),
+            Self::CL2_0 => make_version(2, 0, 0),
+            Self::CL3_0 => make_version(3, 0, 0),
+        }
+    }
+
+    /// The value of the `-cl-std` option.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::CL1_1 => "CL1.1",
+            Self::CL1_2 => "CL1.2",
+            Self::CL2_0 => "CL2.0",
+            Self::CL3_0 => "CL3.0",
+        }
+    }
+
+    /// Get the `ClStd` for a `-cl-std` option value, e.g. "CL2.0".
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Self::CL1_1, Self::CL1_2, Self::CL2_0, Self::CL3_0]
+            .into_iter()
+            .find(|cl_std| cl_std.as_str() == name)
+    }
+}
+
+impl fmt::Display for ClStd {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Format an `OpenCL` C version as "major.minor".
+fn version_text(version: cl_version) -> String {
+    format!("{}.{}", version_major(version), version_minor(version))
+}
+
+/// The `OpenCL` C versions and the version that introduced them of the
+/// flag options of `clBuildProgram` and `clCompileProgram`.
+const FLAG_OPTIONS: &[(&str, cl_version)] = &[
+    ("-cl-single-precision-constant", make_version(1, 0, 0)),
+    ("-cl-denorms-are-zero", make_version(1, 0, 0)),
+    (
+        "-cl-fp32-correctly-rounded-divide-sqrt",
+        make_version(1, 2, 0),
+    ),
+    ("-cl-opt-disable", make_version(1, 0, 0)),
+    ("-cl-mad-enable", make_version(1, 0, 0)),
+    ("-cl-no-signed-zeros", make_version(1, 0, 0)),
+    ("-cl-unsafe-math-optimizations", make_version(1, 0, 0)),
+    ("-cl-finite-math-only", make_version(1, 0, 0)),
+    ("-cl-fast-relaxed-math", make_version(1, 0, 0)),
+    ("-cl-uniform-work-group-size", make_version(2, 0, 0)),
+    // Depends on the device rather than the OpenCL C version, see DEVICE_FLAG_OPTIONS.
+    ("-cl-no-subgroup-ifp", make_version(1, 0, 0)),
+    ("-cl-kernel-arg-info", make_version(1, 2, 0)),
+    ("-w", make_version(1, 0, 0)),
+    ("-Werror", make_version(1, 0, 0)),
+    ("-g", make_version(2, 0, 0)),
+];
+
+/// The flag options that depend on the device rather than the `OpenCL` C
+/// version, with the `OpenCL` version or the extension that provides them.
+const DEVICE_FLAG_OPTIONS: &[(&str, cl_version, &str)] = &[(
+    "-cl-no-subgroup-ifp",
+    make_version(2, 1, 0),
+    "cl_khr_subgroups",
+)];
+
+/// The reasons that [`BuildOptions`] may be rejected.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum BuildOptionsError {
+    #[error("unknown build option: {0}")]
+    UnknownOption(String),
+    #[error("invalid build option: {0}")]
+    InvalidOption(String),
+    #[error("-cl-std={0} is not supported by the device")]
+    UnsupportedClStd(ClStd),
+    #[error("{option} requires OpenCL C {}, the target is OpenCL C {}", version_text(*required), version_text(*target))]
+    OptionRequiresVersion {
+        option: String,
+        required: cl_version,
+        target: cl_version,
+    },
+    #[error("{option} requires OpenCL {} or {extension}, the device supports OpenCL {}", version_text(*required), version_text(*device_version))]
+    OptionRequiresDevice {
+        option: String,
+        required: cl_version,
+        extension: String,
+        device_version: cl_version,
+    },
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl BuildOptionsError {
+    /// The `OpenCL` error code that `clBuildProgram` would have returned for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::Cl(error) => *error,
+            _ => CL_INVALID_BUILD_OPTIONS,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for BuildOptionsError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<BuildOptionsError> for ClError {
+    fn from(error: BuildOptionsError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// A build option, in the order that it was added.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum BuildOption {
+    Define(String, Option<String>),
+    IncludeDir(PathBuf),
+    Flag(&'static str),
+    Vendor(String),
+}
+
+/// Quote an option argument if it contains whitespace, escaping backslashes
+/// and quotes.
+fn quote(argument: &str) -> String {
+    if argument.contains(char::is_whitespace) {
+        format!(
+            "\"{}\"",
+            argument.replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    } else {
+        argument.to_owned()
+    }
+}
+
+impl fmt::Display for BuildOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Define(name, None) => write!(f, "-D {name}"),
+            Self::Define(name, Some(value)) => write!(f, "-D {name}={}", quote(value)),
+            Self::IncludeDir(dir) => write!(f, "-I {}", quote(&dir.to_string_lossy())),
+            Self::Flag(flag) => write!(f, "{flag}"),
+            Self::Vendor(option) => write!(f, "{option}"),
+        }
+    }
+}
+
+/// A builder for `OpenCL` program build options, see the module documentation.
+///
+/// # Examples
+/// ```
+/// use cl3::build_options::{BuildOptions, ClStd};
+///
+/// let options = BuildOptions::new()
+///     .cl_std(ClStd::CL2_0)
+///     .define_value("TILE_SIZE", "16")
+///     .mad_enable()
+///     .kernel_arg_info();
+/// assert_eq!(
+///     "-cl-std=CL2.0 -D TILE_SIZE=16 -cl-mad-enable -cl-kernel-arg-info",
+///     options.to_string()
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BuildOptions {
+    cl_std: Option<ClStd>,
+    options: Vec<BuildOption>,
+}
+
+impl BuildOptions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the `OpenCL` C language version: `-cl-std`.
+    #[must_use]
+    pub const fn cl_std(mut self, cl_std: ClStd) -> Self {
+        self.cl_std = Some(cl_std);
+        self
+    }
+
+    /// Define a preprocessor macro: `-D name`.
+    #[must_use]
+    pub fn define(mut self, name: impl Into<String>) -> Self {
+        self.options.push(BuildOption::Define(name.into(), None));
+        self
+    }
+
+    /// Define a preprocessor macro with a value: `-D name=value`.
+    #[must_use]
+    pub fn define_value(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options
+            .push(BuildOption::Define(name.into(), Some(value.into())));
+        self
+    }
+
+    /// Add a directory to the header search path: `-I dir`.
+    #[must_use]
+    pub fn include_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.options.push(BuildOption::IncludeDir(dir.into()));
+        self
+    }
+
+    /// Add a flag option if it has not already been added.
+    fn flag(mut self, flag: &'static str) -> Self {
+        if !self.options.contains(&BuildOption::Flag(flag)) {
+            self.options.push(BuildOption::Flag(flag));
+        }
+        self
+    }
+
+    /// `-cl-opt-disable`
+    #[must_use]
+    pub fn opt_disable(self) -> Self {
+        self.flag("-cl-opt-disable")
+    }
+
+    /// `-cl-single-precision-constant`
+    #[must_use]
+    pub fn single_precision_constant(self) -> Self {
+        self.flag("-cl-single-precision-constant")
+    }
+
+    /// `-cl-denorms-are-zero`
+    #[must_use]
+    pub fn denorms_are_zero(self) -> Self {
+        self.flag("-cl-denorms-are-zero")
+    }
+
+    /// `-cl-fp32-correctly-rounded-divide-sqrt`, `OpenCL` C 1.2
+    #[must_use]
+    pub fn fp32_correctly_rounded_divide_sqrt(self) -> Self {
+        self.flag("-cl-fp32-correctly-rounded-divide-sqrt")
+    }
+
+    /// `-cl-mad-enable`
+    #[must_use]
+    pub fn mad_enable(self) -> Self {
+        self.flag("-cl-mad-enable")
+    }
+
+    /// `-cl-no-signed-zeros`
+    #[must_use]
+    pub fn no_signed_zeros(self) -> Self {
+        self.flag("-cl-no-signed-zeros")
+    }
+
+    /// `-cl-unsafe-math-optimizations`
+    #[must_use]
+    pub fn unsafe_math_optimizations(self) -> Self {
+        self.flag("-cl-unsafe-math-optimizations")
+    }
+
+    /// `-cl-finite-math-only`
+    #[must_use]
+    pub fn finite_math_only(self) -> Self {
+        self.flag("-cl-finite-math-only")
+    }
+
+    /// `-cl-fast-relaxed-math`
+    #[must_use]
+    pub fn fast_relaxed_math(self) -> Self {
+        self.flag("-cl-fast-relaxed-math")
+    }
+
+    /// `-cl-uniform-work-group-size`, `OpenCL` C 2.0
+    #[must_use]
+    pub fn uniform_work_group_size(self) -> Self {
+        self.flag("-cl-uniform-work-group-size")
+    }
+
+    /// `-cl-no-subgroup-ifp`, `OpenCL` 2.1 or `cl_khr_subgroups`
+    #[must_use]
+    pub fn no_subgroup_ifp(self) -> Self {
+        self.flag("-cl-no-subgroup-ifp")
+    }
+
+    /// `-cl-kernel-arg-info`, `OpenCL` C 1.2
+    #[must_use]
+    pub fn kernel_arg_info(self) -> Self {
+        self.flag("-cl-kernel-arg-info")
+    }
+
+    /// `-g`, `OpenCL` C 2.0
+    #[must_use]
+    pub fn debug_info(self) -> Self {
+        self.flag("-g")
+    }
+
+    /// `-w`
+    #[must_use]
+    pub fn inhibit_warnings(self) -> Self {
+        self.flag("-w")
+    }
+
+    /// `-Werror`
+    #[must_use]
+    pub fn warnings_as_errors(self) -> Self {
+        self.flag("-Werror")
+    }
+
+    /// Add a standard option from a string, e.g. from a configuration file.
+    ///
+    /// * `option` - a single option, e.g. "-cl-mad-enable", "-DSIZE=4" or "-cl-std=CL2.0".
+    ///
+    /// returns a Result containing the `BuildOptions` or a `BuildOptionsError`
+    /// if the option is not a standard option.
+    pub fn option(self, option: &str) -> Result<Self, BuildOptionsError> {
+        let option = option.trim();
+        if let Some(name) = option.strip_prefix("-cl-std=") {
+            let cl_std = ClStd::from_name(name)
+                .ok_or_else(|| BuildOptionsError::InvalidOption(option.to_owned()))?;
+            Ok(self.cl_std(cl_std))
+        } else if let Some(define) = option.strip_prefix("-D") {
+            let define = define.trim_start();
+            match define.split_once('=') {
+                _ if define.is_empty() => Err(BuildOptionsError::InvalidOption(option.to_owned())),
+                Some((name, value)) => Ok(self.define_value(name, value)),
+                None => Ok(self.define(define)),
+            }
+        } else if let Some(dir) = option.strip_prefix("-I") {
+            let dir = dir.trim_start();
+            if dir.is_empty() {
+                Err(BuildOptionsError::InvalidOption(option.to_owned()))
+            } else {
+                Ok(self.include_dir(dir))
+            }
+        } else {
+            FLAG_OPTIONS
+                .iter()
+                .find(|(flag, _)| *flag == option)
+                .map(|&(flag, _)| self.flag(flag))
+                .ok_or_else(|| BuildOptionsError::UnknownOption(option.to_owned()))
+        }
+    }
+
+    /// Add a vendor specific option, e.g. "-cl-nv-verbose".
+    /// Vendor options are not validated.
+    #[must_use]
+    pub fn vendor_option(mut self, option: impl Into<String>) -> Self {
+        self.options.push(BuildOption::Vendor(option.into()));
+        self
+    }
+
+    /// The `-cl-std` option, if set.
+    #[must_use]
+    pub const fn get_cl_std(&self) -> Option<ClStd> {
+        self.cl_std
+    }
+
+    /// Validate the options against the `OpenCL` C versions supported by a device.
+    ///
+    /// If `-cl-std` is not set, the target is the highest `OpenCL` C 1.x
+    /// version supported by the device, as for `clBuildProgram`.
+    ///
+    /// * `supported_versions` - the `OpenCL` C versions supported by the device,
+    ///   see [`supported_c_versions`].
+    ///
+    /// returns a Result containing the target `OpenCL` C version
+    /// or a `BuildOptionsError`.
+    pub fn validate_versions(
+        &self,
+        supported_versions: &[cl_version],
+    ) -> Result<cl_version, BuildOptionsError> {
+        let same_version = |a: cl_version, b: cl_version| {
+            version_major(a) == version_major(b) && version_minor(a) == version_minor(b)
+        };
+        let target = if let Some(cl_std) = self.cl_std {
+            if !supported_versions
+                .iter()
+                .any(|&version| same_version(version, cl_std.version()))
+            {
+                return Err(BuildOptionsError::UnsupportedClStd(cl_std));
+            }
+            cl_std.version()
+        } else {
+            supported_versions
+                .iter()
+                .copied()
+                .filter(|&version| 1 == version_major(version))
+                .max()
+                .unwrap_or(make_version(1, 0, 0))
+        };
+
+        for option in &self.options {
+            if let BuildOption::Flag(flag) = option
+                && let Some(&(_, required)) = FLAG_OPTIONS.iter().find(|(name, _)| name == flag)
+                && make_version(version_major(target), version_minor(target), 0) < required
+            {
+                return Err(BuildOptionsError::OptionRequiresVersion {
+                    option: (*flag).to_owned(),
+                    required,
+                    target,
+                });
+            }
+        }
+        Ok(target)
+    }
+
+    /// Validate the options that depend on the device rather than on the
+    /// `OpenCL` C version, e.g. `-cl-no-subgroup-ifp` requires `OpenCL` 2.1
+    /// or `cl_khr_subgroups`.
+    ///
+    /// * `device_version` - the `OpenCL` version of the device,
+    ///   see [`device_version`].
+    /// * `extensions` - the space separated extensions of the device.
+    ///
+    /// returns an empty Result or a `BuildOptionsError`.
+    pub fn validate_device_support(
+        &self,
+        device_version: cl_version,
+        extensions: &str,
+    ) -> Result<(), BuildOptionsError> {
+        let version = make_version(
+            version_major(device_version),
+            version_minor(device_version),
+            0,
+        );
+        for option in &self.options {
+            if let BuildOption::Flag(flag) = option
+                && let Some(&(_, required, extension)) =
+                    DEVICE_FLAG_OPTIONS.iter().find(|(name, _, _)| name == flag)
+                && version < required
+                && !extensions.split_whitespace().any(|name| name == extension)
+            {
+                return Err(BuildOptionsError::OptionRequiresDevice {
+                    option: (*flag).to_owned(),
+                    required,
+                    extension: extension.to_owned(),
+                    device_version,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate the options against the `OpenCL` C versions, `OpenCL` version
+    /// and extensions of a device, see [`Self::validate_versions`] and
+    /// [`Self::validate_device_support`].
+    ///
+    /// * `device` - the `OpenCL` device.
+    ///
+    /// returns a Result containing the target `OpenCL` C version
+    /// or a `BuildOptionsError`.
+    pub fn validate(&self, device: cl_device_id) -> Result<cl_version, BuildOptionsError> {
+        let target = self.validate_versions(&supported_c_versions(device)?)?;
+        let extensions: String = get_device_info(device, CL_DEVICE_EXTENSIONS)?.into();
+        self.validate_device_support(device_version(device)?, &extensions)?;
+        Ok(target)
+    }
+
+    /// The options as a null terminated string for `build_program` or `compile_program`.
+    ///
+    /// returns a Result containing the options
+    /// or a `BuildOptionsError` if an option contains a null character.
+    pub fn to_cstring(&self) -> Result<CString, BuildOptionsError> {
+        let options = self.to_string();
+        CString::new(options).map_err(|error| {
+            BuildOptionsError::InvalidOption(String::from_utf8_lossy(&error.into_vec()).into())
+        })
+    }
+
+    /// Validate the options against each of the devices and build a program.
+    /// Calls `clBuildProgram` with the options.
+    ///
+    /// * `program` - a valid `OpenCL` program.
+    /// * `devices` - a slice of devices that are in context.
+    ///
+    /// returns an empty Result or a `BuildOptionsError`.
+    pub fn build(
+        &self,
+        program: cl_program,
+        devices: &[cl_device_id],
+    ) -> Result<(), BuildOptionsError> {
+        for &device in devices {
+            self.validate(device)?;
+        }
+        let options = self.to_cstring()?;
+        Ok(build_program(
+            program,
+            devices,
+            &options,
+            None,
+            ptr::null_mut(),
+        )?)
+    }
+}
+
+impl fmt::Display for BuildOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut separator = if let Some(cl_std) = self.cl_std {
+            write!(f, "-cl-std={cl_std}")?;
+            " "
+        } else {
+            ""
+        };
+        for option in &self.options {
+            write!(f, "{separator}{option}")?;
+            separator = " ";
+        }
+        Ok(())
+    }
+}
+
+/// Get the `OpenCL` C versions supported by a device.
+///
+/// Calls clGetDeviceInfo with `CL_DEVICE_OPENCL_C_ALL_VERSIONS` (`CL_VERSION_3_0`).
+/// If that is not supported, returns the versions up to `CL_DEVICE_OPENCL_C_VERSION`.
+///
+/// * `device` - the `OpenCL` device.
+///
+/// returns a Result containing the supported `OpenCL` C versions
+/// or the error code from the `OpenCL` C API function.
+pub fn supported_c_versions(device: cl_device_id) -> Result<Vec<cl_version>, cl_int> {
+    if let Ok(versions) = get_device_info(device, CL_DEVICE_OPENCL_C_ALL_VERSIONS) {
+        let versions: Vec<cl_version> = versions
+            .to_vec_name_version()
+            .iter()
+            .map(|name_version| name_version.version)
+            .collect();
+        if !versions.is_empty() {
+            return Ok(versions);
+        }
+    }
+
+    let text: String = get_device_info(device, CL_DEVICE_OPENCL_C_VERSION)?.into();
+    let highest = parse_c_version(&text).ok_or(CL_INVALID_BUILD_OPTIONS)?;
+    let mut versions: Vec<cl_version> = [
+        make_version(1, 0, 0),
+        make_version(1, 1, 0),
+        make_version(1, 2, 0),
+        make_version(2, 0, 0),
+    ]
+    .into_iter()
+    .filter(|&version| version < highest)
+    .collect();
+    versions.push(highest);
+    Ok(versions)
+}
+
+/// Get the `OpenCL` version of a device.
+///
+/// Calls clGetDeviceInfo with `CL_DEVICE_NUMERIC_VERSION` (`CL_VERSION_3_0`).
+/// If that is not supported, parses `CL_DEVICE_VERSION`.
+///
+/// * `device` - the `OpenCL` device.
+///
+/// returns a Result containing the `OpenCL` version of the device
+/// or the error code from the `OpenCL` C API function.
+pub fn device_version(device: cl_device_id) -> Result<cl_version, cl_int> {
+    if let Ok(version) = get_device_info(device, CL_DEVICE_NUMERIC_VERSION) {
+        return Ok(version.to_uint());
+    }
+
+    let text: String = get_device_info(device, CL_DEVICE_VERSION)?.into();
+    parse_version(&text, "OpenCL ").ok_or(CL_INVALID_BUILD_OPTIONS)
+}
+
+/// Parse an `OpenCL` C version string, e.g. "`OpenCL` C 1.2 vendor-specific".
+fn parse_c_version(text: &str) -> Option<cl_version> {
+    parse_version(text, "OpenCL C ")
+}
+
+/// Parse a "major.minor" version after `prefix` in a version string.
+fn parse_version(text: &str, prefix: &str) -> Option<cl_version> {
+    let version = text.strip_prefix(prefix)?.split_whitespace().next()?;
+    let (major, minor) = version.split_once('.')?;
+    Some(make_version(major.parse().ok()?, minor.parse().ok()?, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_options_display() {
+        let options = BuildOptions::new()
+            .define("DEBUG")
+            .define_value("NAME", "a value")
+            .include_dir("/usr/include/my headers")
+            .fast_relaxed_math()
+            .fast_relaxed_math()
+            .vendor_option("-cl-nv-verbose");
+        assert_eq!(
+            r#"-D DEBUG -D NAME="a value" -I "/usr/include/my headers" -cl-fast-relaxed-math -cl-nv-verbose"#,
+            options.to_string()
+        );
+        assert!(options.to_cstring().is_ok());
+        assert_eq!("", BuildOptions::new().to_string());
+
+        let options = BuildOptions::new().include_dir(r"C:\My Headers\");
+        assert_eq!(r#"-I "C:\\My Headers\\""#, options.to_string());
+
+        let options = BuildOptions::new().define_value("BAD", "\0");
+        assert!(matches!(
+            options.to_cstring(),
+            Err(BuildOptionsError::InvalidOption(_))
+        ));
+    }
+
+    #[test]
+    fn test_build_options_option() {
+        let options = BuildOptions::new()
+            .option("-cl-std=CL3.0")
+            .unwrap()
+            .option("-DSIZE=4")
+            .unwrap()
+            .option("-I include")
+            .unwrap()
+            .option("-cl-mad-enable")
+            .unwrap();
+        assert_eq!(Some(ClStd::CL3_0), options.get_cl_std());
+        assert_eq!(
+            "-cl-std=CL3.0 -D SIZE=4 -I include -cl-mad-enable",
+            options.to_string()
+        );
+
+        assert_eq!(
+            Err(BuildOptionsError::UnknownOption(String::from(
+                "-cl-mad-enabled"
+            ))),
+            BuildOptions::new().option("-cl-mad-enabled")
+        );
+        assert_eq!(
+            Err(BuildOptionsError::InvalidOption(String::from(
+                "-cl-std=CL2.1"
+            ))),
+            BuildOptions::new().option("-cl-std=CL2.1")
+        );
+        assert!(BuildOptions::new().option("-D").is_err());
+    }
+
+    #[test]
+    fn test_build_options_validate() {
+        let v1_2 = [
+            make_version(1, 0, 0),
+            make_version(1, 1, 0),
+            make_version(1, 2, 0),
+        ];
+        let v3_0 = [
+            make_version(1, 0, 0),
+            make_version(1, 1, 0),
+            make_version(1, 2, 0),
+            make_version(3, 0, 0),
+        ];
+
+        let options = BuildOptions::new().kernel_arg_info();
+        assert_eq!(Ok(make_version(1, 2, 0)), options.validate_versions(&v1_2));
+
+        let options = BuildOptions::new().cl_std(ClStd::CL2_0);
+        assert_eq!(
+            Err(BuildOptionsError::UnsupportedClStd(ClStd::CL2_0)),
+            options.validate_versions(&v3_0)
+        );
+
+        // -g requires OpenCL C 2.0, the default target is OpenCL C 1.2
+        let options = BuildOptions::new().debug_info();
+        let error = options.validate_versions(&v3_0).unwrap_err();
+        assert_eq!(
+            "-g requires OpenCL C 2.0, the target is OpenCL C 1.2",
+            error.to_string()
+        );
+        assert_eq!(CL_INVALID_BUILD_OPTIONS, error.error_code());
+
+        let options = options.cl_std(ClStd::CL3_0);
+        assert_eq!(Ok(make_version(3, 0, 0)), options.validate_versions(&v3_0));
+
+        // -cl-no-subgroup-ifp depends on the device, not on -cl-std
+        let v2_0 = [
+            make_version(1, 2, 0),
+            make_version(2, 0, 0),
+            make_version(3, 0, 0),
+        ];
+        let options = BuildOptions::new().no_subgroup_ifp().cl_std(ClStd::CL2_0);
+        assert_eq!(Ok(make_version(2, 0, 0)), options.validate_versions(&v2_0));
+        assert_eq!(
+            Ok(()),
+            options.validate_device_support(make_version(2, 1, 0), "")
+        );
+        assert_eq!(
+            Ok(()),
+            options.validate_device_support(make_version(1, 2, 0), "cl_khr_fp64 cl_khr_subgroups")
+        );
+        let error = options
+            .validate_device_support(make_version(2, 0, 0), "cl_khr_fp64 cl_khr_subgroups_ext")
+            .unwrap_err();
+        assert_eq!(
+            "-cl-no-subgroup-ifp requires OpenCL 2.1 or cl_khr_subgroups, the device supports OpenCL 2.0",
+            error.to_string()
+        );
+        assert_eq!(CL_INVALID_BUILD_OPTIONS, error.error_code());
+        assert_eq!(
+            Ok(()),
+            BuildOptions::new()
+                .debug_info()
+                .validate_device_support(make_version(1, 2, 0), "")
+        );
+    }
+
+    #[test]
+    fn test_parse_c_version() {
+        assert_eq!(
+            Some(make_version(1, 2, 0)),
+            parse_c_version("OpenCL C 1.2 ")
+        );
+        assert_eq!(
+            Some(make_version(3, 0, 0)),
+            parse_c_version("OpenCL C 3.0 (Build 0)")
+        );
+        assert_eq!(None, parse_c_version("OpenCL 3.0"));
+        assert_eq!(
+            Some(make_version(2, 1, 0)),
+            parse_version("OpenCL 2.1 AMD-APP (3614.0)", "OpenCL ")
+        );
+    }
+}
@@ -0,0 +1,906 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A recorder for `cl_khr_command_buffer` command-buffers.
+//!
+//! A [`CommandBuffer`] owns a `cl_command_buffer_khr` and records commands
+//! into it. Each recorded command returns a typed [`SyncPoint`] that later
+//! commands may wait for, and the recorder keeps the dependency graph of
+//! the commands in a [`CommandGraph`].
+//!
+//! Sync points are checked against the command-buffer that created them,
+//! identified by a process-wide id, so a sync point from another
+//! command-buffer is rejected before calling `OpenCL`. Once [`finalize`](CommandBuffer::finalize)d, a command-buffer
+//! can be enqueued repeatedly with slice event wait lists.
+//!
+//! Note: the memory objects and kernels recorded in a command-buffer must
+//! remain valid while the command-buffer may be enqueued.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub use opencl_sys::{
+    CL_COMMAND_BUFFER_FLAGS_KHR, CL_COMMAND_BUFFER_MUTABLE_KHR,
+    CL_COMMAND_BUFFER_SIMULTANEOUS_USE_KHR, CL_INVALID_OPERATION,
+    CL_INVALID_SYNC_POINT_WAIT_LIST_KHR, cl_command_buffer_flags_khr, cl_command_buffer_khr,
+    cl_command_buffer_properties_khr, cl_command_properties_khr, cl_command_queue, cl_event,
+    cl_int, cl_kernel, cl_mem, cl_mutable_command_khr, cl_sync_point_khr, cl_uint,
+};
+
+use super::enqueue::enqueue_command_buffer_khr;
+use super::error_codes::{ClError, error_text};
+use super::ext::{
+    command_barrier_with_wait_list_khr, command_copy_buffer_khr, command_copy_buffer_rect_khr,
+    command_copy_buffer_to_image_khr, command_copy_image_khr, command_copy_image_to_buffer_khr,
+    command_fill_buffer_khr, command_nd_range_kernel_khr, create_command_buffer_khr,
+    finalize_command_buffer_khr, release_command_buffer_khr,
+};
+use super::nd_range::NDRange;
+use super::types::Pod;
+use libc::{c_void, size_t};
+use std::mem;
+use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use thiserror::Error;
+
+/// The id of the next [`CommandGraph`], unique within the process.
+static NEXT_GRAPH_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The reasons that recording or enqueueing a [`CommandBuffer`] may fail.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum CommandBufferError {
+    #[error("sync point {0} was not recorded in this command-buffer")]
+    ForeignSyncPoint(cl_sync_point_khr),
+    #[error("mutable command was not recorded in this command-buffer")]
+    ForeignMutableCommand,
+    #[error("the command-buffer is finalized, commands can't be recorded")]
+    Finalized,
+    #[error("the command-buffer is not finalized, it can't be enqueued")]
+    NotFinalized,
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl CommandBufferError {
+    /// The `OpenCL` error code that the `cl_khr_command_buffer` functions
+    /// would have returned for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::ForeignSyncPoint(_) => CL_INVALID_SYNC_POINT_WAIT_LIST_KHR,
+            Self::ForeignMutableCommand | Self::Finalized | Self::NotFinalized => {
+                CL_INVALID_OPERATION
+            }
+            Self::Cl(error) => *error,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for CommandBufferError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<CommandBufferError> for ClError {
+    fn from(error: CommandBufferError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// The sync point of a command recorded in a [`CommandBuffer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SyncPoint {
+    owner: u64,
+    value: cl_sync_point_khr,
+}
+
+impl SyncPoint {
+    /// The `OpenCL` sync point value.
+    #[must_use]
+    pub const fn value(&self) -> cl_sync_point_khr {
+        self.value
+    }
+}
+
+/// The handle of a mutable command recorded in a [`CommandBuffer`],
+/// see `cl_khr_command_buffer_mutable_dispatch`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MutableCommand {
+    owner: u64,
+    handle: cl_mutable_command_khr,
+    sync_point: SyncPoint,
+}
+
+impl MutableCommand {
+    /// The `OpenCL` mutable command handle.
+    #[must_use]
+    pub const fn handle(&self) -> cl_mutable_command_khr {
+        self.handle
+    }
+
+    /// The sync point of the command.
+    #[must_use]
+    pub const fn sync_point(&self) -> SyncPoint {
+        self.sync_point
+    }
+}
+
+#[cfg(test)]
+impl MutableCommand {
+    /// A mutable command that was not recorded in a command-buffer, for tests.
+    pub(crate) const fn unrecorded(handle: cl_mutable_command_khr) -> Self {
+        Self {
+            owner: 0,
+            handle,
+            sync_point: SyncPoint { owner: 0, value: 0 },
+        }
+    }
+}
+
+/// The kinds of command that can be recorded in a [`CommandBuffer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandKind {
+    Barrier,
+    CopyBuffer,
+    CopyBufferRect,
+    CopyBufferToImage,
+    CopyImage,
+    CopyImageToBuffer,
+    FillBuffer,
+    NDRangeKernel,
+}
+
+/// A command recorded in a [`CommandBuffer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedCommand {
+    pub kind: CommandKind,
+    pub sync_point: SyncPoint,
+    /// The sync points that the command waits for.
+    pub dependencies: Vec<SyncPoint>,
+    /// Whether the command has a mutable command handle.
+    pub mutable: bool,
+}
+
+/// The dependency graph of the commands recorded in a [`CommandBuffer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandGraph {
+    owner: u64,
+    commands: Vec<RecordedCommand>,
+}
+
+impl CommandGraph {
+    fn new() -> Self {
+        Self {
+            owner: NEXT_GRAPH_ID.fetch_add(1, Ordering::Relaxed),
+            commands: Vec::new(),
+        }
+    }
+
+    /// The recorded commands in the order that they were recorded.
+    #[must_use]
+    pub fn commands(&self) -> &[RecordedCommand] {
+        &self.commands
+    }
+
+    /// Whether a sync point was recorded in this graph.
+    #[must_use]
+    pub fn contains(&self, sync_point: SyncPoint) -> bool {
+        self.command(sync_point).is_some()
+    }
+
+    fn command(&self, sync_point: SyncPoint) -> Option<&RecordedCommand> {
+        if self.owner == sync_point.owner {
+            self.commands
+                .iter()
+                .find(|command| command.sync_point == sync_point)
+        } else {
+            None
+        }
+    }
+
+    /// The sync points that a command waits for,
+    /// or None if the sync point was not recorded in this graph.
+    #[must_use]
+    pub fn dependencies(&self, sync_point: SyncPoint) -> Option<&[SyncPoint]> {
+        self.command(sync_point)
+            .map(|command| command.dependencies.as_slice())
+    }
+
+    /// The sync points of the commands that wait for a command.
+    #[must_use]
+    pub fn dependents(&self, sync_point: SyncPoint) -> Vec<SyncPoint> {
+        self.commands
+            .iter()
+            .filter(|command| command.dependencies.contains(&sync_point))
+            .map(|command| command.sync_point)
+            .collect()
+    }
+
+    /// The sync points of the commands that no other command waits for.
+    #[must_use]
+    pub fn leaves(&self) -> Vec<SyncPoint> {
+        self.commands
+            .iter()
+            .map(|command| command.sync_point)
+            .filter(|sync_point| {
+                !self
+                    .commands
+                    .iter()
+                    .any(|command| command.dependencies.contains(sync_point))
+            })
+            .collect()
+    }
+
+    /// Check that the sync points of a wait list were recorded in this graph.
+    ///
+    /// returns a Result containing the `OpenCL` sync point values
+    /// or `CommandBufferError::ForeignSyncPoint`.
+    fn wait_list(
+        &self,
+        wait_list: &[SyncPoint],
+    ) -> Result<Vec<cl_sync_point_khr>, CommandBufferError> {
+        wait_list
+            .iter()
+            .map(|sync_point| {
+                if self.contains(*sync_point) {
+                    Ok(sync_point.value)
+                } else {
+                    Err(CommandBufferError::ForeignSyncPoint(sync_point.value))
+                }
+            })
+            .collect()
+    }
+
+    /// Add a command to the graph.
+    fn push(
+        &mut self,
+        kind: CommandKind,
+        value: cl_sync_point_khr,
+        wait_list: &[SyncPoint],
+        mutable: bool,
+    ) -> SyncPoint {
+        let sync_point = SyncPoint {
+            owner: self.owner,
+            value,
+        };
+        self.commands.push(RecordedCommand {
+            kind,
+            sync_point,
+            dependencies: wait_list.to_vec(),
+            mutable,
+        });
+        sync_point
+    }
+}
+
+/// An owned `cl_khr_command_buffer` command-buffer and the dependency graph
+/// of its recorded commands, see the module documentation.
+/// The command-buffer is released when it is dropped.
+#[derive(Debug)]
+pub struct CommandBuffer {
+    buffer: cl_command_buffer_khr,
+    graph: CommandGraph,
+    finalized: bool,
+}
+
+impl CommandBuffer {
+    /// Create a command-buffer for a command-queue.
+    /// Calls `clCreateCommandBufferKHR`.
+    ///
+    /// * `command_queue` - the command-queue that the commands are recorded for.
+    /// * `flags` - the `CL_COMMAND_BUFFER_FLAGS_KHR` property, e.g.
+    ///   `CL_COMMAND_BUFFER_SIMULTANEOUS_USE_KHR`, or 0.
+    ///
+    /// returns a Result containing the new `CommandBuffer`
+    /// or the error code from the `OpenCL` C API function.
+    pub fn new(
+        command_queue: cl_command_queue,
+        flags: cl_command_buffer_flags_khr,
+    ) -> Result<Self, cl_int> {
+        let properties: [cl_command_buffer_properties_khr; 3] =
+            [CL_COMMAND_BUFFER_FLAGS_KHR, flags, 0];
+        let properties_ptr = if 0 == flags {
+            ptr::null()
+        } else {
+            properties.as_ptr()
+        };
+        let buffer = create_command_buffer_khr(&[command_queue], properties_ptr)?;
+        Ok(Self {
+            buffer,
+            graph: CommandGraph::new(),
+            finalized: false,
+        })
+    }
+
+    /// The underlying `OpenCL` command-buffer.
+    #[must_use]
+    pub const fn get(&self) -> cl_command_buffer_khr {
+        self.buffer
+    }
+
+    /// The dependency graph of the recorded commands.
+    #[must_use]
+    pub const fn graph(&self) -> &CommandGraph {
+        &self.graph
+    }
+
+    /// Whether the command-buffer has been finalized.
+    #[must_use]
+    pub const fn is_finalized(&self) -> bool {
+        self.finalized
+    }
+
+    /// Record a command: check the wait list, call `record` with the sync
+    /// point values and add the command to the graph.
+    fn record<F>(
+        &mut self,
+        kind: CommandKind,
+        wait_list: &[SyncPoint],
+        mutable: bool,
+        record: F,
+    ) -> Result<(SyncPoint, cl_mutable_command_khr), CommandBufferError>
+    where
+        F: FnOnce(
+            cl_command_buffer_khr,
+            &[cl_sync_point_khr],
+            *mut cl_sync_point_khr,
+            *mut cl_mutable_command_khr,
+        ) -> Result<(), cl_int>,
+    {
+        if self.finalized {
+            return Err(CommandBufferError::Finalized);
+        }
+        let sync_points = self.graph.wait_list(wait_list)?;
+        let mut value: cl_sync_point_khr = 0;
+        let mut handle: cl_mutable_command_khr = ptr::null_mut();
+        let handle_ptr = if mutable {
+            &raw mut handle
+        } else {
+            ptr::null_mut()
+        };
+        record(self.buffer, &sync_points, &raw mut value, handle_ptr)?;
+        let sync_point = self.graph.push(kind, value, wait_list, mutable);
+        Ok((sync_point, handle))
+    }
+
+    /// Record a barrier that waits for sync points, or all previously
+    /// recorded commands if `wait_list` is empty.
+    /// Calls `clCommandBarrierWithWaitListKHR`.
+    ///
+    /// returns a Result containing the sync point of the barrier
+    /// or a `CommandBufferError`.
+    pub fn barrier(&mut self, wait_list: &[SyncPoint]) -> Result<SyncPoint, CommandBufferError> {
+        self.record(
+            CommandKind::Barrier,
+            wait_list,
+            false,
+            |buffer, sync_points, sync_point, handle| unsafe {
+                command_barrier_with_wait_list_khr(
+                    buffer,
+                    ptr::null_mut(),
+                    ptr::null(),
+                    sync_points,
+                    sync_point,
+                    handle,
+                )
+            },
+        )
+        .map(|(sync_point, _)| sync_point)
+    }
+
+    /// Record a copy between buffer objects.
+    /// Calls `clCommandCopyBufferKHR`.
+    ///
+    /// returns a Result containing the sync point of the command
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// `src_buffer` and `dst_buffer` must be valid `OpenCL` memory objects
+    /// that are not released while the command-buffer may be enqueued.
+    pub unsafe fn copy_buffer(
+        &mut self,
+        src_buffer: cl_mem,
+        dst_buffer: cl_mem,
+        src_offset: size_t,
+        dst_offset: size_t,
+        size: size_t,
+        wait_list: &[SyncPoint],
+    ) -> Result<SyncPoint, CommandBufferError> {
+        self.record(
+            CommandKind::CopyBuffer,
+            wait_list,
+            false,
+            |buffer, sync_points, sync_point, handle| unsafe {
+                command_copy_buffer_khr(
+                    buffer,
+                    ptr::null_mut(),
+                    ptr::null(),
+                    src_buffer,
+                    dst_buffer,
+                    src_offset,
+                    dst_offset,
+                    size,
+                    sync_points,
+                    sync_point,
+                    handle,
+                )
+            },
+        )
+        .map(|(sync_point, _)| sync_point)
+    }
+
+    /// Record a copy of a 2D or 3D rectangular region between buffer objects.
+    /// Calls `clCommandCopyBufferRectKHR`.
+    ///
+    /// returns a Result containing the sync point of the command
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// `src_buffer` and `dst_buffer` must be valid `OpenCL` memory objects
+    /// that are not released while the command-buffer may be enqueued.
+    #[allow(clippy::too_many_arguments)]
+    pub unsafe fn copy_buffer_rect(
+        &mut self,
+        src_buffer: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: &[size_t; 3],
+        dst_origin: &[size_t; 3],
+        region: &[size_t; 3],
+        src_pitches: (size_t, size_t),
+        dst_pitches: (size_t, size_t),
+        wait_list: &[SyncPoint],
+    ) -> Result<SyncPoint, CommandBufferError> {
+        self.record(
+            CommandKind::CopyBufferRect,
+            wait_list,
+            false,
+            |buffer, sync_points, sync_point, handle| unsafe {
+                command_copy_buffer_rect_khr(
+                    buffer,
+                    ptr::null_mut(),
+                    ptr::null(),
+                    src_buffer,
+                    dst_buffer,
+                    src_origin.as_ptr(),
+                    dst_origin.as_ptr(),
+                    region.as_ptr(),
+                    src_pitches.0,
+                    src_pitches.1,
+                    dst_pitches.0,
+                    dst_pitches.1,
+                    sync_points,
+                    sync_point,
+                    handle,
+                )
+            },
+        )
+        .map(|(sync_point, _)| sync_point)
+    }
+
+    /// Record a copy from a buffer object to an image object.
+    /// Calls `clCommandCopyBufferToImageKHR`.
+    ///
+    /// returns a Result containing the sync point of the command
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// `src_buffer` and `dst_image` must be valid `OpenCL` memory objects
+    /// that are not released while the command-buffer may be enqueued.
+    pub unsafe fn copy_buffer_to_image(
+        &mut self,
+        src_buffer: cl_mem,
+        dst_image: cl_mem,
+        src_offset: size_t,
+        dst_origin: &[size_t; 3],
+        region: &[size_t; 3],
+        wait_list: &[SyncPoint],
+    ) -> Result<SyncPoint, CommandBufferError> {
+        self.record(
+            CommandKind::CopyBufferToImage,
+            wait_list,
+            false,
+            |buffer, sync_points, sync_point, handle| unsafe {
+                command_copy_buffer_to_image_khr(
+                    buffer,
+                    ptr::null_mut(),
+                    ptr::null(),
+                    src_buffer,
+                    dst_image,
+                    src_offset,
+                    dst_origin.as_ptr(),
+                    region.as_ptr(),
+                    sync_points,
+                    sync_point,
+                    handle,
+                )
+            },
+        )
+        .map(|(sync_point, _)| sync_point)
+    }
+
+    /// Record a copy between image objects.
+    /// Calls `clCommandCopyImageKHR`.
+    ///
+    /// returns a Result containing the sync point of the command
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// `src_image` and `dst_image` must be valid `OpenCL` memory objects
+    /// that are not released while the command-buffer may be enqueued.
+    pub unsafe fn copy_image(
+        &mut self,
+        src_image: cl_mem,
+        dst_image: cl_mem,
+        src_origin: &[size_t; 3],
+        dst_origin: &[size_t; 3],
+        region: &[size_t; 3],
+        wait_list: &[SyncPoint],
+    ) -> Result<SyncPoint, CommandBufferError> {
+        self.record(
+            CommandKind::CopyImage,
+            wait_list,
+            false,
+            |buffer, sync_points, sync_point, handle| unsafe {
+                command_copy_image_khr(
+                    buffer,
+                    ptr::null_mut(),
+                    ptr::null(),
+                    src_image,
+                    dst_image,
+                    src_origin.as_ptr(),
+                    dst_origin.as_ptr(),
+                    region.as_ptr(),
+                    sync_points,
+                    sync_point,
+                    handle,
+                )
+            },
+        )
+        .map(|(sync_point, _)| sync_point)
+    }
+
+    /// Record a copy from an image object to a buffer object.
+    /// Calls `clCommandCopyImageToBufferKHR`.
+    ///
+    /// returns a Result containing the sync point of the command
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// `src_image` and `dst_buffer` must be valid `OpenCL` memory objects
+    /// that are not released while the command-buffer may be enqueued.
+    pub unsafe fn copy_image_to_buffer(
+        &mut self,
+        src_image: cl_mem,
+        dst_buffer: cl_mem,
+        src_origin: &[size_t; 3],
+        region: &[size_t; 3],
+        dst_offset: size_t,
+        wait_list: &[SyncPoint],
+    ) -> Result<SyncPoint, CommandBufferError> {
+        self.record(
+            CommandKind::CopyImageToBuffer,
+            wait_list,
+            false,
+            |buffer, sync_points, sync_point, handle| unsafe {
+                command_copy_image_to_buffer_khr(
+                    buffer,
+                    ptr::null_mut(),
+                    ptr::null(),
+                    src_image,
+                    dst_buffer,
+                    src_origin.as_ptr(),
+                    region.as_ptr(),
+                    dst_offset,
+                    sync_points,
+                    sync_point,
+                    handle,
+                )
+            },
+        )
+        .map(|(sync_point, _)| sync_point)
+    }
+
+    /// Record a fill of a buffer object with a pattern.
+    /// Calls `clCommandFillBufferKHR`, the pattern is copied when the command is recorded.
+    ///
+    /// * `pattern` - the pattern to fill the buffer with, a [`Pod`] type.
+    /// * `size` - the size in bytes to fill, a multiple of the pattern size.
+    ///
+    /// returns a Result containing the sync point of the command
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must be a valid `OpenCL` memory object
+    /// that is not released while the command-buffer may be enqueued.
+    pub unsafe fn fill_buffer<T: Pod>(
+        &mut self,
+        buffer: cl_mem,
+        pattern: &T,
+        offset: size_t,
+        size: size_t,
+        wait_list: &[SyncPoint],
+    ) -> Result<SyncPoint, CommandBufferError> {
+        self.record(
+            CommandKind::FillBuffer,
+            wait_list,
+            false,
+            |command_buffer, sync_points, sync_point, handle| unsafe {
+                command_fill_buffer_khr(
+                    command_buffer,
+                    ptr::null_mut(),
+                    ptr::null(),
+                    buffer,
+                    ptr::from_ref(pattern).cast::<c_void>(),
+                    mem::size_of::<T>(),
+                    offset,
+                    size,
+                    sync_points,
+                    sync_point,
+                    handle,
+                )
+            },
+        )
+        .map(|(sync_point, _)| sync_point)
+    }
+
+    /// Record a kernel execution, the kernel arguments are captured when
+    /// the command is recorded.
+    /// Calls `clCommandNDRangeKernelKHR`.
+    ///
+    /// returns a Result containing the sync point of the command
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// The kernel arguments must be set and valid for the kernel.
+    pub unsafe fn nd_range_kernel(
+        &mut self,
+        kernel: cl_kernel,
+        range: &NDRange,
+        wait_list: &[SyncPoint],
+    ) -> Result<SyncPoint, CommandBufferError> {
+        unsafe { self.record_nd_range_kernel(kernel, range, &[], wait_list, false) }
+            .map(|(sync_point, _)| sync_point)
+    }
+
+    /// Record a kernel execution that can be updated after the command-buffer
+    /// is finalized, see `cl_khr_command_buffer_mutable_dispatch`.
+    /// Calls `clCommandNDRangeKernelKHR`.
+    ///
+    /// * `properties` - the command properties, e.g.
+    ///   `CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR` and its fields, without
+    ///   the terminating zero, or empty for the default properties.
+    ///
+    /// returns a Result containing the mutable command
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// The kernel arguments must be set and valid for the kernel.
+    pub unsafe fn nd_range_kernel_mutable(
+        &mut self,
+        kernel: cl_kernel,
+        range: &NDRange,
+        properties: &[cl_command_properties_khr],
+        wait_list: &[SyncPoint],
+    ) -> Result<MutableCommand, CommandBufferError> {
+        let (sync_point, handle) =
+            unsafe { self.record_nd_range_kernel(kernel, range, properties, wait_list, true) }?;
+        Ok(MutableCommand {
+            owner: self.graph.owner,
+            handle,
+            sync_point,
+        })
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    unsafe fn record_nd_range_kernel(
+        &mut self,
+        kernel: cl_kernel,
+        range: &NDRange,
+        properties: &[cl_command_properties_khr],
+        wait_list: &[SyncPoint],
+        mutable: bool,
+    ) -> Result<(SyncPoint, cl_mutable_command_khr), CommandBufferError> {
+        let properties: Vec<cl_command_properties_khr> = properties
+            .iter()
+            .copied()
+            .chain(std::iter::once(0))
+            .collect();
+        let properties_ptr = if 1 == properties.len() {
+            ptr::null()
+        } else {
+            properties.as_ptr()
+        };
+        self.record(
+            CommandKind::NDRangeKernel,
+            wait_list,
+            mutable,
+            |buffer, sync_points, sync_point, handle| unsafe {
+                command_nd_range_kernel_khr(
+                    buffer,
+                    ptr::null_mut(),
+                    properties_ptr,
+                    kernel,
+                    range.work_dim() as cl_uint,
+                    range.offset().map_or(ptr::null(), <[size_t]>::as_ptr),
+                    range.global().as_ptr(),
+                    range.local().map_or(ptr::null(), <[size_t]>::as_ptr),
+                    sync_points,
+                    sync_point,
+                    handle,
+                )
+            },
+        )
+    }
+
+    /// Check that a mutable command was recorded in this command-buffer.
+    ///
+    /// returns a Result containing the mutable command handle
+    /// or `CommandBufferError::ForeignMutableCommand`.
+    pub const fn mutable_handle(
+        &self,
+        command: &MutableCommand,
+    ) -> Result<cl_mutable_command_khr, CommandBufferError> {
+        if self.graph.owner == command.owner {
+            Ok(command.handle)
+        } else {
+            Err(CommandBufferError::ForeignMutableCommand)
+        }
+    }
+
+    /// Finalize the command-buffer so that it can be enqueued, no more
+    /// commands can be recorded after it is finalized.
+    /// Calls `clFinalizeCommandBufferKHR`.
+    ///
+    /// returns an empty Result or a `CommandBufferError`.
+    pub fn finalize(&mut self) -> Result<(), CommandBufferError> {
+        if self.finalized {
+            return Err(CommandBufferError::Finalized);
+        }
+        finalize_command_buffer_khr(self.buffer)?;
+        self.finalized = true;
+        Ok(())
+    }
+
+    /// Enqueue the command-buffer on the command-queue that it was recorded for.
+    /// Calls `clEnqueueCommandBufferKHR`.
+    ///
+    /// * `event_wait_list` - the events that must complete before the command-buffer.
+    /// * `return_event` - whether to create an event for the command-buffer.
+    ///
+    /// returns a Result containing the new event if `return_event` is true,
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// The events in `event_wait_list` must be valid `OpenCL` objects and the
+    /// memory objects and kernels recorded in the command-buffer must not
+    /// have been released.
+    pub unsafe fn enqueue(
+        &self,
+        event_wait_list: &[cl_event],
+        return_event: bool,
+    ) -> Result<Option<cl_event>, CommandBufferError> {
+        unsafe { self.enqueue_on(&mut [], event_wait_list, return_event) }
+    }
+
+    /// Enqueue the command-buffer on replacement command-queues.
+    /// Calls `clEnqueueCommandBufferKHR`.
+    ///
+    /// * `queues` - the command-queues to replace the recorded command-queue,
+    ///   or empty to use the recorded command-queue.
+    ///
+    /// returns a Result containing the new event if `return_event` is true,
+    /// or a `CommandBufferError`.
+    ///
+    /// # Safety
+    ///
+    /// `queues` and the events in `event_wait_list` must be valid `OpenCL`
+    /// objects, see [`enqueue`](Self::enqueue).
+    pub unsafe fn enqueue_on(
+        &self,
+        queues: &mut [cl_command_queue],
+        event_wait_list: &[cl_event],
+        return_event: bool,
+    ) -> Result<Option<cl_event>, CommandBufferError> {
+        if !self.finalized {
+            return Err(CommandBufferError::NotFinalized);
+        }
+        Ok(unsafe {
+            enqueue_command_buffer_khr(queues, self.buffer, event_wait_list, return_event)?
+        })
+    }
+}
+
+impl Drop for CommandBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = release_command_buffer_khr(self.buffer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_graph() {
+        let mut graph = CommandGraph::new();
+        let fill = graph.push(CommandKind::FillBuffer, 1, &[], false);
+        let copy = graph.push(CommandKind::CopyBuffer, 2, &[], false);
+        let kernel = graph.push(CommandKind::NDRangeKernel, 3, &[fill, copy], true);
+        let read = graph.push(CommandKind::CopyBuffer, 4, &[kernel], false);
+
+        assert_eq!(4, graph.commands().len());
+        assert_eq!(Some([fill, copy].as_slice()), graph.dependencies(kernel));
+        assert_eq!(vec![kernel], graph.dependents(fill));
+        assert_eq!(vec![read], graph.leaves());
+        assert_eq!(Ok(vec![1, 3]), graph.wait_list(&[fill, kernel]));
+
+        // A sync point from another command-buffer is rejected.
+        let mut other = CommandGraph::new();
+        let foreign = other.push(CommandKind::Barrier, 1, &[], false);
+        assert!(!graph.contains(foreign));
+        assert_eq!(None, graph.dependencies(foreign));
+        assert_eq!(
+            Err(CommandBufferError::ForeignSyncPoint(1)),
+            graph.wait_list(&[fill, foreign])
+        );
+        assert_eq!(
+            CL_INVALID_SYNC_POINT_WAIT_LIST_KHR,
+            CommandBufferError::ForeignSyncPoint(1).error_code()
+        );
+    }
+
+    #[test]
+    fn test_command_buffer_rejects_foreign_sync_point() {
+        // The wait list is checked before calling OpenCL, so a null
+        // command-buffer is not used.
+        let mut command_buffer = CommandBuffer {
+            buffer: ptr::null_mut(),
+            graph: CommandGraph::new(),
+            finalized: false,
+        };
+        let mut other = CommandGraph::new();
+        let foreign = other.push(CommandKind::Barrier, 1, &[], false);
+        assert_ne!(command_buffer.graph.owner, other.owner);
+        assert_eq!(
+            Err(CommandBufferError::ForeignSyncPoint(1)),
+            command_buffer.barrier(&[foreign])
+        );
+        assert_eq!(Err(CommandBufferError::ForeignSyncPoint(1)), unsafe {
+            command_buffer.copy_buffer(ptr::null_mut(), ptr::null_mut(), 0, 0, 4, &[foreign])
+        });
+        assert!(command_buffer.graph().commands().is_empty());
+
+        command_buffer.finalized = true;
+        assert_eq!(
+            Err(CommandBufferError::Finalized),
+            command_buffer.barrier(&[])
+        );
+        // Don't release the null command-buffer.
+        mem::forget(command_buffer);
+    }
+}
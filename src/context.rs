@@ -27,8 +27,11 @@ pub use opencl_sys::{
 use super::info_type::InfoType;
 use super::{api_info_size, api_info_value, api_info_vector};
 use libc::{c_char, c_void, intptr_t, size_t};
+use std::ffi::CStr;
 use std::mem;
+use std::panic::{self, AssertUnwindSafe};
 use std::ptr;
+use std::slice;
 
 /// Create an `OpenCL` context.
 /// Calls clCreateContext to create an `OpenCL` context.
@@ -68,6 +71,87 @@ pub fn create_context(
     }
 }
 
+/// A boxed closure called by [`create_context_with_closure`].
+type ContextNotify = Box<dyn Fn(&str, &[u8]) + Send + Sync>;
+
+/// The context notification trampoline: `user_data` is a `Box<ContextNotify>`
+/// which is borrowed, since the notification may be called many times.
+extern "C" fn context_notify_trampoline(
+    errinfo: *const c_char,
+    private_info: *const c_void,
+    cb: size_t,
+    user_data: *mut c_void,
+) {
+    // Catch any panic so that it can't unwind across the FFI boundary.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        let notify = unsafe { &*user_data.cast::<ContextNotify>().cast_const() };
+        let errinfo = if errinfo.is_null() {
+            String::new()
+        } else {
+            unsafe { CStr::from_ptr(errinfo) }
+                .to_string_lossy()
+                .into_owned()
+        };
+        let private_info: &[u8] = if private_info.is_null() || 0 == cb {
+            &[]
+        } else {
+            unsafe { slice::from_raw_parts(private_info.cast::<u8>(), cb) }
+        };
+        notify(&errinfo, private_info);
+    }));
+}
+
+/// The context destructor trampoline that drops a `Box<ContextNotify>`.
+#[cfg(any(feature = "CL_VERSION_3_0", feature = "dynamic"))]
+extern "C" fn drop_context_notify(_context: cl_context, user_data: *mut c_void) {
+    let _ =
+        panic::catch_unwind(|| drop(unsafe { Box::from_raw(user_data.cast::<ContextNotify>()) }));
+}
+
+/// Create an `OpenCL` context with a closure to report errors in the context.
+/// Calls clCreateContext with a notification routine that calls the closure.
+///
+/// The closure may be called many times, from any thread, while the context exists.
+/// A panic in the closure is caught, it does not unwind into the `OpenCL` library.
+/// The closure is dropped by a context destructor callback when the context
+/// is destroyed. If the platform does not support `clSetContextDestructorCallback`
+/// (i.e. before `CL_VERSION_3_0`), the closure is leaked.
+///
+/// * `devices` - a slice of unique devices for an `OpenCL` platform.
+/// * `properties` - a null terminated list of `cl_context_properties`, see
+///   [Context Properties](https://www.khronos.org/registry/OpenCL/specs/3.0-unified/html/OpenCL_API.html#context-properties-table).
+/// * `notify` - the closure, called with the error information string and
+///   the implementation specific private information.
+///
+/// returns a Result containing the new `OpenCL` context
+/// or the error code from the `OpenCL` C API function.
+#[inline]
+pub fn create_context_with_closure<F>(
+    devices: &[cl_device_id],
+    properties: *const cl_context_properties,
+    notify: F,
+) -> Result<cl_context, cl_int>
+where
+    F: Fn(&str, &[u8]) + Send + Sync + 'static,
+{
+    let user_data = Box::into_raw(Box::new(Box::new(notify) as ContextNotify));
+    let context = create_context(
+        devices,
+        properties,
+        Some(context_notify_trampoline),
+        user_data.cast::<c_void>(),
+    )
+    .inspect_err(|_| drop(unsafe { Box::from_raw(user_data) }))?;
+
+    #[cfg(any(feature = "CL_VERSION_3_0", feature = "dynamic"))]
+    let _ = set_context_destructor_callback(
+        context,
+        Some(drop_context_notify),
+        user_data.cast::<c_void>(),
+    );
+    Ok(context)
+}
+
 /// Create an `OpenCL` context from a specific device type.
 /// Calls `clCreateContextFromType` to create an `OpenCL` context.
 ///
@@ -214,6 +298,48 @@ pub fn set_context_destructor_callback(
         Err(status)
     }
 }
+
+/// A boxed closure called by [`set_context_destructor_callback_with_closure`].
+#[cfg(any(feature = "CL_VERSION_3_0", feature = "dynamic"))]
+type ContextDestructor = Box<dyn FnOnce(cl_context) + Send>;
+
+/// The context destructor trampoline: `user_data` is a `Box<ContextDestructor>`
+/// which is consumed.
+#[cfg(any(feature = "CL_VERSION_3_0", feature = "dynamic"))]
+extern "C" fn context_destructor_trampoline(context: cl_context, user_data: *mut c_void) {
+    let callback = unsafe { Box::from_raw(user_data.cast::<ContextDestructor>()) };
+    // Catch any panic so that it can't unwind across the FFI boundary.
+    let _ = panic::catch_unwind(AssertUnwindSafe(move || callback(context)));
+}
+
+/// Register a closure with a context that is called when the `context` is destroyed.
+/// Calls `clSetContextDestructorCallback` with a callback function that calls the closure.
+/// `CL_VERSION_3_0`
+///
+/// The closure is dropped after it has been called.
+/// A panic in the closure is caught, it does not unwind into the `OpenCL` library.
+///
+/// * `context` - the `cl_context` of the `OpenCL` context.
+/// * `callback` - the closure, called with the context when it is destroyed.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+#[cfg(any(feature = "CL_VERSION_3_0", feature = "dynamic"))]
+#[inline]
+pub fn set_context_destructor_callback_with_closure<F>(
+    context: cl_context,
+    callback: F,
+) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_context) + Send + 'static,
+{
+    let user_data = Box::into_raw(Box::new(Box::new(callback) as ContextDestructor));
+    set_context_destructor_callback(
+        context,
+        Some(context_destructor_trampoline),
+        user_data.cast::<c_void>(),
+    )
+    .inspect_err(|_| drop(unsafe { Box::from_raw(user_data) }))
+}
 // #endif
 
 #[cfg(test)]
@@ -221,6 +347,40 @@ mod tests {
     use super::*;
     use crate::device::{CL_DEVICE_TYPE_GPU, get_device_ids};
     use crate::platform::get_platform_ids;
+    use std::ffi::CString;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_context_notify_trampoline() {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&messages);
+        let notify: ContextNotify = Box::new(move |errinfo, private_info| {
+            received
+                .lock()
+                .unwrap()
+                .push((errinfo.to_owned(), private_info.to_vec()));
+        });
+        let user_data = Box::into_raw(Box::new(notify));
+
+        let errinfo = CString::new("out of memory").unwrap();
+        let private_info = [1_u8, 2, 3];
+        context_notify_trampoline(
+            errinfo.as_ptr(),
+            private_info.as_ptr().cast::<c_void>(),
+            private_info.len(),
+            user_data.cast::<c_void>(),
+        );
+        context_notify_trampoline(ptr::null(), ptr::null(), 0, user_data.cast::<c_void>());
+        let notified = messages.lock().unwrap().clone();
+        assert_eq!(2, notified.len());
+        assert_eq!("out of memory", notified[0].0);
+        assert_eq!(vec![1, 2, 3], notified[0].1);
+        assert!(notified[1].0.is_empty());
+        assert!(notified[1].1.is_empty());
+
+        drop_context_notify(ptr::null_mut(), user_data.cast::<c_void>());
+        assert_eq!(1, Arc::strong_count(&messages));
+    }
 
     #[test]
     fn test_context() {
@@ -0,0 +1,1412 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` enqueue commands with slice event wait lists.
+//!
+//! The functions in this module mirror the enqueue functions in
+//! [`command_queue`](crate::command_queue) and [`ext`](crate::ext), but take
+//! the event wait list as a slice instead of a count and a pointer,
+//! so that an empty wait list is simply `&[]`.
+//!
+//! They also take a `return_event` flag: when it is `false` no event is
+//! created for the command and the function returns `Ok(None)`, so there is
+//! no event to release.
+//!
+//! Host memory is passed as slices where the size of the transfer is
+//! determined by the slice. The functions that may access host memory after
+//! they return are `unsafe`.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::too_many_arguments, clippy::not_unsafe_ptr_arg_deref)]
+
+pub use opencl_sys::{
+    CL_INVALID_VALUE, CL_SUCCESS, cl_bool, cl_command_queue, cl_event, cl_int, cl_kernel,
+    cl_map_flags, cl_mem, cl_mem_migration_flags, cl_uint,
+};
+
+use super::types::Pod;
+use libc::{c_void, size_t};
+#[cfg(any(feature = "cl_khr_command_buffer", feature = "dynamic"))]
+use opencl_sys::cl_command_buffer_khr;
+#[cfg(any(feature = "cl_khr_semaphore", feature = "dynamic"))]
+use opencl_sys::{cl_semaphore_khr, cl_semaphore_payload_khr};
+use std::mem;
+use std::ptr;
+
+/// Convert an event wait list slice into the count and pointer required by
+/// the `OpenCL` C API. An empty slice is converted to `(0, ptr::null())`.
+///
+/// * `event_wait_list` - the events to wait for.
+///
+/// returns the number of events and a pointer to the events.
+#[must_use]
+#[allow(clippy::cast_possible_truncation)]
+pub const fn wait_list_parts(event_wait_list: &[cl_event]) -> (cl_uint, *const cl_event) {
+    if event_wait_list.is_empty() {
+        (0, ptr::null())
+    } else {
+        (event_wait_list.len() as cl_uint, event_wait_list.as_ptr())
+    }
+}
+
+/// The event pointer to pass to the `OpenCL` C API: null if no event is required.
+const fn event_ptr(event: &mut cl_event, return_event: bool) -> *mut cl_event {
+    if return_event { event } else { ptr::null_mut() }
+}
+
+/// Convert an `OpenCL` status and event into a Result.
+fn event_result(status: cl_int, event: cl_event) -> Result<Option<cl_event>, cl_int> {
+    if CL_SUCCESS == status {
+        Ok((!event.is_null()).then_some(event))
+    } else {
+        Err(status)
+    }
+}
+
+/// Enqueue a command to read from a buffer object into host memory.
+/// Calls `clEnqueueReadBuffer`, the size of the read is the size of `data`.
+///
+/// * `command_queue` - the `OpenCL` command-queue.
+/// * `buffer` - the buffer object to read from.
+/// * `blocking_read` - `CL_BLOCKING` or `CL_NON_BLOCKING`.
+/// * `offset` - the offset in bytes in the buffer object to read from.
+/// * `data` - the host memory to read into, a [`Pod`] type since it is
+///   overwritten with the bytes of the buffer.
+/// * `event_wait_list` - the events that must complete before the command.
+/// * `return_event` - whether to create an event for the command.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// If the read is non-blocking, `data` must not be accessed or dropped
+/// until the command has completed.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_read_buffer<T: Pod>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_read: cl_bool,
+    offset: size_t,
+    data: &mut [T],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueReadBuffer(
+            command_queue,
+            buffer,
+            blocking_read,
+            offset,
+            mem::size_of_val(data),
+            data.as_mut_ptr().cast::<c_void>(),
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to read a 2D or 3D rectangular region from a buffer
+/// object into host memory.
+/// Calls `clEnqueueReadBufferRect`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for the host region and pitches and, if the read is
+/// non-blocking, it must remain valid until the command has completed.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_read_buffer_rect(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_read: cl_bool,
+    buffer_origin: &[size_t; 3],
+    host_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    buffer_row_pitch: size_t,
+    buffer_slice_pitch: size_t,
+    host_row_pitch: size_t,
+    host_slice_pitch: size_t,
+    ptr: *mut c_void,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueReadBufferRect(
+            command_queue,
+            buffer,
+            blocking_read,
+            buffer_origin.as_ptr(),
+            host_origin.as_ptr(),
+            region.as_ptr(),
+            buffer_row_pitch,
+            buffer_slice_pitch,
+            host_row_pitch,
+            host_slice_pitch,
+            ptr,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to write to a buffer object from host memory.
+/// Calls `clEnqueueWriteBuffer`, the size of the write is the size of `data`.
+///
+/// * `command_queue` - the `OpenCL` command-queue.
+/// * `buffer` - the buffer object to write to.
+/// * `blocking_write` - `CL_BLOCKING` or `CL_NON_BLOCKING`.
+/// * `offset` - the offset in bytes in the buffer object to write to.
+/// * `data` - the host memory to write from, a [`Pod`] type so that every
+///   byte written to the buffer is initialised.
+/// * `event_wait_list` - the events that must complete before the command.
+/// * `return_event` - whether to create an event for the command.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// If the write is non-blocking, `data` must not be modified or dropped
+/// until the command has completed.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_write_buffer<T: Pod>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_write: cl_bool,
+    offset: size_t,
+    data: &[T],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueWriteBuffer(
+            command_queue,
+            buffer,
+            blocking_write,
+            offset,
+            mem::size_of_val(data),
+            data.as_ptr().cast::<c_void>(),
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to write a 2D or 3D rectangular region to a buffer
+/// object from host memory.
+/// Calls `clEnqueueWriteBufferRect`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for the host region and pitches and, if the write is
+/// non-blocking, it must remain valid until the command has completed.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_write_buffer_rect(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_write: cl_bool,
+    buffer_origin: &[size_t; 3],
+    host_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    buffer_row_pitch: size_t,
+    buffer_slice_pitch: size_t,
+    host_row_pitch: size_t,
+    host_slice_pitch: size_t,
+    ptr: *const c_void,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueWriteBufferRect(
+            command_queue,
+            buffer,
+            blocking_write,
+            buffer_origin.as_ptr(),
+            host_origin.as_ptr(),
+            region.as_ptr(),
+            buffer_row_pitch,
+            buffer_slice_pitch,
+            host_row_pitch,
+            host_slice_pitch,
+            ptr,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to fill a buffer object with a pattern.
+/// Calls `clEnqueueFillBuffer`, the pattern is copied when the command is enqueued.
+/// `CL_VERSION_1_2`
+///
+/// * `command_queue` - the `OpenCL` command-queue.
+/// * `buffer` - the buffer object to fill.
+/// * `pattern` - the pattern to fill the buffer with, a [`Pod`] type.
+/// * `offset` - the offset in bytes in the buffer object to fill from.
+/// * `size` - the size in bytes to fill, a multiple of the pattern size.
+/// * `event_wait_list` - the events that must complete before the command.
+/// * `return_event` - whether to create an event for the command.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue` and `buffer` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_fill_buffer<T: Pod>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    pattern: &T,
+    offset: size_t,
+    size: size_t,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueFillBuffer(
+            command_queue,
+            buffer,
+            ptr::from_ref(pattern).cast::<c_void>(),
+            mem::size_of::<T>(),
+            offset,
+            size,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to copy between buffer objects.
+/// Calls `clEnqueueCopyBuffer`.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue`, `src_buffer` and `dst_buffer` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_copy_buffer(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_buffer: cl_mem,
+    src_offset: size_t,
+    dst_offset: size_t,
+    size: size_t,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueCopyBuffer(
+            command_queue,
+            src_buffer,
+            dst_buffer,
+            src_offset,
+            dst_offset,
+            size,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to copy a 2D or 3D rectangular region between buffer objects.
+/// Calls `clEnqueueCopyBufferRect`.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue`, `src_buffer` and `dst_buffer` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_copy_buffer_rect(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_buffer: cl_mem,
+    src_origin: &[size_t; 3],
+    dst_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    src_row_pitch: size_t,
+    src_slice_pitch: size_t,
+    dst_row_pitch: size_t,
+    dst_slice_pitch: size_t,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueCopyBufferRect(
+            command_queue,
+            src_buffer,
+            dst_buffer,
+            src_origin.as_ptr(),
+            dst_origin.as_ptr(),
+            region.as_ptr(),
+            src_row_pitch,
+            src_slice_pitch,
+            dst_row_pitch,
+            dst_slice_pitch,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to read from an image object into host memory.
+/// Calls `clEnqueueReadImage`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for the region and pitches and, if the read is
+/// non-blocking, it must remain valid until the command has completed.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_read_image(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_read: cl_bool,
+    origin: &[size_t; 3],
+    region: &[size_t; 3],
+    row_pitch: size_t,
+    slice_pitch: size_t,
+    ptr: *mut c_void,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueReadImage(
+            command_queue,
+            image,
+            blocking_read,
+            origin.as_ptr(),
+            region.as_ptr(),
+            row_pitch,
+            slice_pitch,
+            ptr,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to write to an image object from host memory.
+/// Calls `clEnqueueWriteImage`.
+///
+/// # Safety
+///
+/// `ptr` must be valid for the region and pitches and, if the write is
+/// non-blocking, it must remain valid until the command has completed.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_write_image(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_write: cl_bool,
+    origin: &[size_t; 3],
+    region: &[size_t; 3],
+    input_row_pitch: size_t,
+    input_slice_pitch: size_t,
+    ptr: *const c_void,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueWriteImage(
+            command_queue,
+            image,
+            blocking_write,
+            origin.as_ptr(),
+            region.as_ptr(),
+            input_row_pitch,
+            input_slice_pitch,
+            ptr,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to fill an image object with a colour.
+/// Calls `clEnqueueFillImage`, the colour is copied when the command is enqueued.
+/// `CL_VERSION_1_2`
+///
+/// * `fill_color` - a four component `cl_float`, `cl_int` or `cl_uint` colour,
+///   depending upon the image channel data type.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue` and `image` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_fill_image<T>(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    fill_color: &[T; 4],
+    origin: &[size_t; 3],
+    region: &[size_t; 3],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueFillImage(
+            command_queue,
+            image,
+            fill_color.as_ptr().cast::<c_void>(),
+            origin.as_ptr(),
+            region.as_ptr(),
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to copy between image objects.
+/// Calls `clEnqueueCopyImage`.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue`, `src_image` and `dst_image` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_copy_image(
+    command_queue: cl_command_queue,
+    src_image: cl_mem,
+    dst_image: cl_mem,
+    src_origin: &[size_t; 3],
+    dst_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueCopyImage(
+            command_queue,
+            src_image,
+            dst_image,
+            src_origin.as_ptr(),
+            dst_origin.as_ptr(),
+            region.as_ptr(),
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to copy from an image object to a buffer object.
+/// Calls `clEnqueueCopyImageToBuffer`.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue`, `src_image` and `dst_buffer` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_copy_image_to_buffer(
+    command_queue: cl_command_queue,
+    src_image: cl_mem,
+    dst_buffer: cl_mem,
+    src_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    dst_offset: size_t,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueCopyImageToBuffer(
+            command_queue,
+            src_image,
+            dst_buffer,
+            src_origin.as_ptr(),
+            region.as_ptr(),
+            dst_offset,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to copy from a buffer object to an image object.
+/// Calls `clEnqueueCopyBufferToImage`.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue`, `src_buffer` and `dst_image` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_copy_buffer_to_image(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_image: cl_mem,
+    src_offset: size_t,
+    dst_origin: &[size_t; 3],
+    region: &[size_t; 3],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueCopyBufferToImage(
+            command_queue,
+            src_buffer,
+            dst_image,
+            src_offset,
+            dst_origin.as_ptr(),
+            region.as_ptr(),
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to map a region of a buffer object into the host address space.
+/// Calls `clEnqueueMapBuffer`.
+///
+/// * `mapped_ptr` - set to the host pointer of the mapped region.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// If the map is non-blocking, the mapped region must not be accessed until
+/// the command has completed. The region must be unmapped with
+/// [`enqueue_unmap_mem_object`].
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_map_buffer(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_map: cl_bool,
+    map_flags: cl_map_flags,
+    offset: size_t,
+    size: size_t,
+    mapped_ptr: &mut *mut c_void,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let mut status: cl_int = CL_INVALID_VALUE;
+    *mapped_ptr = unsafe {
+        cl_call!(clEnqueueMapBuffer(
+            command_queue,
+            buffer,
+            blocking_map,
+            map_flags,
+            offset,
+            size,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+            &raw mut status,
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to map a region of an image object into the host address space.
+/// Calls `clEnqueueMapImage`.
+///
+/// * `image_row_pitch` - set to the row pitch of the mapped region.
+/// * `image_slice_pitch` - set to the slice pitch of the mapped region.
+/// * `mapped_ptr` - set to the host pointer of the mapped region.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// If the map is non-blocking, the mapped region must not be accessed until
+/// the command has completed. The region must be unmapped with
+/// [`enqueue_unmap_mem_object`].
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_map_image(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_map: cl_bool,
+    map_flags: cl_map_flags,
+    origin: &[size_t; 3],
+    region: &[size_t; 3],
+    image_row_pitch: &mut size_t,
+    image_slice_pitch: &mut size_t,
+    mapped_ptr: &mut *mut c_void,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let mut status: cl_int = CL_INVALID_VALUE;
+    *mapped_ptr = unsafe {
+        cl_call!(clEnqueueMapImage(
+            command_queue,
+            image,
+            blocking_map,
+            map_flags,
+            origin.as_ptr(),
+            region.as_ptr(),
+            image_row_pitch,
+            image_slice_pitch,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+            &raw mut status,
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to unmap a previously mapped region of a memory object.
+/// Calls `clEnqueueUnmapMemObject`.
+///
+/// # Safety
+///
+/// `mapped_ptr` must have been returned by a map of `memobj` and must not be
+/// accessed after this call.
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_unmap_mem_object(
+    command_queue: cl_command_queue,
+    memobj: cl_mem,
+    mapped_ptr: *mut c_void,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueUnmapMemObject(
+            command_queue,
+            memobj,
+            mapped_ptr,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to migrate memory objects to the device of the command-queue.
+/// Calls `clEnqueueMigrateMemObjects`.
+/// `CL_VERSION_1_2`
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue` and `mem_objects` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(unused_unsafe)]
+pub unsafe fn enqueue_migrate_mem_object(
+    command_queue: cl_command_queue,
+    mem_objects: &[cl_mem],
+    flags: cl_mem_migration_flags,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueMigrateMemObjects(
+            command_queue,
+            mem_objects.len() as cl_uint,
+            mem_objects.as_ptr(),
+            flags,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to execute a kernel on a device.
+/// Calls `clEnqueueNDRangeKernel`, the work dimension is the length of
+/// `global_work_size`.
+///
+/// * `global_work_offset` - the global work offset, or None for zero offsets.
+/// * `global_work_size` - the global work size in each dimension.
+/// * `local_work_size` - the local work size, or None for the `OpenCL`
+///   implementation to choose.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+/// `CL_INVALID_VALUE` if the offset or local work size does not have the
+/// same length as `global_work_size`.
+///
+/// # Safety
+///
+/// The kernel arguments must be set and valid for the kernel.
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(unused_unsafe)]
+pub unsafe fn enqueue_nd_range_kernel(
+    command_queue: cl_command_queue,
+    kernel: cl_kernel,
+    global_work_offset: Option<&[size_t]>,
+    global_work_size: &[size_t],
+    local_work_size: Option<&[size_t]>,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let work_dim = global_work_size.len();
+    if global_work_offset.is_some_and(|offset| offset.len() != work_dim)
+        || local_work_size.is_some_and(|local| local.len() != work_dim)
+    {
+        return Err(CL_INVALID_VALUE);
+    }
+
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueNDRangeKernel(
+            command_queue,
+            kernel,
+            work_dim as cl_uint,
+            global_work_offset.map_or(ptr::null(), <[size_t]>::as_ptr),
+            global_work_size.as_ptr(),
+            local_work_size.map_or(ptr::null(), <[size_t]>::as_ptr),
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a marker command which waits for events or all previously
+/// enqueued commands to complete.
+/// Calls `clEnqueueMarkerWithWaitList`.
+/// `CL_VERSION_1_2`
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_marker_with_wait_list(
+    command_queue: cl_command_queue,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueMarkerWithWaitList(
+            command_queue,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a barrier command which waits for events or all previously
+/// enqueued commands to complete before later commands can execute.
+/// Calls `clEnqueueBarrierWithWaitList`.
+/// `CL_VERSION_1_2`
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_barrier_with_wait_list(
+    command_queue: cl_command_queue,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueBarrierWithWaitList(
+            command_queue,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to free SVM pointers with `clSVMFree`.
+/// Calls `clEnqueueSVMFree` without a user callback function.
+/// `CL_VERSION_2_0`
+///
+/// # Safety
+///
+/// The SVM pointers must have been allocated by `svm_alloc` and must not be
+/// used after this call.
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "dynamic"))]
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(unused_unsafe)]
+pub unsafe fn enqueue_svm_free(
+    command_queue: cl_command_queue,
+    svm_pointers: &[*const c_void],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueSVMFree(
+            command_queue,
+            svm_pointers.len() as cl_uint,
+            svm_pointers.as_ptr(),
+            None,
+            ptr::null_mut(),
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to copy memory to or from SVM or host memory.
+/// Calls `clEnqueueSVMMemcpy`.
+/// `CL_VERSION_2_0`
+///
+/// # Safety
+///
+/// `dst_ptr` and `src_ptr` must be valid for `size` bytes and, if the copy is
+/// non-blocking, they must remain valid until the command has completed.
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_svm_mem_cpy(
+    command_queue: cl_command_queue,
+    blocking_copy: cl_bool,
+    dst_ptr: *mut c_void,
+    src_ptr: *const c_void,
+    size: size_t,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueSVMMemcpy(
+            command_queue,
+            blocking_copy,
+            dst_ptr,
+            src_ptr,
+            size,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to fill a region of SVM with a pattern.
+/// Calls `clEnqueueSVMMemFill`, the pattern is copied when the command is enqueued.
+/// `CL_VERSION_2_0`
+///
+/// The pattern is a [`Pod`] type so that every byte of it is initialised.
+///
+/// # Safety
+///
+/// `svm_ptr` must be an SVM pointer valid for `size` bytes.
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_svm_mem_fill<T: Pod>(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    pattern: &T,
+    size: size_t,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueSVMMemFill(
+            command_queue,
+            svm_ptr,
+            ptr::from_ref(pattern).cast::<c_void>(),
+            mem::size_of::<T>(),
+            size,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to map a region of coarse-grained SVM for host access.
+/// Calls `clEnqueueSVMMap`.
+/// `CL_VERSION_2_0`
+///
+/// # Safety
+///
+/// `svm_ptr` must be an SVM pointer valid for `size` bytes and, if the map is
+/// non-blocking, it must not be accessed until the command has completed.
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_svm_map(
+    command_queue: cl_command_queue,
+    blocking_map: cl_bool,
+    flags: cl_map_flags,
+    svm_ptr: *mut c_void,
+    size: size_t,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueSVMMap(
+            command_queue,
+            blocking_map,
+            flags,
+            svm_ptr,
+            size,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to unmap a previously mapped region of SVM.
+/// Calls `clEnqueueSVMUnmap`.
+/// `CL_VERSION_2_0`
+///
+/// # Safety
+///
+/// `svm_ptr` must have been mapped by [`enqueue_svm_map`] and must not be
+/// accessed by the host after this call.
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_svm_unmap(
+    command_queue: cl_command_queue,
+    svm_ptr: *mut c_void,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueSVMUnmap(
+            command_queue,
+            svm_ptr,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to migrate SVM allocations to the device of the command-queue.
+/// Calls `clEnqueueSVMMigrateMem`.
+/// `CL_VERSION_2_1`
+///
+/// * `sizes` - the sizes of the regions to migrate, or empty to migrate
+///   whole allocations.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+/// `CL_INVALID_VALUE` if `sizes` is not empty and is not the same length
+/// as `svm_pointers`.
+///
+/// # Safety
+///
+/// The SVM pointers must be valid for the sizes.
+#[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(unused_unsafe)]
+pub unsafe fn enqueue_svm_migrate_mem(
+    command_queue: cl_command_queue,
+    svm_pointers: &[*const c_void],
+    sizes: &[size_t],
+    flags: cl_mem_migration_flags,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    if !sizes.is_empty() && sizes.len() != svm_pointers.len() {
+        return Err(CL_INVALID_VALUE);
+    }
+
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueSVMMigrateMem(
+            command_queue,
+            svm_pointers.len() as cl_uint,
+            svm_pointers.as_ptr(),
+            if sizes.is_empty() {
+                ptr::null()
+            } else {
+                sizes.as_ptr()
+            },
+            flags,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command-buffer to execute on command-queues.
+/// Calls `clEnqueueCommandBufferKHR`.
+/// `cl_khr_command_buffer`
+///
+/// * `queues` - the command-queues to replace those that the command-buffer
+///   was recorded with, or empty to use the recorded command-queues.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `queues` and `command_buffer` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects and `command_buffer` must be finalized.
+#[cfg(any(feature = "cl_khr_command_buffer", feature = "dynamic"))]
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(unused_unsafe)]
+pub unsafe fn enqueue_command_buffer_khr(
+    queues: &mut [cl_command_queue],
+    command_buffer: cl_command_buffer_khr,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueCommandBufferKHR(
+            queues.len() as cl_uint,
+            if queues.is_empty() {
+                ptr::null_mut()
+            } else {
+                queues.as_mut_ptr()
+            },
+            command_buffer,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to acquire external memory objects for use by the command-queue.
+/// Calls `clEnqueueAcquireExternalMemObjectsKHR`.
+/// `cl_khr_external_memory`
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue` and `mem_objects` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects and the memory objects must be external memory objects.
+#[cfg(any(feature = "cl_khr_external_memory", feature = "dynamic"))]
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(unused_unsafe)]
+pub unsafe fn enqueue_acquire_external_mem_objects_khr(
+    command_queue: cl_command_queue,
+    mem_objects: &[cl_mem],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueAcquireExternalMemObjectsKHR(
+            command_queue,
+            mem_objects.len() as cl_uint,
+            mem_objects.as_ptr(),
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to release external memory objects acquired by the command-queue.
+/// Calls `clEnqueueReleaseExternalMemObjectsKHR`.
+/// `cl_khr_external_memory`
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue` and `mem_objects` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects and the memory objects must be external memory objects.
+#[cfg(any(feature = "cl_khr_external_memory", feature = "dynamic"))]
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+#[allow(unused_unsafe)]
+pub unsafe fn enqueue_release_external_mem_objects_khr(
+    command_queue: cl_command_queue,
+    mem_objects: &[cl_mem],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueReleaseExternalMemObjectsKHR(
+            command_queue,
+            mem_objects.len() as cl_uint,
+            mem_objects.as_ptr(),
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to wait on semaphores.
+/// Calls `clEnqueueWaitSemaphoresKHR`.
+/// `cl_khr_semaphore`
+///
+/// * `sema_payload_list` - the payloads of the semaphores, or empty
+///   for binary semaphores.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+/// `CL_INVALID_VALUE` if `sema_payload_list` is not empty and is not the
+/// same length as `sema_objects`.
+///
+/// # Safety
+///
+/// `command_queue` and `sema_objects` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[cfg(any(feature = "cl_khr_semaphore", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_wait_semaphores_khr(
+    command_queue: cl_command_queue,
+    sema_objects: &[cl_semaphore_khr],
+    sema_payload_list: &[cl_semaphore_payload_khr],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_semaphores, payloads) = semaphore_parts(sema_objects, sema_payload_list)?;
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueWaitSemaphoresKHR(
+            command_queue,
+            num_semaphores,
+            sema_objects.as_ptr(),
+            payloads,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// Enqueue a command to signal semaphores.
+/// Calls `clEnqueueSignalSemaphoresKHR`.
+/// `cl_khr_semaphore`
+///
+/// * `sema_payload_list` - the payloads of the semaphores, or empty
+///   for binary semaphores.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+/// `CL_INVALID_VALUE` if `sema_payload_list` is not empty and is not the
+/// same length as `sema_objects`.
+///
+/// # Safety
+///
+/// `command_queue` and `sema_objects` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+#[cfg(any(feature = "cl_khr_semaphore", feature = "dynamic"))]
+#[allow(unused_unsafe)]
+#[inline]
+pub unsafe fn enqueue_signal_semaphores_khr(
+    command_queue: cl_command_queue,
+    sema_objects: &[cl_semaphore_khr],
+    sema_payload_list: &[cl_semaphore_payload_khr],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let (num_semaphores, payloads) = semaphore_parts(sema_objects, sema_payload_list)?;
+    let (num_events, events) = wait_list_parts(event_wait_list);
+    let mut event: cl_event = ptr::null_mut();
+    let status: cl_int = unsafe {
+        cl_call!(clEnqueueSignalSemaphoresKHR(
+            command_queue,
+            num_semaphores,
+            sema_objects.as_ptr(),
+            payloads,
+            num_events,
+            events,
+            event_ptr(&mut event, return_event),
+        ))
+    };
+    event_result(status, event)
+}
+
+/// The semaphore count and payload pointer for the `OpenCL` C API.
+#[cfg(any(feature = "cl_khr_semaphore", feature = "dynamic"))]
+#[allow(clippy::cast_possible_truncation)]
+const fn semaphore_parts(
+    sema_objects: &[cl_semaphore_khr],
+    sema_payload_list: &[cl_semaphore_payload_khr],
+) -> Result<(cl_uint, *const cl_semaphore_payload_khr), cl_int> {
+    if sema_payload_list.is_empty() {
+        Ok((sema_objects.len() as cl_uint, ptr::null()))
+    } else if sema_payload_list.len() == sema_objects.len() {
+        Ok((sema_objects.len() as cl_uint, sema_payload_list.as_ptr()))
+    } else {
+        Err(CL_INVALID_VALUE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wait_list_parts() {
+        let (count, events) = wait_list_parts(&[]);
+        assert_eq!(0, count);
+        assert!(events.is_null());
+
+        let wait_list = [ptr::null_mut(), ptr::null_mut()];
+        let (count, events) = wait_list_parts(&wait_list);
+        assert_eq!(2, count);
+        assert_eq!(wait_list.as_ptr(), events);
+    }
+
+    #[test]
+    fn test_event_result() {
+        let mut event: cl_event = ptr::null_mut();
+        assert!(event_ptr(&mut event, false).is_null());
+        assert!(!event_ptr(&mut event, true).is_null());
+
+        assert_eq!(Ok(None), event_result(CL_SUCCESS, ptr::null_mut()));
+        assert_eq!(
+            Err(CL_INVALID_VALUE),
+            event_result(CL_INVALID_VALUE, ptr::null_mut())
+        );
+    }
+}
@@ -48,6 +48,7 @@ use super::{api_info_size, api_info_value, api_info_vector};
 use libc::{c_void, intptr_t, size_t};
 use std::fmt;
 use std::mem;
+use std::panic::{self, AssertUnwindSafe};
 use std::ptr;
 
 /// Wait for `OpenCL` events to complete.
@@ -212,6 +213,46 @@ pub fn set_event_callback(
     }
 }
 
+/// A boxed closure called by [`set_event_callback_with_closure`].
+type EventCallback = Box<dyn FnOnce(cl_event, cl_int) + Send>;
+
+/// The event callback trampoline: `user_data` is a `Box<EventCallback>` which is consumed.
+extern "C" fn event_callback_trampoline(event: cl_event, status: cl_int, user_data: *mut c_void) {
+    let callback = unsafe { Box::from_raw(user_data.cast::<EventCallback>()) };
+    // Catch any panic so that it can't unwind across the FFI boundary.
+    let _ = panic::catch_unwind(AssertUnwindSafe(move || callback(event, status)));
+}
+
+/// Register a closure for a specific command execution status.
+/// Calls `clSetEventCallback` to register a callback function that calls the closure.
+///
+/// The closure is called at most once and is dropped after it has been called.
+/// A panic in the closure is caught, it does not unwind into the `OpenCL` library.
+///
+/// * `event` - the `OpenCL` event.
+/// * `command_exec_callback_type` - the command execution status:
+///   `CL_SUBMITTED`, `CL_RUNNING` or `CL_COMPLETE`.
+/// * `callback` - the closure, called with the event and its execution status.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+pub fn set_event_callback_with_closure<F>(
+    event: cl_event,
+    command_exec_callback_type: cl_int,
+    callback: F,
+) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_event, cl_int) + Send + 'static,
+{
+    let user_data = Box::into_raw(Box::new(Box::new(callback) as EventCallback));
+    set_event_callback(
+        event,
+        command_exec_callback_type,
+        event_callback_trampoline,
+        user_data.cast::<c_void>(),
+    )
+    .inspect_err(|_| drop(unsafe { Box::from_raw(user_data) }))
+}
+
 /// Get profiling data about an `OpenCL` event.
 /// Calls `clGetEventProfilingInfo` to get the desired profiling data about the event.
 pub fn get_event_profiling_data(
@@ -356,6 +397,26 @@ impl fmt::Display for EventCommandType {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    #[test]
+    fn test_event_callback_trampoline() {
+        let result = Arc::new(AtomicI32::new(0));
+        let status = Arc::clone(&result);
+        let callback: EventCallback = Box::new(move |_, execution_status| {
+            status.store(execution_status, Ordering::SeqCst);
+        });
+        let user_data = Box::into_raw(Box::new(callback));
+        event_callback_trampoline(ptr::null_mut(), CL_RUNNING, user_data.cast::<c_void>());
+        assert_eq!(CL_RUNNING, result.load(Ordering::SeqCst));
+        assert_eq!(1, Arc::strong_count(&result));
+
+        // A panic in the closure must not unwind out of the trampoline
+        let callback: EventCallback = Box::new(|_, _| panic!("event callback"));
+        let user_data = Box::into_raw(Box::new(callback));
+        event_callback_trampoline(ptr::null_mut(), CL_COMPLETE, user_data.cast::<c_void>());
+    }
 
     #[test]
     fn test_status_text() {
@@ -0,0 +1,338 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` event futures.
+//!
+//! An [`EventFuture`] is an executor agnostic [`Future`] for an `OpenCL` event.
+//! It registers an event callback for `CL_COMPLETE` with `clSetEventCallback`
+//! and wakes the task that polled it when the callback is called, so awaiting
+//! an event does not block a thread.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub use opencl_sys::{CL_COMPLETE, CL_INVALID_EVENT, CL_INVALID_VALUE, cl_event, cl_int};
+
+use super::event::{release_event, retain_event, set_event_callback};
+use libc::c_void;
+use std::future::Future;
+use std::panic;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// The state shared between an `EventFuture` and its event callback.
+#[derive(Debug, Default)]
+struct EventState {
+    status: Option<cl_int>,
+    waker: Option<Waker>,
+}
+
+/// The event callback: records the execution status and wakes the task.
+/// `user_data` is an `Arc<Mutex<EventState>>` which is consumed.
+extern "C" fn event_complete_callback(_event: cl_event, status: cl_int, user_data: *mut c_void) {
+    // Catch any panic from a waker so that it can't unwind across the FFI boundary.
+    let _ = panic::catch_unwind(|| {
+        let state = unsafe { Arc::from_raw(user_data.cast::<Mutex<EventState>>().cast_const()) };
+        let waker = state.lock().ok().and_then(|mut state| {
+            state.status = Some(status);
+            state.waker.take()
+        });
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    });
+}
+
+/// A [`Future`] that resolves when an `OpenCL` event completes.
+///
+/// The future resolves to `Ok(CL_COMPLETE)` when the command completes
+/// successfully or to `Err` containing the negative execution status
+/// if the command terminated abnormally.
+///
+/// The future retains the event while it exists.
+#[derive(Debug)]
+pub struct EventFuture {
+    event: cl_event,
+    state: Arc<Mutex<EventState>>,
+}
+
+impl EventFuture {
+    /// Create a future for an `OpenCL` event.
+    /// Calls `clSetEventCallback` to register a `CL_COMPLETE` callback.
+    ///
+    /// * `event` - the `OpenCL` event.
+    ///
+    /// returns a Result containing the new `EventFuture`
+    /// or the error code from the `OpenCL` C API function.
+    pub fn new(event: cl_event) -> Result<Self, cl_int> {
+        unsafe { retain_event(event)? };
+        let future = Self {
+            event,
+            state: Arc::new(Mutex::new(EventState::default())),
+        };
+
+        // The callback takes ownership of this reference to the state.
+        let user_data = Arc::into_raw(Arc::clone(&future.state));
+        if let Err(error) = set_event_callback(
+            event,
+            CL_COMPLETE,
+            event_complete_callback,
+            user_data.cast::<c_void>().cast_mut(),
+        ) {
+            drop(unsafe { Arc::from_raw(user_data) });
+            return Err(error);
+        }
+        Ok(future)
+    }
+
+    /// The `OpenCL` event of the future.
+    #[must_use]
+    pub const fn event(&self) -> cl_event {
+        self.event
+    }
+
+    /// The execution status reported to the event callback, if it has been called.
+    #[must_use]
+    pub fn status(&self) -> Option<cl_int> {
+        self.state.lock().ok().and_then(|state| state.status)
+    }
+}
+
+// SAFETY: `OpenCL` event objects are thread-safe: since `OpenCL` 1.1 all API
+// functions are thread-safe except those that modify the state of kernel
+// objects, so the event may be released from another thread. The shared state is
+// protected by a Mutex. `JoinEvents` and `SelectEvent` are `Send` and `Sync`
+// because they only contain `EventFuture`s.
+unsafe impl Send for EventFuture {}
+unsafe impl Sync for EventFuture {}
+
+impl Drop for EventFuture {
+    fn drop(&mut self) {
+        if !self.event.is_null() {
+            unsafe {
+                let _ = release_event(self.event);
+            }
+        }
+    }
+}
+
+impl Future for EventFuture {
+    type Output = Result<cl_int, cl_int>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Ok(mut state) = self.state.lock() else {
+            return Poll::Ready(Err(CL_INVALID_EVENT));
+        };
+        match state.status {
+            Some(status) if status < 0 => Poll::Ready(Err(status)),
+            Some(status) => Poll::Ready(Ok(status)),
+            None => {
+                match &mut state.waker {
+                    Some(waker) => waker.clone_from(cx.waker()),
+                    waker @ None => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// A [`Future`] that resolves when all of a set of `OpenCL` events complete,
+/// see [`join_events`].
+#[derive(Debug)]
+pub struct JoinEvents {
+    futures: Vec<EventFuture>,
+}
+
+impl JoinEvents {
+    /// The `EventFuture`s being joined.
+    #[must_use]
+    pub fn futures(&self) -> &[EventFuture] {
+        &self.futures
+    }
+}
+
+impl Future for JoinEvents {
+    type Output = Result<(), cl_int>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut pending = false;
+        for future in &mut self.futures {
+            match Pin::new(future).poll(cx) {
+                Poll::Ready(Err(status)) => return Poll::Ready(Err(status)),
+                Poll::Ready(Ok(_)) => {}
+                Poll::Pending => pending = true,
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+/// Create a future that resolves when all of the events complete.
+///
+/// * `events` - the `OpenCL` events.
+///
+/// returns a Result containing a `JoinEvents` future
+/// or the error code from the `OpenCL` C API function.
+/// The `JoinEvents` future resolves to the first negative execution status
+/// of the events, or `Ok(())` when they have all completed successfully.
+pub fn join_events(events: &[cl_event]) -> Result<JoinEvents, cl_int> {
+    let futures = events
+        .iter()
+        .map(|&event| EventFuture::new(event))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(JoinEvents { futures })
+}
+
+/// A [`Future`] that resolves when any of a set of `OpenCL` events completes,
+/// see [`select_event`].
+#[derive(Debug)]
+pub struct SelectEvent {
+    futures: Vec<EventFuture>,
+}
+
+impl Future for SelectEvent {
+    type Output = (usize, Result<cl_int, cl_int>);
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        for (index, future) in self.futures.iter_mut().enumerate() {
+            if let Poll::Ready(result) = Pin::new(future).poll(cx) {
+                return Poll::Ready((index, result));
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// Create a future that resolves when the first of the events completes.
+///
+/// * `events` - the `OpenCL` events.
+///
+/// returns a Result containing a `SelectEvent` future
+/// or the error code from the `OpenCL` C API function,
+/// `CL_INVALID_VALUE` if `events` is empty, like `clWaitForEvents`.
+/// The `SelectEvent` future resolves to the index of the first event to
+/// complete together with its result.
+pub fn select_event(events: &[cl_event]) -> Result<SelectEvent, cl_int> {
+    if events.is_empty() {
+        return Err(CL_INVALID_VALUE);
+    }
+    let futures = events
+        .iter()
+        .map(|&event| EventFuture::new(event))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(SelectEvent { futures })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opencl_sys::CL_OUT_OF_RESOURCES;
+    use std::ptr;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::task::Wake;
+
+    struct CountWaker(AtomicUsize);
+
+    impl Wake for CountWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    // Create an EventFuture without an OpenCL event and the callback user_data.
+    fn unregistered_future() -> (EventFuture, *mut c_void) {
+        let future = EventFuture {
+            event: ptr::null_mut(),
+            state: Arc::new(Mutex::new(EventState::default())),
+        };
+        let user_data = Arc::into_raw(Arc::clone(&future.state));
+        (future, user_data.cast::<c_void>().cast_mut())
+    }
+
+    #[test]
+    fn test_event_futures_are_send() {
+        const fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<EventFuture>();
+        assert_send_sync::<JoinEvents>();
+        assert_send_sync::<SelectEvent>();
+    }
+
+    #[test]
+    fn test_event_future() {
+        let count = Arc::new(CountWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(Arc::clone(&count));
+        let mut cx = Context::from_waker(&waker);
+
+        let (mut future, user_data) = unregistered_future();
+        assert!(Pin::new(&mut future).poll(&mut cx).is_pending());
+        assert!(future.status().is_none());
+
+        event_complete_callback(ptr::null_mut(), CL_COMPLETE, user_data);
+        assert_eq!(1, count.0.load(Ordering::SeqCst));
+        assert_eq!(Some(CL_COMPLETE), future.status());
+        assert_eq!(
+            Poll::Ready(Ok(CL_COMPLETE)),
+            Pin::new(&mut future).poll(&mut cx)
+        );
+        assert_eq!(1, Arc::strong_count(&future.state));
+
+        let (mut future, user_data) = unregistered_future();
+        event_complete_callback(ptr::null_mut(), CL_OUT_OF_RESOURCES, user_data);
+        assert_eq!(
+            Poll::Ready(Err(CL_OUT_OF_RESOURCES)),
+            Pin::new(&mut future).poll(&mut cx)
+        );
+    }
+
+    #[test]
+    fn test_join_and_select_events() {
+        let count = Arc::new(CountWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(Arc::clone(&count));
+        let mut cx = Context::from_waker(&waker);
+
+        let (future_0, user_data_0) = unregistered_future();
+        let (future_1, user_data_1) = unregistered_future();
+        let mut join = JoinEvents {
+            futures: vec![future_0, future_1],
+        };
+        assert!(Pin::new(&mut join).poll(&mut cx).is_pending());
+        event_complete_callback(ptr::null_mut(), CL_COMPLETE, user_data_1);
+        assert!(Pin::new(&mut join).poll(&mut cx).is_pending());
+        event_complete_callback(ptr::null_mut(), CL_COMPLETE, user_data_0);
+        assert_eq!(Poll::Ready(Ok(())), Pin::new(&mut join).poll(&mut cx));
+        assert_eq!(2, count.0.load(Ordering::SeqCst));
+
+        let (future_0, user_data_0) = unregistered_future();
+        let (future_1, user_data_1) = unregistered_future();
+        let mut select = SelectEvent {
+            futures: vec![future_0, future_1],
+        };
+        assert!(Pin::new(&mut select).poll(&mut cx).is_pending());
+        event_complete_callback(ptr::null_mut(), CL_OUT_OF_RESOURCES, user_data_1);
+        assert_eq!(
+            Poll::Ready((1, Err(CL_OUT_OF_RESOURCES))),
+            Pin::new(&mut select).poll(&mut cx)
+        );
+        event_complete_callback(ptr::null_mut(), CL_COMPLETE, user_data_0);
+
+        // A select of no events would never resolve.
+        assert_eq!(CL_INVALID_VALUE, select_event(&[]).unwrap_err());
+    }
+}
@@ -39,6 +39,23 @@ use std::mem;
 #[allow(unused_imports)]
 use std::ptr;
 
+/// Convert a sync point wait list slice into the count and pointer required by
+/// the `OpenCL` C API. An empty slice is converted to `(0, ptr::null())`.
+#[cfg(any(feature = "cl_khr_command_buffer", feature = "dynamic"))]
+#[allow(clippy::cast_possible_truncation)]
+const fn sync_point_wait_list_parts(
+    sync_point_wait_list: &[cl_sync_point_khr],
+) -> (cl_uint, *const cl_sync_point_khr) {
+    if sync_point_wait_list.is_empty() {
+        (0, ptr::null())
+    } else {
+        (
+            sync_point_wait_list.len() as cl_uint,
+            sync_point_wait_list.as_ptr(),
+        )
+    }
+}
+
 #[cfg(any(feature = "cl_khr_command_buffer", feature = "dynamic"))]
 #[allow(clippy::cast_possible_truncation)]
 pub fn create_command_buffer_khr(
@@ -129,12 +146,13 @@ pub unsafe fn command_barrier_with_wait_list_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandBarrierWithWaitListKHR(
         command_buffer,
         command_queue,
         properties,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -160,6 +178,7 @@ pub unsafe fn command_copy_buffer_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandCopyBufferKHR(
         command_buffer,
         command_queue,
@@ -169,8 +188,8 @@ pub unsafe fn command_copy_buffer_khr(
         src_offset,
         dst_offset,
         size,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -200,6 +219,7 @@ pub unsafe fn command_copy_buffer_rect_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandCopyBufferRectKHR(
         command_buffer,
         command_queue,
@@ -213,8 +233,8 @@ pub unsafe fn command_copy_buffer_rect_khr(
         src_slice_pitch,
         dst_row_pitch,
         dst_slice_pitch,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -240,6 +260,7 @@ pub unsafe fn command_copy_buffer_to_image_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandCopyBufferToImageKHR(
         command_buffer,
         command_queue,
@@ -249,8 +270,8 @@ pub unsafe fn command_copy_buffer_to_image_khr(
         src_offset,
         dst_origin,
         region,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -276,6 +297,7 @@ pub unsafe fn command_copy_image_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandCopyImageKHR(
         command_buffer,
         command_queue,
@@ -285,8 +307,8 @@ pub unsafe fn command_copy_image_khr(
         src_origin,
         dst_origin,
         region,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -312,6 +334,7 @@ pub unsafe fn command_copy_image_to_buffer_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandCopyImageToBufferKHR(
         command_buffer,
         command_queue,
@@ -321,8 +344,8 @@ pub unsafe fn command_copy_image_to_buffer_khr(
         src_origin,
         region,
         dst_offset,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -348,6 +371,7 @@ pub unsafe fn command_fill_buffer_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandFillBufferKHR(
         command_buffer,
         command_queue,
@@ -357,8 +381,8 @@ pub unsafe fn command_fill_buffer_khr(
         pattern_size,
         offset,
         size,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -383,6 +407,7 @@ pub unsafe fn command_fill_image_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandFillImageKHR(
         command_buffer,
         command_queue,
@@ -391,8 +416,8 @@ pub unsafe fn command_fill_image_khr(
         fill_color,
         origin,
         region,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -418,6 +443,7 @@ pub unsafe fn command_nd_range_kernel_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandNDRangeKernelKHR(
         command_buffer,
         command_queue,
@@ -427,8 +453,8 @@ pub unsafe fn command_nd_range_kernel_khr(
         global_work_offset,
         global_work_size,
         local_work_size,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -452,6 +478,7 @@ pub unsafe fn command_svm_memcpy_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandSVMMemcpyKHR(
         command_buffer,
         command_queue,
@@ -459,8 +486,8 @@ pub unsafe fn command_svm_memcpy_khr(
         dst_ptr,
         src_ptr,
         size,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -485,6 +512,7 @@ pub unsafe fn command_svm_mem_fill_khr(
     sync_point: *mut cl_sync_point_khr,
     mutable_handle: *mut cl_mutable_command_khr,
 ) -> Result<(), cl_int> {
+    let (num_sync_points, sync_points) = sync_point_wait_list_parts(sync_point_wait_list);
     let status: cl_int = cl_call!(clCommandSVMMemFillKHR(
         command_buffer,
         command_queue,
@@ -493,8 +521,8 @@ pub unsafe fn command_svm_mem_fill_khr(
         pattern,
         pattern_size,
         size,
-        sync_point_wait_list.len() as cl_uint,
-        sync_point_wait_list.as_ptr(),
+        num_sync_points,
+        sync_points,
         sync_point,
         mutable_handle,
     ));
@@ -1100,6 +1128,36 @@ pub fn get_semaphore_handle_for_type_khr(
     }
 }
 
+/// Get the Linux sync file of a semaphore, -1 if the semaphore is signalled.
+#[cfg(any(
+    all(
+        feature = "cl_khr_external_semaphore",
+        feature = "cl_khr_external_semaphore_sync_fd"
+    ),
+    feature = "dynamic"
+))]
+pub fn get_semaphore_sync_fd_khr(
+    sema_object: cl_semaphore_khr,
+    device: cl_device_id,
+) -> Result<c_int, cl_int> {
+    let mut fd: c_int = -1;
+    let status: cl_int = unsafe {
+        cl_call!(clGetSemaphoreHandleForTypeKHR(
+            sema_object,
+            device,
+            CL_SEMAPHORE_HANDLE_SYNC_FD_KHR,
+            mem::size_of::<c_int>(),
+            (&raw mut fd).cast::<c_void>(),
+            ptr::null_mut(),
+        ))
+    };
+    if CL_SUCCESS == status {
+        Ok(fd)
+    } else {
+        Err(status)
+    }
+}
+
 #[cfg(any(feature = "cl_khr_external_semaphore_sync_fd", feature = "dynamic"))]
 pub unsafe fn reimport_semaphore_sync_fd(
     sema_object: cl_semaphore_khr,
@@ -1531,9 +1589,9 @@ pub unsafe fn host_mem_alloc_intel(
     properties: *const cl_mem_properties_intel,
     size: size_t,
     alignment: cl_uint,
-) -> Result<(), cl_int> {
+) -> Result<*mut c_void, cl_int> {
     let mut status: cl_int = CL_INVALID_VALUE;
-    cl_call!(clHostMemAllocINTEL(
+    let ptr = cl_call!(clHostMemAllocINTEL(
         context,
         properties,
         size,
@@ -1541,7 +1599,7 @@ pub unsafe fn host_mem_alloc_intel(
         &mut status
     ));
     if CL_SUCCESS == status {
-        Ok(())
+        Ok(ptr)
     } else {
         Err(status)
     }
@@ -1554,9 +1612,9 @@ pub unsafe fn device_mem_alloc_intel(
     properties: *const cl_mem_properties_intel,
     size: size_t,
     alignment: cl_uint,
-) -> Result<(), cl_int> {
+) -> Result<*mut c_void, cl_int> {
     let mut status: cl_int = CL_INVALID_VALUE;
-    cl_call!(clDeviceMemAllocINTEL(
+    let ptr = cl_call!(clDeviceMemAllocINTEL(
         context,
         device,
         properties,
@@ -1565,7 +1623,7 @@ pub unsafe fn device_mem_alloc_intel(
         &mut status
     ));
     if CL_SUCCESS == status {
-        Ok(())
+        Ok(ptr)
     } else {
         Err(status)
     }
@@ -1578,9 +1636,9 @@ pub unsafe fn shared_mem_alloc_intel(
     properties: *const cl_mem_properties_intel,
     size: size_t,
     alignment: cl_uint,
-) -> Result<(), cl_int> {
+) -> Result<*mut c_void, cl_int> {
     let mut status: cl_int = CL_INVALID_VALUE;
-    cl_call!(clSharedMemAllocINTEL(
+    let ptr = cl_call!(clSharedMemAllocINTEL(
         context,
         device,
         properties,
@@ -1589,7 +1647,7 @@ pub unsafe fn shared_mem_alloc_intel(
         &mut status
     ));
     if CL_SUCCESS == status {
-        Ok(())
+        Ok(ptr)
     } else {
         Err(status)
     }
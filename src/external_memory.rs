@@ -0,0 +1,289 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `cl_khr_external_memory` import of buffers and images from file descriptors.
+//!
+//! [`import_buffer_from_fd`] and [`import_image_from_fd`] create memory
+//! objects from a Linux dma-buf (`CL_EXTERNAL_MEMORY_HANDLE_DMA_BUF_KHR`) or
+//! an opaque file descriptor (`CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_FD_KHR`).
+//! The file descriptor is only borrowed for the import, it remains owned by
+//! the application.
+//!
+//! External memory objects must be acquired before commands use them and
+//! released afterwards: [`with_external_mem_objects`] brackets the commands
+//! enqueued by a closure with the acquire and release commands.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub use opencl_sys::{
+    CL_DEVICE_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR, CL_EXTERNAL_MEMORY_HANDLE_DMA_BUF_KHR,
+    CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_FD_KHR, CL_MEM_DEVICE_HANDLE_LIST_END_KHR,
+    CL_MEM_DEVICE_HANDLE_LIST_KHR, cl_command_queue, cl_context, cl_device_id, cl_event,
+    cl_external_memory_handle_type_khr, cl_image_desc, cl_image_format, cl_int, cl_mem,
+    cl_mem_flags, cl_mem_properties,
+};
+
+use super::device::get_device_info;
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+use super::enqueue::{
+    enqueue_acquire_external_mem_objects_khr, enqueue_barrier_with_wait_list,
+    enqueue_release_external_mem_objects_khr,
+};
+#[cfg(unix)]
+use super::memory::{create_buffer_with_properties, create_image_with_properties};
+use libc::size_t;
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, BorrowedFd};
+#[cfg(unix)]
+use std::ptr;
+
+/// The external memory handle types that a device can import:
+/// `CL_DEVICE_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR`.
+///
+/// * `device` - the `OpenCL` device.
+///
+/// returns a Result containing the handle types
+/// or the error code from the `OpenCL` C API function.
+pub fn get_import_handle_types(
+    device: cl_device_id,
+) -> Result<Vec<cl_external_memory_handle_type_khr>, cl_int> {
+    Ok(get_device_info(device, CL_DEVICE_EXTERNAL_MEMORY_IMPORT_HANDLE_TYPES_KHR)?.into())
+}
+
+/// Whether a device can import an external memory handle type.
+///
+/// * `device` - the `OpenCL` device.
+/// * `handle_type` - the handle type, e.g. `CL_EXTERNAL_MEMORY_HANDLE_DMA_BUF_KHR`.
+///
+/// returns a Result containing true if the device can import the handle type
+/// or the error code from the `OpenCL` C API function.
+pub fn supports_import_handle_type(
+    device: cl_device_id,
+    handle_type: cl_external_memory_handle_type_khr,
+) -> Result<bool, cl_int> {
+    Ok(get_import_handle_types(device)?.contains(&handle_type))
+}
+
+/// The zero terminated memory properties to import an external memory handle.
+///
+/// * `handle_type` - the handle type, e.g. `CL_EXTERNAL_MEMORY_HANDLE_DMA_BUF_KHR`.
+/// * `handle` - the handle value, e.g. a file descriptor.
+/// * `devices` - the devices that may access the memory object,
+///   or empty for all of the devices in the context.
+///
+/// returns the memory properties.
+#[must_use]
+pub fn external_memory_properties(
+    handle_type: cl_external_memory_handle_type_khr,
+    handle: cl_mem_properties,
+    devices: &[cl_device_id],
+) -> Vec<cl_mem_properties> {
+    let mut properties = vec![cl_mem_properties::from(handle_type), handle];
+    if !devices.is_empty() {
+        properties.push(CL_MEM_DEVICE_HANDLE_LIST_KHR);
+        properties.extend(
+            devices
+                .iter()
+                .map(|device| device.addr() as cl_mem_properties),
+        );
+        properties.push(CL_MEM_DEVICE_HANDLE_LIST_END_KHR);
+    }
+    properties.push(0);
+    properties
+}
+
+/// The memory properties to import a file descriptor.
+#[cfg(unix)]
+#[allow(clippy::cast_sign_loss)]
+fn fd_properties(
+    handle_type: cl_external_memory_handle_type_khr,
+    fd: BorrowedFd,
+    devices: &[cl_device_id],
+) -> Vec<cl_mem_properties> {
+    external_memory_properties(handle_type, fd.as_raw_fd() as cl_mem_properties, devices)
+}
+
+/// Create a buffer object from a file descriptor.
+/// Calls `clCreateBufferWithProperties` with the external memory properties.
+///
+/// * `context` - a valid `OpenCL` context.
+/// * `handle_type` - `CL_EXTERNAL_MEMORY_HANDLE_DMA_BUF_KHR` or
+///   `CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_FD_KHR`.
+/// * `fd` - the file descriptor, it is only borrowed for the import.
+/// * `devices` - the devices that may access the buffer,
+///   or empty for all of the devices in the context.
+/// * `flags` - the buffer usage flags, e.g. `CL_MEM_READ_WRITE`.
+/// * `size` - the size in bytes of the buffer.
+///
+/// returns a Result containing the new `OpenCL` buffer object
+/// or the error code from the `OpenCL` C API function.
+#[cfg(unix)]
+pub fn import_buffer_from_fd(
+    context: cl_context,
+    handle_type: cl_external_memory_handle_type_khr,
+    fd: BorrowedFd,
+    devices: &[cl_device_id],
+    flags: cl_mem_flags,
+    size: size_t,
+) -> Result<cl_mem, cl_int> {
+    let properties = fd_properties(handle_type, fd, devices);
+    unsafe {
+        create_buffer_with_properties(context, properties.as_ptr(), flags, size, ptr::null_mut())
+    }
+}
+
+/// Create an image object from a file descriptor.
+/// Calls `clCreateImageWithProperties` with the external memory properties.
+///
+/// * `context` - a valid `OpenCL` context.
+/// * `handle_type` - `CL_EXTERNAL_MEMORY_HANDLE_DMA_BUF_KHR` or
+///   `CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_FD_KHR`.
+/// * `fd` - the file descriptor, it is only borrowed for the import.
+/// * `devices` - the devices that may access the image,
+///   or empty for all of the devices in the context.
+/// * `flags` - the image usage flags, e.g. `CL_MEM_READ_ONLY`.
+/// * `image_format` - the format of the image.
+/// * `image_desc` - the type and dimensions of the image.
+///
+/// returns a Result containing the new `OpenCL` image object
+/// or the error code from the `OpenCL` C API function.
+#[cfg(unix)]
+pub fn import_image_from_fd(
+    context: cl_context,
+    handle_type: cl_external_memory_handle_type_khr,
+    fd: BorrowedFd,
+    devices: &[cl_device_id],
+    flags: cl_mem_flags,
+    image_format: &cl_image_format,
+    image_desc: &cl_image_desc,
+) -> Result<cl_mem, cl_int> {
+    let properties = fd_properties(handle_type, fd, devices);
+    unsafe {
+        create_image_with_properties(
+            context,
+            properties.as_ptr(),
+            flags,
+            image_format,
+            image_desc,
+            ptr::null_mut(),
+        )
+    }
+}
+
+/// Enqueue commands that use external memory objects between acquire and release commands.
+///
+/// Calls `clEnqueueAcquireExternalMemObjectsKHR` and
+/// `clEnqueueReleaseExternalMemObjectsKHR`.
+///
+/// * `command_queue` - the `OpenCL` command-queue.
+/// * `mem_objects` - the external memory objects.
+/// * `event_wait_list` - the events that must complete before the acquire command.
+/// * `enqueue` - a closure that is passed the event of the acquire command
+///   and enqueues the commands that use the memory objects. It returns the
+///   events that the release command must wait for: e.g. none on an in-order
+///   command-queue. The returned events are owned by this function and
+///   released after the release command has been enqueued: retain an event,
+///   e.g. the acquire event, before returning it.
+///
+/// returns a Result containing the event of the release command or the error
+/// code from the `OpenCL` C API functions or `enqueue`. If `enqueue` fails,
+/// the memory objects are still released after a barrier, so that the release
+/// command waits for any commands that `enqueue` enqueued on `command_queue`
+/// before it failed.
+/// `CL_VERSION_1_2`
+///
+/// # Safety
+///
+/// `command_queue`, `mem_objects` and the events in `event_wait_list` must be
+/// valid `OpenCL` objects and the memory objects must be external memory
+/// objects.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+pub unsafe fn with_external_mem_objects<F>(
+    command_queue: cl_command_queue,
+    mem_objects: &[cl_mem],
+    event_wait_list: &[cl_event],
+    enqueue: F,
+) -> Result<cl_event, cl_int>
+where
+    F: FnOnce(cl_event) -> Result<Vec<cl_event>, cl_int>,
+{
+    let acquired = unsafe {
+        enqueue_acquire_external_mem_objects_khr(command_queue, mem_objects, event_wait_list, true)?
+    }
+    .unwrap_or(std::ptr::null_mut());
+
+    let result = enqueue(acquired);
+    let barrier = if result.is_err() {
+        // The events of the commands that enqueue had enqueued are unknown:
+        // wait for all of the previously enqueued commands.
+        unsafe { enqueue_barrier_with_wait_list(command_queue, &[], true) }
+            .ok()
+            .flatten()
+    } else {
+        None
+    };
+    let fallback = [barrier.unwrap_or(acquired)];
+    let release_wait_list = match &result {
+        Ok(events) => events.as_slice(),
+        Err(_) => &fallback,
+    };
+    let released = unsafe {
+        enqueue_release_external_mem_objects_khr(
+            command_queue,
+            mem_objects,
+            release_wait_list,
+            true,
+        )
+    };
+    unsafe {
+        let _ = super::event::release_event(acquired);
+        if let Some(barrier) = barrier {
+            let _ = super::event::release_event(barrier);
+        }
+        if let Ok(events) = &result {
+            for &event in events {
+                let _ = super::event::release_event(event);
+            }
+        }
+    }
+    result?;
+    Ok(released?.unwrap_or(std::ptr::null_mut()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_memory_properties() {
+        assert_eq!(
+            vec![CL_EXTERNAL_MEMORY_HANDLE_DMA_BUF_KHR.into(), 7, 0],
+            external_memory_properties(CL_EXTERNAL_MEMORY_HANDLE_DMA_BUF_KHR, 7, &[])
+        );
+
+        let device = std::ptr::without_provenance_mut::<libc::c_void>(0x1000).cast();
+        assert_eq!(
+            vec![
+                CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_FD_KHR.into(),
+                3,
+                CL_MEM_DEVICE_HANDLE_LIST_KHR,
+                0x1000,
+                CL_MEM_DEVICE_HANDLE_LIST_END_KHR,
+                0
+            ],
+            external_memory_properties(CL_EXTERNAL_MEMORY_HANDLE_OPAQUE_FD_KHR, 3, &[device])
+        );
+    }
+}
@@ -0,0 +1,739 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Image formats and an image descriptor builder.
+//!
+//! [`channel_order_name`] and [`channel_type_name`] name the values of
+//! `cl_image_format`, e.g. from `get_supported_image_formats`, and
+//! [`element_size`] calculates the size of a pixel of a format.
+//!
+//! [`ImageDesc`] builds the `cl_image_desc` of a 1D, 2D, 3D, array or buffer
+//! image, computes its row and slice pitches and checks its format against
+//! the formats that the context supports before `create_image`.
+
+#![allow(non_camel_case_types, non_upper_case_globals)]
+
+pub use opencl_sys::{
+    CL_A, CL_ABGR, CL_ARGB, CL_BGRA, CL_DEPTH, CL_FLOAT, CL_HALF_FLOAT, CL_INTENSITY, CL_LUMINANCE,
+    CL_MEM_OBJECT_IMAGE1D, CL_MEM_OBJECT_IMAGE1D_ARRAY, CL_MEM_OBJECT_IMAGE1D_BUFFER,
+    CL_MEM_OBJECT_IMAGE2D, CL_MEM_OBJECT_IMAGE2D_ARRAY, CL_MEM_OBJECT_IMAGE3D, CL_NV12_INTEL,
+    CL_NV21_IMG, CL_R, CL_RA, CL_RG, CL_RGB, CL_RGBA, CL_RGBx, CL_RGx, CL_Rx, CL_SIGNED_INT8,
+    CL_SIGNED_INT16, CL_SIGNED_INT32, CL_SNORM_INT8, CL_SNORM_INT16, CL_UNORM_INT_2_101010_EXT,
+    CL_UNORM_INT_101010, CL_UNORM_INT_101010_2, CL_UNORM_INT8, CL_UNORM_INT10X6_EXT,
+    CL_UNORM_INT12X4_EXT, CL_UNORM_INT14X2_EXT, CL_UNORM_INT16, CL_UNORM_SHORT_555,
+    CL_UNORM_SHORT_565, CL_UNSIGNED_INT_RAW10_EXT, CL_UNSIGNED_INT_RAW12_EXT, CL_UNSIGNED_INT8,
+    CL_UNSIGNED_INT10X6_EXT, CL_UNSIGNED_INT12X4_EXT, CL_UNSIGNED_INT14X2_EXT, CL_UNSIGNED_INT16,
+    CL_UNSIGNED_INT32, CL_UYVY_INTEL, CL_VYUY_INTEL, CL_YUYV_INTEL, CL_YV12_IMG, CL_YVYU_INTEL,
+    CL_sBGRA, CL_sRGB, CL_sRGBA, CL_sRGBx, cl_channel_order, cl_channel_type, cl_context,
+    cl_image_desc, cl_image_format, cl_int, cl_mem, cl_mem_flags, cl_mem_object_type, cl_uint,
+};
+
+use super::error_codes::{ClError, error_text};
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+use super::memory::create_image;
+use super::memory::get_supported_image_formats;
+use libc::{c_void, size_t};
+use opencl_sys::{
+    CL_IMAGE_FORMAT_NOT_SUPPORTED, CL_INVALID_IMAGE_DESCRIPTOR, CL_INVALID_IMAGE_FORMAT_DESCRIPTOR,
+};
+use std::mem;
+use std::ptr;
+use thiserror::Error;
+
+/// The name of a channel order, e.g. `"CL_RGBA"`.
+///
+/// returns the name or None if the channel order is unknown.
+#[must_use]
+pub const fn channel_order_name(order: cl_channel_order) -> Option<&'static str> {
+    match order {
+        CL_R => Some("CL_R"),
+        CL_A => Some("CL_A"),
+        CL_RG => Some("CL_RG"),
+        CL_RA => Some("CL_RA"),
+        CL_RGB => Some("CL_RGB"),
+        CL_RGBA => Some("CL_RGBA"),
+        CL_BGRA => Some("CL_BGRA"),
+        CL_ARGB => Some("CL_ARGB"),
+        CL_INTENSITY => Some("CL_INTENSITY"),
+        CL_LUMINANCE => Some("CL_LUMINANCE"),
+        CL_Rx => Some("CL_Rx"),
+        CL_RGx => Some("CL_RGx"),
+        CL_RGBx => Some("CL_RGBx"),
+        CL_DEPTH => Some("CL_DEPTH"),
+        CL_sRGB => Some("CL_sRGB"),
+        CL_sRGBx => Some("CL_sRGBx"),
+        CL_sRGBA => Some("CL_sRGBA"),
+        CL_sBGRA => Some("CL_sBGRA"),
+        CL_ABGR => Some("CL_ABGR"),
+        CL_YUYV_INTEL => Some("CL_YUYV_INTEL"),
+        CL_UYVY_INTEL => Some("CL_UYVY_INTEL"),
+        CL_YVYU_INTEL => Some("CL_YVYU_INTEL"),
+        CL_VYUY_INTEL => Some("CL_VYUY_INTEL"),
+        CL_NV12_INTEL => Some("CL_NV12_INTEL"),
+        CL_NV21_IMG => Some("CL_NV21_IMG"),
+        CL_YV12_IMG => Some("CL_YV12_IMG"),
+        _ => None,
+    }
+}
+
+/// The name of a channel data type, e.g. `"CL_UNORM_INT8"`.
+///
+/// returns the name or None if the channel data type is unknown.
+#[must_use]
+pub const fn channel_type_name(data_type: cl_channel_type) -> Option<&'static str> {
+    match data_type {
+        CL_SNORM_INT8 => Some("CL_SNORM_INT8"),
+        CL_SNORM_INT16 => Some("CL_SNORM_INT16"),
+        CL_UNORM_INT8 => Some("CL_UNORM_INT8"),
+        CL_UNORM_INT16 => Some("CL_UNORM_INT16"),
+        CL_UNORM_SHORT_565 => Some("CL_UNORM_SHORT_565"),
+        CL_UNORM_SHORT_555 => Some("CL_UNORM_SHORT_555"),
+        CL_UNORM_INT_101010 => Some("CL_UNORM_INT_101010"),
+        CL_SIGNED_INT8 => Some("CL_SIGNED_INT8"),
+        CL_SIGNED_INT16 => Some("CL_SIGNED_INT16"),
+        CL_SIGNED_INT32 => Some("CL_SIGNED_INT32"),
+        CL_UNSIGNED_INT8 => Some("CL_UNSIGNED_INT8"),
+        CL_UNSIGNED_INT16 => Some("CL_UNSIGNED_INT16"),
+        CL_UNSIGNED_INT32 => Some("CL_UNSIGNED_INT32"),
+        CL_HALF_FLOAT => Some("CL_HALF_FLOAT"),
+        CL_FLOAT => Some("CL_FLOAT"),
+        CL_UNORM_INT_101010_2 => Some("CL_UNORM_INT_101010_2"),
+        CL_UNSIGNED_INT_RAW10_EXT => Some("CL_UNSIGNED_INT_RAW10_EXT"),
+        CL_UNSIGNED_INT_RAW12_EXT => Some("CL_UNSIGNED_INT_RAW12_EXT"),
+        CL_UNSIGNED_INT10X6_EXT => Some("CL_UNSIGNED_INT10X6_EXT"),
+        CL_UNSIGNED_INT12X4_EXT => Some("CL_UNSIGNED_INT12X4_EXT"),
+        CL_UNSIGNED_INT14X2_EXT => Some("CL_UNSIGNED_INT14X2_EXT"),
+        CL_UNORM_INT10X6_EXT => Some("CL_UNORM_INT10X6_EXT"),
+        CL_UNORM_INT12X4_EXT => Some("CL_UNORM_INT12X4_EXT"),
+        CL_UNORM_INT14X2_EXT => Some("CL_UNORM_INT14X2_EXT"),
+        CL_UNORM_INT_2_101010_EXT => Some("CL_UNORM_INT_2_101010_EXT"),
+        _ => None,
+    }
+}
+
+/// The number of channels of a channel order, including any padding channel.
+///
+/// returns the number of channels or None if the channel order is unknown
+/// or is a planar YUV order.
+#[must_use]
+pub const fn channel_count(order: cl_channel_order) -> Option<size_t> {
+    match order {
+        CL_R | CL_A | CL_INTENSITY | CL_LUMINANCE | CL_DEPTH => Some(1),
+        CL_RG | CL_RA | CL_Rx => Some(2),
+        CL_RGB | CL_RGx | CL_sRGB => Some(3),
+        CL_RGBA | CL_BGRA | CL_ARGB | CL_ABGR | CL_RGBx | CL_sRGBx | CL_sRGBA | CL_sBGRA => Some(4),
+        _ => None,
+    }
+}
+
+/// The size in bytes of a channel of a channel data type.
+///
+/// returns the size or None if the type is unknown or packed, e.g.
+/// `CL_UNORM_SHORT_565`, see [`element_size`].
+#[must_use]
+pub const fn channel_size(data_type: cl_channel_type) -> Option<size_t> {
+    match data_type {
+        CL_SNORM_INT8 | CL_UNORM_INT8 | CL_SIGNED_INT8 | CL_UNSIGNED_INT8 => Some(1),
+        CL_SNORM_INT16
+        | CL_UNORM_INT16
+        | CL_SIGNED_INT16
+        | CL_UNSIGNED_INT16
+        | CL_HALF_FLOAT
+        | CL_UNSIGNED_INT10X6_EXT
+        | CL_UNSIGNED_INT12X4_EXT
+        | CL_UNSIGNED_INT14X2_EXT
+        | CL_UNORM_INT10X6_EXT
+        | CL_UNORM_INT12X4_EXT
+        | CL_UNORM_INT14X2_EXT => Some(2),
+        CL_SIGNED_INT32 | CL_UNSIGNED_INT32 | CL_FLOAT => Some(4),
+        _ => None,
+    }
+}
+
+/// The size in bytes of a pixel of an image format: `CL_IMAGE_ELEMENT_SIZE`.
+///
+/// returns the size or None if the format is not a valid combination of a
+/// known channel order and data type, e.g. the sRGB orders are only valid
+/// with `CL_UNORM_INT8` and `CL_DEPTH` with `CL_UNORM_INT16` or `CL_FLOAT`.
+#[must_use]
+pub const fn element_size(format: &cl_image_format) -> Option<size_t> {
+    let order = format.image_channel_order;
+    match format.image_channel_data_type {
+        CL_UNORM_SHORT_565 | CL_UNORM_SHORT_555 if matches!(order, CL_RGB | CL_RGBx) => Some(2),
+        CL_UNORM_INT_101010 if matches!(order, CL_RGB | CL_RGBx) => Some(4),
+        CL_UNORM_INT_101010_2 if matches!(order, CL_RGBA) => Some(4),
+        CL_UNORM_INT_2_101010_EXT if matches!(order, CL_RGBA | CL_BGRA) => Some(4),
+        data_type => match (channel_count(order), channel_size(data_type)) {
+            // CL_RGB and CL_RGBx are only valid with packed data types
+            (Some(_), Some(_)) if matches!(order, CL_RGB | CL_RGBx) => None,
+            (Some(_), Some(_))
+                if matches!(order, CL_sRGB | CL_sRGBx | CL_sRGBA | CL_sBGRA)
+                    && data_type != CL_UNORM_INT8 =>
+            {
+                None
+            }
+            (Some(_), Some(_))
+                if matches!(order, CL_DEPTH) && !matches!(data_type, CL_UNORM_INT16 | CL_FLOAT) =>
+            {
+                None
+            }
+            (Some(count), Some(size)) => Some(count * size),
+            _ => None,
+        },
+    }
+}
+
+/// A `cl_image_format` of a channel order and data type.
+#[must_use]
+pub const fn image_format(order: cl_channel_order, data_type: cl_channel_type) -> cl_image_format {
+    cl_image_format {
+        image_channel_order: order,
+        image_channel_data_type: data_type,
+    }
+}
+
+/// The reasons that an image format or descriptor may be invalid.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum ImageError {
+    #[error("invalid image format: {order:#x}, {data_type:#x}")]
+    InvalidFormat {
+        order: cl_channel_order,
+        data_type: cl_channel_type,
+    },
+    #[error("image format {order:#x}, {data_type:#x} is not supported")]
+    UnsupportedFormat {
+        order: cl_channel_order,
+        data_type: cl_channel_type,
+    },
+    #[error("the image dimensions must be greater than zero")]
+    ZeroSize,
+    #[error("image pitch {pitch} must be a multiple of {multiple} and at least {min}")]
+    InvalidPitch {
+        pitch: size_t,
+        min: size_t,
+        multiple: size_t,
+    },
+    #[error("the image size overflows size_t")]
+    Overflow,
+    #[error("a 1D buffer image requires a buffer")]
+    MissingBuffer,
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl ImageError {
+    /// The `OpenCL` error code that `create_image` would have returned
+    /// for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::InvalidFormat { .. } => CL_INVALID_IMAGE_FORMAT_DESCRIPTOR,
+            Self::UnsupportedFormat { .. } => CL_IMAGE_FORMAT_NOT_SUPPORTED,
+            Self::ZeroSize | Self::InvalidPitch { .. } | Self::Overflow | Self::MissingBuffer => {
+                CL_INVALID_IMAGE_DESCRIPTOR
+            }
+            Self::Cl(error) => *error,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for ImageError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<ImageError> for ClError {
+    fn from(error: ImageError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// The size in bytes of a pixel of an image format.
+///
+/// returns a Result containing the size or `ImageError::InvalidFormat`.
+fn checked_element_size(format: cl_image_format) -> Result<size_t, ImageError> {
+    element_size(&format).ok_or(ImageError::InvalidFormat {
+        order: format.image_channel_order,
+        data_type: format.image_channel_data_type,
+    })
+}
+
+/// Check that a context supports an image format for an image type and flags.
+/// Calls `clGetSupportedImageFormats`.
+///
+/// * `context` - a valid `OpenCL` context.
+/// * `flags` - the memory flags of the image, e.g. `CL_MEM_READ_ONLY`.
+/// * `image_type` - the image type, e.g. `CL_MEM_OBJECT_IMAGE2D`.
+/// * `format` - the image format.
+///
+/// returns an empty Result, `ImageError::UnsupportedFormat`
+/// or the error code from the `OpenCL` C API function.
+pub fn check_image_format(
+    context: cl_context,
+    flags: cl_mem_flags,
+    image_type: cl_mem_object_type,
+    format: &cl_image_format,
+) -> Result<(), ImageError> {
+    let supported = get_supported_image_formats(context, flags, image_type)?;
+    if supported.iter().any(|f| {
+        f.image_channel_order == format.image_channel_order
+            && f.image_channel_data_type == format.image_channel_data_type
+    }) {
+        Ok(())
+    } else {
+        Err(ImageError::UnsupportedFormat {
+            order: format.image_channel_order,
+            data_type: format.image_channel_data_type,
+        })
+    }
+}
+
+/// A builder of the `cl_image_desc` of an image, see the module documentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImageDesc {
+    image_type: cl_mem_object_type,
+    width: size_t,
+    height: size_t,
+    depth: size_t,
+    array_size: size_t,
+    row_pitch: size_t,
+    slice_pitch: size_t,
+    num_mip_levels: cl_uint,
+    mem_object: cl_mem,
+}
+
+impl ImageDesc {
+    const fn new(
+        image_type: cl_mem_object_type,
+        width: size_t,
+        height: size_t,
+        depth: size_t,
+        array_size: size_t,
+    ) -> Self {
+        Self {
+            image_type,
+            width,
+            height,
+            depth,
+            array_size,
+            row_pitch: 0,
+            slice_pitch: 0,
+            num_mip_levels: 0,
+            mem_object: ptr::null_mut(),
+        }
+    }
+
+    /// A 1D image: `CL_MEM_OBJECT_IMAGE1D`.
+    #[must_use]
+    pub const fn image1d(width: size_t) -> Self {
+        Self::new(CL_MEM_OBJECT_IMAGE1D, width, 0, 0, 0)
+    }
+
+    /// A 1D image created from a buffer: `CL_MEM_OBJECT_IMAGE1D_BUFFER`.
+    #[must_use]
+    pub const fn image1d_buffer(width: size_t, buffer: cl_mem) -> Self {
+        Self::new(CL_MEM_OBJECT_IMAGE1D_BUFFER, width, 0, 0, 0).mem_object(buffer)
+    }
+
+    /// An array of 1D images: `CL_MEM_OBJECT_IMAGE1D_ARRAY`.
+    #[must_use]
+    pub const fn image1d_array(width: size_t, array_size: size_t) -> Self {
+        Self::new(CL_MEM_OBJECT_IMAGE1D_ARRAY, width, 0, 0, array_size)
+    }
+
+    /// A 2D image: `CL_MEM_OBJECT_IMAGE2D`.
+    #[must_use]
+    pub const fn image2d(width: size_t, height: size_t) -> Self {
+        Self::new(CL_MEM_OBJECT_IMAGE2D, width, height, 0, 0)
+    }
+
+    /// An array of 2D images: `CL_MEM_OBJECT_IMAGE2D_ARRAY`.
+    #[must_use]
+    pub const fn image2d_array(width: size_t, height: size_t, array_size: size_t) -> Self {
+        Self::new(CL_MEM_OBJECT_IMAGE2D_ARRAY, width, height, 0, array_size)
+    }
+
+    /// A 3D image: `CL_MEM_OBJECT_IMAGE3D`.
+    #[must_use]
+    pub const fn image3d(width: size_t, height: size_t, depth: size_t) -> Self {
+        Self::new(CL_MEM_OBJECT_IMAGE3D, width, height, depth, 0)
+    }
+
+    /// The buffer of a 1D buffer image, or the buffer or image that a 2D
+    /// image is created from.
+    #[must_use]
+    pub const fn mem_object(mut self, mem_object: cl_mem) -> Self {
+        self.mem_object = mem_object;
+        self
+    }
+
+    /// The row pitch in bytes of the host or buffer data,
+    /// zero to use the minimum row pitch.
+    #[must_use]
+    pub const fn row_pitch(mut self, row_pitch: size_t) -> Self {
+        self.row_pitch = row_pitch;
+        self
+    }
+
+    /// The slice pitch in bytes of the host data,
+    /// zero to use the minimum slice pitch.
+    #[must_use]
+    pub const fn slice_pitch(mut self, slice_pitch: size_t) -> Self {
+        self.slice_pitch = slice_pitch;
+        self
+    }
+
+    /// The number of mip-levels, see `cl_khr_mipmap_image`.
+    #[must_use]
+    pub const fn num_mip_levels(mut self, num_mip_levels: cl_uint) -> Self {
+        self.num_mip_levels = num_mip_levels;
+        self
+    }
+
+    #[must_use]
+    pub const fn image_type(&self) -> cl_mem_object_type {
+        self.image_type
+    }
+
+    /// The number of rows in a slice: 1 for 1D images.
+    const fn rows(&self) -> size_t {
+        match self.image_type {
+            CL_MEM_OBJECT_IMAGE2D | CL_MEM_OBJECT_IMAGE2D_ARRAY | CL_MEM_OBJECT_IMAGE3D => {
+                self.height
+            }
+            _ => 1,
+        }
+    }
+
+    /// The number of slices: the depth of 3D images, the array size of
+    /// image arrays, otherwise 1.
+    const fn slices(&self) -> size_t {
+        match self.image_type {
+            CL_MEM_OBJECT_IMAGE3D => self.depth,
+            CL_MEM_OBJECT_IMAGE1D_ARRAY | CL_MEM_OBJECT_IMAGE2D_ARRAY => self.array_size,
+            _ => 1,
+        }
+    }
+
+    /// The minimum row pitch in bytes: the width times the element size.
+    ///
+    /// returns a Result containing the row pitch, `ImageError::InvalidFormat`
+    /// or `ImageError::Overflow`.
+    pub fn min_row_pitch(&self, format: &cl_image_format) -> Result<size_t, ImageError> {
+        self.width
+            .checked_mul(checked_element_size(*format)?)
+            .ok_or(ImageError::Overflow)
+    }
+
+    /// The row pitch in bytes: the row pitch if set, otherwise the minimum.
+    ///
+    /// returns a Result containing the row pitch, or `ImageError::InvalidPitch`
+    /// if the row pitch is less than the minimum or is not a multiple of the
+    /// element size.
+    pub fn effective_row_pitch(&self, format: &cl_image_format) -> Result<size_t, ImageError> {
+        let element_size = checked_element_size(*format)?;
+        let min = self.min_row_pitch(format)?;
+        checked_pitch(self.row_pitch, min, element_size)
+    }
+
+    /// The slice pitch in bytes: the slice pitch if set, otherwise the
+    /// minimum. The minimum is the row pitch for 1D image arrays and the row
+    /// pitch times the height for 2D image arrays and 3D images.
+    ///
+    /// returns a Result containing the slice pitch, `ImageError::InvalidPitch`
+    /// or `ImageError::Overflow`.
+    pub fn effective_slice_pitch(&self, format: &cl_image_format) -> Result<size_t, ImageError> {
+        let row_pitch = self.effective_row_pitch(format)?;
+        let min = row_pitch
+            .checked_mul(self.rows())
+            .ok_or(ImageError::Overflow)?;
+        match self.image_type {
+            CL_MEM_OBJECT_IMAGE1D_ARRAY | CL_MEM_OBJECT_IMAGE2D_ARRAY | CL_MEM_OBJECT_IMAGE3D => {
+                checked_pitch(self.slice_pitch, min, row_pitch)
+            }
+            _ => Ok(min),
+        }
+    }
+
+    /// Set the row and slice pitches to their effective values, e.g. to
+    /// describe host data.
+    ///
+    /// returns a Result containing the descriptor or an `ImageError`.
+    pub fn with_pitches(self, format: &cl_image_format) -> Result<Self, ImageError> {
+        let row_pitch = self.effective_row_pitch(format)?;
+        let slice_pitch = match self.image_type {
+            CL_MEM_OBJECT_IMAGE1D_ARRAY | CL_MEM_OBJECT_IMAGE2D_ARRAY | CL_MEM_OBJECT_IMAGE3D => {
+                self.effective_slice_pitch(format)?
+            }
+            _ => 0,
+        };
+        Ok(self.row_pitch(row_pitch).slice_pitch(slice_pitch))
+    }
+
+    /// The size in bytes of the host data of the image.
+    ///
+    /// returns a Result containing the size or an `ImageError`.
+    pub fn host_size(&self, format: &cl_image_format) -> Result<size_t, ImageError> {
+        self.effective_slice_pitch(format)?
+            .checked_mul(self.slices())
+            .ok_or(ImageError::Overflow)
+    }
+
+    /// Validate the dimensions, pitches and buffer of the descriptor for a
+    /// format.
+    ///
+    /// returns an empty Result or an `ImageError`, e.g.
+    /// `ImageError::MissingBuffer` if a 1D buffer image has no buffer.
+    pub fn validate(&self, format: &cl_image_format) -> Result<(), ImageError> {
+        if self.width == 0 || self.rows() == 0 || self.slices() == 0 {
+            return Err(ImageError::ZeroSize);
+        }
+        if self.image_type == CL_MEM_OBJECT_IMAGE1D_BUFFER && self.mem_object.is_null() {
+            return Err(ImageError::MissingBuffer);
+        }
+        self.host_size(format).map(|_| ())
+    }
+
+    /// The `cl_image_desc` of the descriptor.
+    #[must_use]
+    pub const fn to_cl_image_desc(&self) -> cl_image_desc {
+        // SAFETY: cl_image_desc is plain data, valid when zeroed.
+        let mut desc: cl_image_desc = unsafe { mem::zeroed() };
+        desc.image_type = self.image_type;
+        desc.image_width = self.width;
+        desc.image_height = self.height;
+        desc.image_depth = self.depth;
+        desc.image_array_size = self.array_size;
+        desc.image_row_pitch = self.row_pitch;
+        desc.image_slice_pitch = self.slice_pitch;
+        desc.num_mip_levels = self.num_mip_levels;
+        // The buffer (or mem_object union) is the last field of both layouts
+        // of cl_image_desc, see the opencl-sys CL_HAS_ANON_UNION feature.
+        unsafe {
+            ptr::from_mut(&mut desc)
+                .cast::<u8>()
+                .add(mem::size_of::<cl_image_desc>() - mem::size_of::<cl_mem>())
+                .cast::<cl_mem>()
+                .write_unaligned(self.mem_object);
+        }
+        desc
+    }
+
+    /// Create an image after validating the descriptor and checking that the
+    /// context supports the format.
+    /// Calls `clGetSupportedImageFormats` and `clCreateImage`.
+    /// `CL_VERSION_1_2`
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `flags` - the memory flags of the image, e.g. `CL_MEM_READ_ONLY`.
+    /// * `format` - the image format.
+    /// * `host_ptr` - the host data of the image, or null.
+    ///
+    /// returns a Result containing the new `OpenCL` image object
+    /// or an `ImageError`.
+    ///
+    /// # Safety
+    ///
+    /// `host_ptr` must be null or valid for [`ImageDesc::host_size`] bytes,
+    /// as required by `flags`.
+    #[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+    pub unsafe fn create(
+        &self,
+        context: cl_context,
+        flags: cl_mem_flags,
+        format: &cl_image_format,
+        host_ptr: *mut c_void,
+    ) -> Result<cl_mem, ImageError> {
+        self.validate(format)?;
+        check_image_format(context, flags, self.image_type, format)?;
+        let desc = self.to_cl_image_desc();
+        Ok(unsafe { create_image(context, flags, format, ptr::from_ref(&desc), host_ptr)? })
+    }
+}
+
+/// A pitch: the pitch if set, otherwise the minimum.
+///
+/// returns a Result containing the pitch, or `ImageError::InvalidPitch` if a
+/// set pitch is less than `min` or not a multiple of `multiple`.
+const fn checked_pitch(pitch: size_t, min: size_t, multiple: size_t) -> Result<size_t, ImageError> {
+    if pitch == 0 {
+        Ok(min)
+    } else if pitch < min || (multiple != 0 && !pitch.is_multiple_of(multiple)) {
+        Err(ImageError::InvalidPitch {
+            pitch,
+            min,
+            multiple,
+        })
+    } else {
+        Ok(pitch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_formats() {
+        assert_eq!(Some("CL_RGBA"), channel_order_name(CL_RGBA));
+        assert_eq!(Some("CL_sBGRA"), channel_order_name(CL_sBGRA));
+        assert_eq!(None, channel_order_name(0));
+        assert_eq!(Some("CL_UNORM_INT8"), channel_type_name(CL_UNORM_INT8));
+        assert_eq!(None, channel_type_name(0));
+
+        assert_eq!(Some(4), element_size(&image_format(CL_RGBA, CL_UNORM_INT8)));
+        assert_eq!(Some(16), element_size(&image_format(CL_RGBA, CL_FLOAT)));
+        assert_eq!(Some(4), element_size(&image_format(CL_RG, CL_HALF_FLOAT)));
+        assert_eq!(
+            Some(2),
+            element_size(&image_format(CL_RGB, CL_UNORM_SHORT_565))
+        );
+        assert_eq!(
+            Some(4),
+            element_size(&image_format(CL_RGBx, CL_UNORM_INT_101010))
+        );
+        assert_eq!(None, element_size(&image_format(CL_RGB, CL_UNORM_INT8)));
+        assert_eq!(
+            None,
+            element_size(&image_format(CL_RGBA, CL_UNORM_SHORT_565))
+        );
+
+        assert_eq!(Some("CL_UYVY_INTEL"), channel_order_name(CL_UYVY_INTEL));
+        assert_eq!(Some("CL_NV21_IMG"), channel_order_name(CL_NV21_IMG));
+        assert_eq!(Some("CL_YV12_IMG"), channel_order_name(CL_YV12_IMG));
+        assert_eq!(
+            Some("CL_UNORM_INT12X4_EXT"),
+            channel_type_name(CL_UNORM_INT12X4_EXT)
+        );
+        assert_eq!(
+            Some("CL_UNORM_INT_2_101010_EXT"),
+            channel_type_name(CL_UNORM_INT_2_101010_EXT)
+        );
+        assert_eq!(Some(2), channel_size(CL_UNORM_INT10X6_EXT));
+        assert_eq!(
+            Some(4),
+            element_size(&image_format(CL_RG, CL_UNORM_INT14X2_EXT))
+        );
+        assert_eq!(
+            Some(4),
+            element_size(&image_format(CL_BGRA, CL_UNORM_INT_2_101010_EXT))
+        );
+
+        // sRGB and depth orders are only valid with some data types
+        assert_eq!(
+            Some(4),
+            element_size(&image_format(CL_sRGBA, CL_UNORM_INT8))
+        );
+        assert_eq!(Some(3), element_size(&image_format(CL_sRGB, CL_UNORM_INT8)));
+        assert_eq!(None, element_size(&image_format(CL_sRGBA, CL_FLOAT)));
+        assert_eq!(None, element_size(&image_format(CL_sBGRA, CL_UNORM_INT16)));
+        assert_eq!(
+            Some(2),
+            element_size(&image_format(CL_DEPTH, CL_UNORM_INT16))
+        );
+        assert_eq!(Some(4), element_size(&image_format(CL_DEPTH, CL_FLOAT)));
+        assert_eq!(None, element_size(&image_format(CL_DEPTH, CL_UNORM_INT8)));
+        assert_eq!(None, element_size(&image_format(CL_DEPTH, CL_HALF_FLOAT)));
+    }
+
+    #[test]
+    fn test_image_desc() {
+        let format = image_format(CL_RGBA, CL_UNORM_INT8);
+
+        let desc = ImageDesc::image2d(100, 50);
+        assert_eq!(Ok(400), desc.effective_row_pitch(&format));
+        assert_eq!(Ok(20_000), desc.host_size(&format));
+        assert_eq!(Ok(()), desc.validate(&format));
+
+        let desc = ImageDesc::image2d(100, 50).row_pitch(512);
+        assert_eq!(Ok(25_600), desc.host_size(&format));
+        assert!(matches!(
+            ImageDesc::image2d(100, 50).row_pitch(402).validate(&format),
+            Err(ImageError::InvalidPitch { pitch: 402, .. })
+        ));
+        assert!(matches!(
+            ImageDesc::image2d(100, 50).row_pitch(396).validate(&format),
+            Err(ImageError::InvalidPitch { min: 400, .. })
+        ));
+
+        let desc = ImageDesc::image3d(16, 8, 4).with_pitches(&format).unwrap();
+        let cl_desc = desc.to_cl_image_desc();
+        assert_eq!(CL_MEM_OBJECT_IMAGE3D, cl_desc.image_type);
+        assert_eq!(64, cl_desc.image_row_pitch);
+        assert_eq!(512, cl_desc.image_slice_pitch);
+        assert_eq!(Ok(2048), desc.host_size(&format));
+
+        let desc = ImageDesc::image1d_array(32, 3);
+        assert_eq!(Ok(128), desc.effective_slice_pitch(&format));
+        assert_eq!(Ok(384), desc.host_size(&format));
+
+        assert_eq!(
+            Err(ImageError::ZeroSize),
+            ImageDesc::image2d(0, 1).validate(&format)
+        );
+        assert_eq!(
+            Err(ImageError::InvalidFormat {
+                order: CL_RGB,
+                data_type: CL_FLOAT
+            }),
+            ImageDesc::image1d(8).validate(&image_format(CL_RGB, CL_FLOAT))
+        );
+    }
+
+    #[test]
+    fn test_image_desc_overflow() {
+        let format = image_format(CL_RGBA, CL_FLOAT);
+
+        let desc = ImageDesc::image1d(size_t::MAX / 8);
+        assert_eq!(Err(ImageError::Overflow), desc.min_row_pitch(&format));
+        assert_eq!(Err(ImageError::Overflow), desc.validate(&format));
+
+        let desc = ImageDesc::image2d(1 << 16, size_t::MAX >> 16);
+        assert_eq!(Ok(1 << 20), desc.min_row_pitch(&format));
+        assert_eq!(
+            Err(ImageError::Overflow),
+            desc.effective_slice_pitch(&format)
+        );
+
+        let desc = ImageDesc::image2d_array(1 << 16, 1 << 16, size_t::MAX >> 24);
+        assert_eq!(Ok(1 << 36), desc.effective_slice_pitch(&format));
+        assert_eq!(Err(ImageError::Overflow), desc.host_size(&format));
+        assert_eq!(Err(ImageError::Overflow), desc.validate(&format));
+        assert_eq!(
+            CL_INVALID_IMAGE_DESCRIPTOR,
+            ImageError::Overflow.error_code()
+        );
+    }
+
+    #[test]
+    fn test_image1d_buffer_desc() {
+        let format = image_format(CL_RGBA, CL_UNORM_INT8);
+
+        let desc = ImageDesc::image1d_buffer(64, ptr::null_mut());
+        assert_eq!(Err(ImageError::MissingBuffer), desc.validate(&format));
+        assert_eq!(
+            CL_INVALID_IMAGE_DESCRIPTOR,
+            ImageError::MissingBuffer.error_code()
+        );
+
+        // The buffer is only stored in the descriptor, it is not accessed.
+        let buffer: cl_mem = ptr::dangling_mut();
+        let desc = ImageDesc::image1d_buffer(64, buffer);
+        assert_eq!(Ok(()), desc.validate(&format));
+        assert_eq!(Ok(256), desc.host_size(&format));
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::image::{channel_order_name, channel_type_name};
 use libc::{intptr_t, size_t};
 use opencl_sys::{
     CL_LUID_SIZE_KHR, CL_UUID_SIZE_KHR, cl_image_format, cl_int, cl_name_version, cl_uchar,
@@ -226,9 +227,15 @@ impl fmt::Display for InfoType {
                 for b in a {
                     s.push('\n');
 
-                    s.push_str(&b.image_channel_order.to_string());
+                    match channel_order_name(b.image_channel_order) {
+                        Some(name) => s.push_str(name),
+                        None => s.push_str(&b.image_channel_order.to_string()),
+                    }
                     s.push_str(": ");
-                    s.push_str(&b.image_channel_data_type.to_string());
+                    match channel_type_name(b.image_channel_data_type) {
+                        Some(name) => s.push_str(name),
+                        None => s.push_str(&b.image_channel_data_type.to_string()),
+                    }
                 }
 
                 write!(f, "{s}")
@@ -0,0 +1,560 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An offline parser for `OpenCL` C kernel signatures.
+//!
+//! [`parse_kernel_signatures`] extracts the `kernel` functions of `OpenCL` C
+//! source without an `OpenCL` runtime. It produces the same [`KernelSignature`]
+//! model as `clGetKernelArgInfo`, so typed launchers can be generated with
+//! [`generate_launchers`](super::kernel_signature::generate_launchers)
+//! at build time, e.g. in a `build.rs`.
+//!
+//! Note: the source is not preprocessed, so kernels declared by macros are not
+//! found and typedef names are not resolved.
+
+#![allow(non_camel_case_types)]
+
+pub use super::kernel_signature::{AccessQualifier, AddressQualifier, KernelArg, KernelSignature};
+pub use opencl_sys::{
+    CL_KERNEL_ARG_TYPE_CONST, CL_KERNEL_ARG_TYPE_PIPE, CL_KERNEL_ARG_TYPE_RESTRICT,
+    CL_KERNEL_ARG_TYPE_VOLATILE, cl_ulong,
+};
+
+use thiserror::Error;
+
+/// The reasons that `OpenCL` C source may fail to parse.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum KernelParseError {
+    #[error("unterminated comment")]
+    UnterminatedComment,
+    #[error("unbalanced '{0}' on line {1}")]
+    Unbalanced(char, usize),
+    #[error("invalid kernel declaration on line {line}: {message}")]
+    InvalidKernel { line: usize, message: &'static str },
+}
+
+/// A token of `OpenCL` C source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    /// An identifier or keyword.
+    Ident(String),
+    /// A number, string or character literal.
+    Literal(String),
+    Punct(char),
+}
+
+/// A token and its line number.
+type Spanned = (Token, usize);
+
+impl Token {
+    fn is_ident(&self, name: &str) -> bool {
+        matches!(self, Self::Ident(ident) if ident == name)
+    }
+
+    const fn is_punct(&self, c: char) -> bool {
+        matches!(self, Self::Punct(punct) if *punct == c)
+    }
+
+    fn text(&self) -> String {
+        match self {
+            Self::Ident(text) | Self::Literal(text) => text.clone(),
+            Self::Punct(c) => c.to_string(),
+        }
+    }
+}
+
+/// Split `OpenCL` C source into tokens, skipping comments and preprocessor directives.
+fn tokenize(source: &str) -> Result<Vec<Spanned>, KernelParseError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 1;
+    let mut at_line_start = true;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if '\n' == c {
+            line += 1;
+            at_line_start = true;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if '/' == c && Some('/') == next {
+            while i < chars.len() && '\n' != chars[i] {
+                i += 1;
+            }
+        } else if '/' == c && Some('*') == next {
+            i += 2;
+            loop {
+                match chars.get(i) {
+                    None => return Err(KernelParseError::UnterminatedComment),
+                    Some('*') if Some(&'/') == chars.get(i + 1) => break,
+                    Some('\n') => line += 1,
+                    _ => {}
+                }
+                i += 1;
+            }
+            i += 2;
+        } else if '#' == c && at_line_start {
+            // Skip the directive, including continuation lines.
+            while i < chars.len() && '\n' != chars[i] {
+                if '\\' == chars[i] && Some(&'\n') == chars.get(i + 1) {
+                    line += 1;
+                    i += 1;
+                }
+                i += 1;
+            }
+        } else {
+            at_line_start = false;
+            let start = i;
+            if c.is_alphabetic() || '_' == c {
+                while i < chars.len() && (chars[i].is_alphanumeric() || '_' == chars[i]) {
+                    i += 1;
+                }
+                let ident = chars[start..i].iter().collect();
+                tokens.push((Token::Ident(ident), line));
+            } else if c.is_ascii_digit() {
+                while i < chars.len() && (chars[i].is_alphanumeric() || '.' == chars[i]) {
+                    i += 1;
+                }
+                let number = chars[start..i].iter().collect();
+                tokens.push((Token::Literal(number), line));
+            } else if '"' == c || '\'' == c {
+                i += 1;
+                while i < chars.len() && c != chars[i] {
+                    if '\\' == chars[i] {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+                i += 1;
+                let literal = chars[start..i.min(chars.len())].iter().collect();
+                tokens.push((Token::Literal(literal), line));
+            } else {
+                tokens.push((Token::Punct(c), line));
+                i += 1;
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Find the index of the token that closes the delimiter at `open`.
+fn matching_close(tokens: &[Spanned], open: usize) -> Result<usize, KernelParseError> {
+    let (open_char, close_char) = match &tokens[open].0 {
+        Token::Punct('(') => ('(', ')'),
+        Token::Punct('{') => ('{', '}'),
+        Token::Punct('[') => ('[', ']'),
+        _ => unreachable!("not an opening delimiter"),
+    };
+    let mut depth = 0_usize;
+    for (index, (token, _)) in tokens.iter().enumerate().skip(open) {
+        if token.is_punct(open_char) {
+            depth += 1;
+        } else if token.is_punct(close_char) {
+            depth -= 1;
+            if 0 == depth {
+                return Ok(index);
+            }
+        }
+    }
+    Err(KernelParseError::Unbalanced(open_char, tokens[open].1))
+}
+
+/// Format tokens as compact source text, e.g. `reqd_work_group_size(64,1,1)`.
+fn tokens_text(tokens: &[Spanned]) -> String {
+    let mut text = String::new();
+    let mut previous_ident = false;
+    for (token, _) in tokens {
+        let ident = !matches!(token, Token::Punct(_));
+        if ident && previous_ident {
+            text.push(' ');
+        }
+        text.push_str(&token.text());
+        previous_ident = ident;
+    }
+    text
+}
+
+/// Remove the `__attribute__((...))` specifiers from tokens.
+///
+/// returns the remaining tokens and the text of each attribute.
+fn split_attributes(tokens: &[Spanned]) -> Result<(Vec<Spanned>, Vec<String>), KernelParseError> {
+    let mut remaining = Vec::new();
+    let mut attributes = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if tokens[i].0.is_ident("__attribute__") {
+            let line = tokens[i].1;
+            if !tokens
+                .get(i + 1)
+                .is_some_and(|(token, _)| token.is_punct('('))
+            {
+                return Err(KernelParseError::InvalidKernel {
+                    line,
+                    message: "expected '(' after __attribute__",
+                });
+            }
+            let close = matching_close(tokens, i + 1)?;
+            // The attribute list is within double parentheses.
+            let inner = tokens
+                .get(i + 3..close.saturating_sub(1))
+                .unwrap_or_default();
+            for attribute in split_top_level(inner) {
+                if !attribute.is_empty() {
+                    attributes.push(tokens_text(attribute));
+                }
+            }
+            i = close + 1;
+        } else {
+            remaining.push(tokens[i].clone());
+            i += 1;
+        }
+    }
+    Ok((remaining, attributes))
+}
+
+/// Split tokens by commas that are not nested in delimiters.
+fn split_top_level(tokens: &[Spanned]) -> Vec<&[Spanned]> {
+    let mut parts = Vec::new();
+    let mut depth = 0_i32;
+    let mut start = 0;
+    for (index, (token, _)) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct('(' | '[' | '{') => depth += 1,
+            Token::Punct(')' | ']' | '}') => depth -= 1,
+            Token::Punct(',') if 0 == depth => {
+                parts.push(&tokens[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&tokens[start..]);
+    parts
+}
+
+/// Normalize a scalar type name as `clGetKernelArgInfo` reports it, e.g. "unsigned int" to "uint".
+fn normalize_type(words: &[String]) -> String {
+    let words: Vec<&str> = words
+        .iter()
+        .map(String::as_str)
+        .filter(|word| "signed" != *word || 1 == words.len())
+        .collect();
+    match words.as_slice() {
+        ["unsigned"] | ["unsigned", "int"] => String::from("uint"),
+        ["unsigned", base @ ("char" | "short" | "long")] => format!("u{base}"),
+        _ => words.join(" "),
+    }
+}
+
+/// Parse a kernel parameter declaration.
+fn parse_param(tokens: &[Spanned], line: usize) -> Result<KernelArg, KernelParseError> {
+    let (tokens, _) = split_attributes(tokens)?;
+    let mut address_qualifier = None;
+    let mut access_qualifier = AccessQualifier::None;
+    let mut pointee_const = false;
+    let mut pointee_volatile = false;
+    let mut restrict = false;
+    let mut pipe = false;
+    let mut pointers = 0;
+    let mut words: Vec<String> = Vec::new();
+
+    for (token, _) in &tokens {
+        match token {
+            Token::Ident(ident) => match ident.trim_start_matches("__") {
+                "global" => address_qualifier = Some(AddressQualifier::Global),
+                "local" => address_qualifier = Some(AddressQualifier::Local),
+                "constant" => address_qualifier = Some(AddressQualifier::Constant),
+                "private" => address_qualifier = Some(AddressQualifier::Private),
+                "read_only" => access_qualifier = AccessQualifier::ReadOnly,
+                "write_only" => access_qualifier = AccessQualifier::WriteOnly,
+                "read_write" => access_qualifier = AccessQualifier::ReadWrite,
+                // const and volatile after a '*' qualify the pointer itself.
+                "const" => pointee_const |= 0 == pointers,
+                "volatile" => pointee_volatile |= 0 == pointers,
+                "restrict" => restrict = true,
+                "pipe" => pipe = true,
+                _ => words.push(ident.clone()),
+            },
+            Token::Punct('*' | '[') => pointers += 1,
+            Token::Punct(']') | Token::Literal(_) => {}
+            Token::Punct(_) => {
+                return Err(KernelParseError::InvalidKernel {
+                    line,
+                    message: "unexpected token in parameter",
+                });
+            }
+        }
+    }
+
+    // The name is the last word, unless the parameter is unnamed.
+    let name = if 1 < words.len() {
+        words.pop().unwrap_or_default()
+    } else {
+        String::new()
+    };
+    if words.is_empty() {
+        return Err(KernelParseError::InvalidKernel {
+            line,
+            message: "parameter without a type",
+        });
+    }
+    let mut type_name = normalize_type(&words);
+    type_name.push_str(&"*".repeat(pointers));
+
+    let is_image = type_name.starts_with("image") && type_name.ends_with("_t");
+    // Images and pipes are global memory objects, read_only by default.
+    let address_qualifier = if is_image || pipe {
+        if AccessQualifier::None == access_qualifier {
+            access_qualifier = AccessQualifier::ReadOnly;
+        }
+        AddressQualifier::Global
+    } else {
+        address_qualifier.unwrap_or(AddressQualifier::Private)
+    };
+
+    let mut type_qualifier: cl_ulong = 0;
+    if 0 < pointers {
+        if pointee_const || AddressQualifier::Constant == address_qualifier {
+            type_qualifier |= cl_ulong::from(CL_KERNEL_ARG_TYPE_CONST);
+        }
+        if pointee_volatile {
+            type_qualifier |= cl_ulong::from(CL_KERNEL_ARG_TYPE_VOLATILE);
+        }
+        if restrict {
+            type_qualifier |= cl_ulong::from(CL_KERNEL_ARG_TYPE_RESTRICT);
+        }
+    }
+    if pipe {
+        type_qualifier |= cl_ulong::from(CL_KERNEL_ARG_TYPE_PIPE);
+    }
+
+    Ok(KernelArg {
+        name,
+        type_name,
+        address_qualifier,
+        access_qualifier,
+        type_qualifier,
+    })
+}
+
+/// Parse a kernel declaration: the tokens from the start of the declaration to its body.
+fn parse_kernel(tokens: &[Spanned]) -> Result<KernelSignature, KernelParseError> {
+    let line = tokens.first().map_or(0, |(_, line)| *line);
+    let (tokens, attributes) = split_attributes(tokens)?;
+    let open = tokens
+        .iter()
+        .position(|(token, _)| token.is_punct('('))
+        .ok_or(KernelParseError::InvalidKernel {
+            line,
+            message: "expected a parameter list",
+        })?;
+    let Some((Token::Ident(name), _)) =
+        open.checked_sub(1)
+            .map(|index| &tokens[index])
+            .filter(|(token, _)| {
+                !["kernel", "__kernel", "void"]
+                    .iter()
+                    .any(|ident| token.is_ident(ident))
+            })
+    else {
+        return Err(KernelParseError::InvalidKernel {
+            line,
+            message: "expected a kernel name",
+        });
+    };
+    let close = matching_close(&tokens, open)?;
+    let params = &tokens[open + 1..close];
+
+    let is_void = 1 == params.len() && params[0].0.is_ident("void");
+    let args = if params.is_empty() || is_void {
+        Vec::new()
+    } else {
+        split_top_level(params)
+            .into_iter()
+            .map(|param| parse_param(param, line))
+            .collect::<Result<_, _>>()?
+    };
+
+    Ok(KernelSignature {
+        name: name.clone(),
+        attributes: attributes.join(" "),
+        args,
+    })
+}
+
+/// Parse the signatures of the kernels in `OpenCL` C source, see the module documentation.
+///
+/// * `source` - the `OpenCL` C source.
+///
+/// returns a Result containing the kernel signatures in source order
+/// or a `KernelParseError`. A kernel that is declared before it is defined
+/// is only returned once.
+pub fn parse_kernel_signatures(source: &str) -> Result<Vec<KernelSignature>, KernelParseError> {
+    let tokens = tokenize(source)?;
+    let mut signatures: Vec<KernelSignature> = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < tokens.len() {
+        let (token, line) = &tokens[i];
+        let end_of_declaration = match token {
+            Token::Punct('{') => {
+                let close = matching_close(&tokens, i)?;
+                Some(close)
+            }
+            Token::Punct(';') => Some(i),
+            Token::Punct(c @ ('}' | ')' | ']')) => {
+                return Err(KernelParseError::Unbalanced(*c, *line));
+            }
+            Token::Punct('(' | '[') => {
+                i = matching_close(&tokens, i)? + 1;
+                continue;
+            }
+            _ => None,
+        };
+
+        if let Some(end) = end_of_declaration {
+            let declaration = &tokens[start..i];
+            if declaration
+                .iter()
+                .any(|(token, _)| token.is_ident("kernel") || token.is_ident("__kernel"))
+            {
+                let signature = parse_kernel(declaration)?;
+                if !signatures.iter().any(|other| other.name == signature.name) {
+                    signatures.push(signature);
+                }
+            }
+            start = end + 1;
+            i = end + 1;
+        } else {
+            i += 1;
+        }
+    }
+    Ok(signatures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel_signature::ArgKind;
+
+    const SOURCE: &str = r#"
+#include "common.h"
+#define TILE 16 \
+    // continued
+
+typedef struct { float x, y; } Point;
+
+/* A helper, not a kernel */
+float scale(float x) { return x * 2.0f; }
+
+__kernel void vector_add(__global const float* restrict a,
+                         global float *b,
+                         __local float* scratch,
+                         const unsigned int n)
+{
+    if (get_global_id(0) < n) { b[0] = a[0]; }
+}
+
+kernel __attribute__((reqd_work_group_size(16, 16, 1), vec_type_hint(float4)))
+void blur(read_only image2d_t input, __write_only image2d_t output, sampler_t s,
+          float4 weights, constant Point* points, pipe int p);
+
+kernel __attribute__((reqd_work_group_size(16, 16, 1), vec_type_hint(float4)))
+void blur(read_only image2d_t input, __write_only image2d_t output, sampler_t s,
+          float4 weights, constant Point* points, pipe int p) {}
+
+__kernel void noop(void) {}
+"#;
+
+    #[test]
+    fn test_parse_kernel_signatures() {
+        let signatures = parse_kernel_signatures(SOURCE).unwrap();
+        let names: Vec<&str> = signatures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(vec!["vector_add", "blur", "noop"], names);
+
+        let vector_add = &signatures[0];
+        assert_eq!("", vector_add.attributes);
+        assert_eq!(4, vector_add.args.len());
+        let a = &vector_add.args[0];
+        assert_eq!("a", a.name);
+        assert_eq!("float*", a.type_name);
+        assert_eq!(AddressQualifier::Global, a.address_qualifier);
+        assert_eq!(
+            cl_ulong::from(CL_KERNEL_ARG_TYPE_CONST | CL_KERNEL_ARG_TYPE_RESTRICT),
+            a.type_qualifier
+        );
+        assert_eq!("global const float* restrict a", a.declaration());
+        assert_eq!(ArgKind::Local, vector_add.args[2].kind());
+        let n = &vector_add.args[3];
+        assert_eq!("uint", n.type_name);
+        assert_eq!(AddressQualifier::Private, n.address_qualifier);
+        // const is only reported for pointers
+        assert_eq!(0, n.type_qualifier);
+
+        let blur = &signatures[1];
+        assert_eq!(
+            "reqd_work_group_size(16,16,1) vec_type_hint(float4)",
+            blur.attributes
+        );
+        let output = &blur.args[1];
+        assert_eq!("image2d_t", output.type_name);
+        assert_eq!(AddressQualifier::Global, output.address_qualifier);
+        assert_eq!(AccessQualifier::WriteOnly, output.access_qualifier);
+        assert_eq!(AccessQualifier::ReadOnly, blur.args[0].access_qualifier);
+        assert_eq!(ArgKind::Sampler, blur.args[2].kind());
+        assert_eq!(
+            ArgKind::Value(String::from("[cl_float; 4]")),
+            blur.args[3].kind()
+        );
+        let points = &blur.args[4];
+        assert_eq!("Point*", points.type_name);
+        assert!(points.is_const());
+        assert_eq!(ArgKind::Pipe, blur.args[5].kind());
+        assert_eq!(AccessQualifier::ReadOnly, blur.args[5].access_qualifier);
+
+        assert!(signatures[2].args.is_empty());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(KernelParseError::UnterminatedComment),
+            parse_kernel_signatures("/* kernel void f() {}")
+        );
+        assert_eq!(
+            Err(KernelParseError::Unbalanced('{', 1)),
+            parse_kernel_signatures("kernel void f() {")
+        );
+        assert_eq!(
+            Err(KernelParseError::Unbalanced('}', 2)),
+            parse_kernel_signatures("void f() {}\n}")
+        );
+        assert!(matches!(
+            parse_kernel_signatures("kernel void (global int* a) {}"),
+            Err(KernelParseError::InvalidKernel { line: 1, .. })
+        ));
+        assert!(parse_kernel_signatures("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_kernel_name_ending_in_kernel() {
+        let signatures =
+            parse_kernel_signatures("kernel void matmul_kernel(global float* a) {}").unwrap();
+        assert_eq!(1, signatures.len());
+        assert_eq!("matmul_kernel", signatures[0].name);
+        assert_eq!("a", signatures[0].args[0].name);
+    }
+}
@@ -0,0 +1,745 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` kernel signatures and launcher generation.
+//!
+//! A [`KernelSignature`] holds the name, attributes and arguments of a kernel
+//! from `clGetKernelInfo` and `clGetKernelArgInfo`. The argument information
+//! is only available if the program was built with `-cl-kernel-arg-info`.
+//!
+//! [`generate_launchers`] generates Rust source code for a typed launcher
+//! struct per kernel, with an `ARG_` constant for the index of each argument
+//! and a typed setter per argument, so that argument indices cannot drift
+//! from the kernel source.
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{
+    CL_KERNEL_ARG_ACCESS_NONE, CL_KERNEL_ARG_ACCESS_READ_ONLY, CL_KERNEL_ARG_ACCESS_READ_WRITE,
+    CL_KERNEL_ARG_ACCESS_WRITE_ONLY, CL_KERNEL_ARG_ADDRESS_CONSTANT, CL_KERNEL_ARG_ADDRESS_GLOBAL,
+    CL_KERNEL_ARG_ADDRESS_LOCAL, CL_KERNEL_ARG_ADDRESS_PRIVATE, CL_KERNEL_ARG_TYPE_CONST,
+    CL_KERNEL_ARG_TYPE_NONE, CL_KERNEL_ARG_TYPE_PIPE, CL_KERNEL_ARG_TYPE_RESTRICT,
+    CL_KERNEL_ARG_TYPE_VOLATILE, cl_int, cl_kernel, cl_kernel_arg_access_qualifier,
+    cl_kernel_arg_address_qualifier, cl_kernel_arg_type_qualifier, cl_program, cl_uint, cl_ulong,
+};
+
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+use super::kernel::{
+    CL_KERNEL_ARG_ACCESS_QUALIFIER, CL_KERNEL_ARG_ADDRESS_QUALIFIER, CL_KERNEL_ARG_NAME,
+    CL_KERNEL_ARG_TYPE_NAME, CL_KERNEL_ARG_TYPE_QUALIFIER, CL_KERNEL_ATTRIBUTES,
+    CL_KERNEL_FUNCTION_NAME, CL_KERNEL_NUM_ARGS, create_kernels_in_program, get_kernel_arg_info,
+    get_kernel_info, release_kernel,
+};
+use std::collections::HashSet;
+use std::fmt::Write;
+use thiserror::Error;
+
+/// The reasons that a kernel launcher may not be generated.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum LauncherError {
+    #[error("kernel {kernel}: arguments {first} and {second} both generate `{identifier}`")]
+    DuplicateIdentifier {
+        kernel: String,
+        first: usize,
+        second: usize,
+        identifier: String,
+    },
+}
+
+/// The address space qualifier of a kernel argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AddressQualifier {
+    Global,
+    Local,
+    Constant,
+    Private,
+}
+
+impl AddressQualifier {
+    /// The `OpenCL` C qualifier.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Local => "local",
+            Self::Constant => "constant",
+            Self::Private => "private",
+        }
+    }
+}
+
+impl From<cl_kernel_arg_address_qualifier> for AddressQualifier {
+    fn from(value: cl_kernel_arg_address_qualifier) -> Self {
+        match value {
+            CL_KERNEL_ARG_ADDRESS_GLOBAL => Self::Global,
+            CL_KERNEL_ARG_ADDRESS_LOCAL => Self::Local,
+            CL_KERNEL_ARG_ADDRESS_CONSTANT => Self::Constant,
+            _ => Self::Private,
+        }
+    }
+}
+
+impl From<AddressQualifier> for cl_kernel_arg_address_qualifier {
+    fn from(value: AddressQualifier) -> Self {
+        match value {
+            AddressQualifier::Global => CL_KERNEL_ARG_ADDRESS_GLOBAL,
+            AddressQualifier::Local => CL_KERNEL_ARG_ADDRESS_LOCAL,
+            AddressQualifier::Constant => CL_KERNEL_ARG_ADDRESS_CONSTANT,
+            AddressQualifier::Private => CL_KERNEL_ARG_ADDRESS_PRIVATE,
+        }
+    }
+}
+
+/// The access qualifier of an image or pipe kernel argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AccessQualifier {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+    None,
+}
+
+impl AccessQualifier {
+    /// The `OpenCL` C qualifier, if any.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read_only",
+            Self::WriteOnly => "write_only",
+            Self::ReadWrite => "read_write",
+            Self::None => "",
+        }
+    }
+}
+
+impl From<cl_kernel_arg_access_qualifier> for AccessQualifier {
+    fn from(value: cl_kernel_arg_access_qualifier) -> Self {
+        match value {
+            CL_KERNEL_ARG_ACCESS_READ_ONLY => Self::ReadOnly,
+            CL_KERNEL_ARG_ACCESS_WRITE_ONLY => Self::WriteOnly,
+            CL_KERNEL_ARG_ACCESS_READ_WRITE => Self::ReadWrite,
+            _ => Self::None,
+        }
+    }
+}
+
+impl From<AccessQualifier> for cl_kernel_arg_access_qualifier {
+    fn from(value: AccessQualifier) -> Self {
+        match value {
+            AccessQualifier::ReadOnly => CL_KERNEL_ARG_ACCESS_READ_ONLY,
+            AccessQualifier::WriteOnly => CL_KERNEL_ARG_ACCESS_WRITE_ONLY,
+            AccessQualifier::ReadWrite => CL_KERNEL_ARG_ACCESS_READ_WRITE,
+            AccessQualifier::None => CL_KERNEL_ARG_ACCESS_NONE,
+        }
+    }
+}
+
+/// The kinds of kernel argument, by how their value is set.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ArgKind {
+    /// A `global` or `constant` pointer: a buffer `cl_mem`.
+    Buffer,
+    /// A `local` pointer: set by size only.
+    Local,
+    /// An image: an image `cl_mem`.
+    Image,
+    /// A pipe: a pipe `cl_mem`.
+    Pipe,
+    /// A `sampler_t`: a `cl_sampler`.
+    Sampler,
+    /// A scalar or vector value, with the Rust type of the value, e.g. `cl_float` or `[cl_float; 4]`.
+    Value(String),
+    /// A struct or another type without a known Rust type.
+    Other,
+}
+
+/// Get the Rust type of an `OpenCL` C scalar type.
+fn scalar_type(type_name: &str) -> Option<&'static str> {
+    Some(match type_name {
+        "char" | "signed char" => "cl_char",
+        "uchar" | "unsigned char" => "cl_uchar",
+        "short" => "cl_short",
+        "ushort" | "unsigned short" => "cl_ushort",
+        "int" => "cl_int",
+        "uint" | "unsigned int" | "unsigned" => "cl_uint",
+        "long" => "cl_long",
+        "ulong" | "unsigned long" => "cl_ulong",
+        "half" => "cl_half",
+        "float" => "cl_float",
+        "double" => "cl_double",
+        _ => return None,
+    })
+}
+
+/// Get the Rust type of an `OpenCL` C scalar or vector type,
+/// e.g. `cl_float` for `float` or `[cl_float; 4]` for `float3`.
+fn value_type(type_name: &str) -> Option<String> {
+    if let Some(scalar) = scalar_type(type_name) {
+        return Some(scalar.to_owned());
+    }
+    let digits = type_name
+        .bytes()
+        .rev()
+        .take_while(u8::is_ascii_digit)
+        .count();
+    let (base, size) = type_name.split_at(type_name.len() - digits);
+    let size: usize = match size.parse().ok()? {
+        // A 3 component vector has the size of a 4 component vector.
+        3 => 4,
+        size @ (2 | 4 | 8 | 16) => size,
+        _ => return None,
+    };
+    Some(format!("[{}; {size}]", scalar_type(base)?))
+}
+
+/// A kernel argument, from `clGetKernelArgInfo`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KernelArg {
+    /// The name of the argument.
+    pub name: String,
+    /// The unqualified type name, e.g. `float*`, `image2d_t` or `uint4`.
+    pub type_name: String,
+    pub address_qualifier: AddressQualifier,
+    pub access_qualifier: AccessQualifier,
+    /// A bitfield of `CL_KERNEL_ARG_TYPE_CONST`, `CL_KERNEL_ARG_TYPE_RESTRICT`,
+    /// `CL_KERNEL_ARG_TYPE_VOLATILE` and `CL_KERNEL_ARG_TYPE_PIPE`.
+    pub type_qualifier: cl_ulong,
+}
+
+impl KernelArg {
+    /// Whether the argument is a pointer.
+    #[must_use]
+    pub fn is_pointer(&self) -> bool {
+        self.type_name.trim_end().ends_with('*')
+    }
+
+    /// Whether the argument is a pipe.
+    #[must_use]
+    pub fn is_pipe(&self) -> bool {
+        self.has_type_qualifier(CL_KERNEL_ARG_TYPE_PIPE)
+    }
+
+    /// Whether the argument is `const` qualified.
+    #[must_use]
+    pub fn is_const(&self) -> bool {
+        self.has_type_qualifier(CL_KERNEL_ARG_TYPE_CONST)
+    }
+
+    /// Whether the argument has a type qualifier, e.g. `CL_KERNEL_ARG_TYPE_RESTRICT`.
+    #[must_use]
+    pub fn has_type_qualifier(&self, qualifier: cl_kernel_arg_type_qualifier) -> bool {
+        0 != self.type_qualifier & cl_ulong::from(qualifier)
+    }
+
+    /// How the value of the argument is set.
+    #[must_use]
+    pub fn kind(&self) -> ArgKind {
+        let type_name = self.type_name.trim();
+        if self.is_pipe() {
+            ArgKind::Pipe
+        } else if self.is_pointer() {
+            if AddressQualifier::Local == self.address_qualifier {
+                ArgKind::Local
+            } else {
+                ArgKind::Buffer
+            }
+        } else if type_name.starts_with("image") && type_name.ends_with("_t") {
+            ArgKind::Image
+        } else if "sampler_t" == type_name {
+            ArgKind::Sampler
+        } else {
+            value_type(type_name).map_or(ArgKind::Other, ArgKind::Value)
+        }
+    }
+
+    /// The `OpenCL` C declaration of the argument, e.g. "global const float* a".
+    #[must_use]
+    pub fn declaration(&self) -> String {
+        let mut declaration = String::new();
+        if AddressQualifier::Private != self.address_qualifier {
+            declaration.push_str(self.address_qualifier.as_str());
+            declaration.push(' ');
+        }
+        if AccessQualifier::None != self.access_qualifier {
+            declaration.push_str(self.access_qualifier.as_str());
+            declaration.push(' ');
+        }
+        if self.is_pipe() {
+            declaration.push_str("pipe ");
+        }
+        if self.is_const() {
+            declaration.push_str("const ");
+        }
+        if self.has_type_qualifier(CL_KERNEL_ARG_TYPE_VOLATILE) {
+            declaration.push_str("volatile ");
+        }
+        declaration.push_str(&self.type_name);
+        if self.has_type_qualifier(CL_KERNEL_ARG_TYPE_RESTRICT) {
+            declaration.push_str(" restrict");
+        }
+        declaration.push(' ');
+        declaration.push_str(&self.name);
+        declaration
+    }
+}
+
+/// The signature of a kernel: its name, attributes and arguments.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KernelSignature {
+    /// The kernel function name.
+    pub name: String,
+    /// The kernel attributes as `CL_KERNEL_ATTRIBUTES`,
+    /// e.g. "`reqd_work_group_size(64,1,1)`".
+    pub attributes: String,
+    pub args: Vec<KernelArg>,
+}
+
+impl KernelSignature {
+    /// Get the signature of a kernel.
+    /// Calls `clGetKernelInfo` and `clGetKernelArgInfo` for every argument.
+    /// `CL_VERSION_1_2`
+    ///
+    /// * `kernel` - the `OpenCL` kernel, its program must have been built
+    ///   with `-cl-kernel-arg-info`.
+    ///
+    /// returns a Result containing the `KernelSignature`
+    /// or the error code from the `OpenCL` C API function,
+    /// `CL_KERNEL_ARG_INFO_NOT_AVAILABLE` if the argument information is not available.
+    #[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+    pub fn get(kernel: cl_kernel) -> Result<Self, cl_int> {
+        let name = get_kernel_info(kernel, CL_KERNEL_FUNCTION_NAME)?.into();
+        let attributes = get_kernel_info(kernel, CL_KERNEL_ATTRIBUTES)?.into();
+        let num_args = get_kernel_info(kernel, CL_KERNEL_NUM_ARGS)?.to_uint();
+        let args = (0..num_args)
+            .map(|index| {
+                Ok(KernelArg {
+                    name: get_kernel_arg_info(kernel, index, CL_KERNEL_ARG_NAME)?.into(),
+                    type_name: get_kernel_arg_info(kernel, index, CL_KERNEL_ARG_TYPE_NAME)?.into(),
+                    address_qualifier: AddressQualifier::from(
+                        get_kernel_arg_info(kernel, index, CL_KERNEL_ARG_ADDRESS_QUALIFIER)?
+                            .to_uint(),
+                    ),
+                    access_qualifier: AccessQualifier::from(
+                        get_kernel_arg_info(kernel, index, CL_KERNEL_ARG_ACCESS_QUALIFIER)?
+                            .to_uint(),
+                    ),
+                    type_qualifier: get_kernel_arg_info(
+                        kernel,
+                        index,
+                        CL_KERNEL_ARG_TYPE_QUALIFIER,
+                    )?
+                    .to_ulong(),
+                })
+            })
+            .collect::<Result<Vec<_>, cl_int>>()?;
+        Ok(Self {
+            name,
+            attributes,
+            args,
+        })
+    }
+
+    /// Get the index of a named argument.
+    #[must_use]
+    pub fn arg_index(&self, name: &str) -> Option<cl_uint> {
+        self.args
+            .iter()
+            .position(|arg| arg.name == name)
+            .and_then(|index| cl_uint::try_from(index).ok())
+    }
+
+    /// Generate the Rust source of a typed launcher struct for the kernel,
+    /// see [`generate_launchers`].
+    ///
+    /// returns the Rust source code or a [`LauncherError`] if two arguments
+    /// generate the same constant or setter, e.g. arguments `a` and `A`.
+    pub fn generate_launcher(&self) -> Result<String, LauncherError> {
+        self.check_identifiers()?;
+        let mut code = String::new();
+        // Writing to a String cannot fail
+        let _ = self.write_launcher(&mut code);
+        Ok(code)
+    }
+
+    /// Check that the argument constants and setters are unique.
+    fn check_identifiers(&self) -> Result<(), LauncherError> {
+        let mut identifiers: Vec<(String, usize)> = Vec::new();
+        for (index, arg) in self.args.iter().enumerate() {
+            identifiers.push((arg_const(arg, index), index));
+            identifiers.push((setter_name(arg, index), index));
+        }
+        let mut seen = HashSet::new();
+        for (identifier, index) in &identifiers {
+            if !seen.insert(identifier) {
+                let first = identifiers
+                    .iter()
+                    .find(|(other, _)| other == identifier)
+                    .map_or(0, |(_, first)| *first);
+                return Err(LauncherError::DuplicateIdentifier {
+                    kernel: self.name.clone(),
+                    first,
+                    second: *index,
+                    identifier: identifier.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn write_launcher(&self, code: &mut String) -> std::fmt::Result {
+        let struct_name = camel_case(&self.name);
+        writeln!(code, "/// A launcher for the `{}` kernel.", self.name)?;
+        if !self.attributes.is_empty() {
+            writeln!(code, "///")?;
+            writeln!(code, "/// Attributes: `{}`", self.attributes)?;
+        }
+        writeln!(code, "#[derive(Debug)]")?;
+        writeln!(code, "pub struct {struct_name} {{")?;
+        writeln!(code, "    kernel: cl3::types::cl_kernel,")?;
+        writeln!(code, "}}")?;
+        writeln!(code)?;
+        writeln!(code, "impl {struct_name} {{")?;
+        writeln!(code, "    /// The kernel function name.")?;
+        writeln!(code, "    pub const NAME: &'static str = {:?};", self.name)?;
+        for (index, arg) in self.args.iter().enumerate() {
+            writeln!(code, "    /// The index of `{}`.", arg.declaration())?;
+            writeln!(
+                code,
+                "    pub const {}: cl3::types::cl_uint = {index};",
+                arg_const(arg, index)
+            )?;
+        }
+        writeln!(code)?;
+        writeln!(code, "    /// Create the kernel from a built program.")?;
+        writeln!(
+            code,
+            "    pub fn new(program: cl3::types::cl_program) -> Result<Self, cl3::types::cl_int> {{"
+        )?;
+        writeln!(
+            code,
+            "        let kernel = cl3::kernel::create_kernel(program, c{:?})?;",
+            self.name
+        )?;
+        writeln!(code, "        Ok(Self {{ kernel }})")?;
+        writeln!(code, "    }}")?;
+        writeln!(code)?;
+        writeln!(code, "    /// The `OpenCL` kernel.")?;
+        writeln!(
+            code,
+            "    pub const fn kernel(&self) -> cl3::types::cl_kernel {{"
+        )?;
+        writeln!(code, "        self.kernel")?;
+        writeln!(code, "    }}")?;
+        for (index, arg) in self.args.iter().enumerate() {
+            writeln!(code)?;
+            write_setter(code, arg, index)?;
+        }
+        writeln!(code, "}}")?;
+        writeln!(code)?;
+        writeln!(code, "impl Drop for {struct_name} {{")?;
+        writeln!(code, "    fn drop(&mut self) {{")?;
+        writeln!(
+            code,
+            "        let _ = unsafe {{ cl3::kernel::release_kernel(self.kernel) }};"
+        )?;
+        writeln!(code, "    }}")?;
+        writeln!(code, "}}")
+    }
+}
+
+/// Convert a kernel name to a Rust type name, e.g. "`vector_add`" to "`VectorAdd`".
+fn camel_case(name: &str) -> String {
+    let mut camel = String::new();
+    for word in name.split('_').filter(|word| !word.is_empty()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    if camel.starts_with(|c: char| c.is_ascii_digit()) || camel.is_empty() {
+        camel.insert_str(0, "Kernel");
+    }
+    camel
+}
+
+/// The identifier of an argument: its name, or "argN" if it has no name.
+fn arg_identifier(arg: &KernelArg, index: usize) -> String {
+    let name: String = arg
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("arg{index}")
+    } else {
+        name.to_lowercase()
+    }
+}
+
+/// The name of the index constant of an argument, e.g. "`ARG_A`".
+fn arg_const(arg: &KernelArg, index: usize) -> String {
+    format!("ARG_{}", arg_identifier(arg, index).to_uppercase())
+}
+
+/// The name of the setter of an argument, e.g. "`set_a`" or "`set_scratch_size`".
+fn setter_name(arg: &KernelArg, index: usize) -> String {
+    let identifier = arg_identifier(arg, index);
+    if ArgKind::Local == arg.kind() {
+        format!("set_{identifier}_size")
+    } else {
+        format!("set_{identifier}")
+    }
+}
+
+/// Write the setter for a kernel argument.
+fn write_setter(code: &mut String, arg: &KernelArg, index: usize) -> std::fmt::Result {
+    let setter = setter_name(arg, index);
+    let index_name = arg_const(arg, index);
+    let declaration = arg.declaration();
+    let value = |rust_type: &str| {
+        format!(
+            "cl3::kernel::set_kernel_arg(self.kernel, Self::{index_name}, \
+             ::std::mem::size_of::<{rust_type}>(), (&raw const value).cast())"
+        )
+    };
+    let (signature, call, is_unsafe) = match arg.kind() {
+        ArgKind::Buffer | ArgKind::Image | ArgKind::Pipe => (
+            format!("{setter}(&self, value: cl3::types::cl_mem)"),
+            value("cl3::types::cl_mem"),
+            false,
+        ),
+        ArgKind::Sampler => (
+            format!("{setter}(&self, value: cl3::types::cl_sampler)"),
+            value("cl3::types::cl_sampler"),
+            false,
+        ),
+        ArgKind::Local => (
+            format!("{setter}(&self, size: usize)"),
+            format!(
+                "cl3::kernel::set_kernel_arg(self.kernel, Self::{index_name}, size, ::std::ptr::null())"
+            ),
+            false,
+        ),
+        ArgKind::Value(rust_type) => {
+            let rust_type = rust_type.replace("cl_", "cl3::types::cl_");
+            (
+                format!("{setter}(&self, value: {rust_type})"),
+                value(&rust_type),
+                false,
+            )
+        }
+        ArgKind::Other => (
+            format!("{setter}<T>(&self, value: &T)"),
+            format!(
+                "cl3::kernel::set_kernel_arg(self.kernel, Self::{index_name}, \
+                 ::std::mem::size_of::<T>(), ::std::ptr::from_ref(value).cast())"
+            ),
+            true,
+        ),
+    };
+
+    writeln!(code, "    /// Set `{declaration}`.")?;
+    if is_unsafe {
+        writeln!(code, "    ///")?;
+        writeln!(code, "    /// # Safety")?;
+        writeln!(code, "    ///")?;
+        writeln!(
+            code,
+            "    /// `T` must have the layout of the `{}` kernel argument.",
+            arg.type_name
+        )?;
+        writeln!(
+            code,
+            "    pub unsafe fn {signature} -> Result<(), cl3::types::cl_int> {{"
+        )?;
+    } else {
+        writeln!(
+            code,
+            "    pub fn {signature} -> Result<(), cl3::types::cl_int> {{"
+        )?;
+    }
+    writeln!(code, "        unsafe {{ {call} }}")?;
+    writeln!(code, "    }}")
+}
+
+/// Generate the Rust source of a typed launcher struct for each kernel.
+///
+/// Each launcher owns a `cl_kernel` created with `create_kernel` and has:
+/// * an `ARG_` constant with the index of each argument, e.g. `ARG_A`,
+/// * a typed setter for each argument, e.g. `set_a(&self, value: cl_mem)`
+///   for a buffer or `set_tile_size(&self, size: usize)` for a local pointer.
+///
+/// The generated code uses the `cl3` crate.
+///
+/// * `signatures` - the kernel signatures.
+///
+/// returns the Rust source code or a [`LauncherError`] if two arguments of
+/// a kernel generate the same constant or setter.
+pub fn generate_launchers(signatures: &[KernelSignature]) -> Result<String, LauncherError> {
+    let mut code = String::from("// Generated from OpenCL kernel signatures, do not edit.\n");
+    for signature in signatures {
+        code.push('\n');
+        code.push_str(&signature.generate_launcher()?);
+    }
+    Ok(code)
+}
+
+/// Get the signatures of all of the kernels in a program.
+/// Calls `clCreateKernelsInProgram` and [`KernelSignature::get`] for each kernel.
+/// `CL_VERSION_1_2`
+///
+/// * `program` - a program built with `-cl-kernel-arg-info`.
+///
+/// returns a Result containing the kernel signatures sorted by name
+/// or the error code from the `OpenCL` C API function.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+pub fn get_program_signatures(program: cl_program) -> Result<Vec<KernelSignature>, cl_int> {
+    let kernels = create_kernels_in_program(program)?;
+    let signatures: Result<Vec<KernelSignature>, cl_int> = kernels
+        .iter()
+        .map(|&kernel| KernelSignature::get(kernel))
+        .collect();
+    for kernel in kernels {
+        unsafe {
+            let _ = release_kernel(kernel);
+        }
+    }
+    let mut signatures = signatures?;
+    signatures.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(signatures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arg(
+        name: &str,
+        type_name: &str,
+        address_qualifier: AddressQualifier,
+        type_qualifier: cl_kernel_arg_type_qualifier,
+    ) -> KernelArg {
+        KernelArg {
+            name: name.to_owned(),
+            type_name: type_name.to_owned(),
+            address_qualifier,
+            access_qualifier: AccessQualifier::None,
+            type_qualifier: cl_ulong::from(type_qualifier),
+        }
+    }
+
+    fn signature() -> KernelSignature {
+        KernelSignature {
+            name: String::from("vector_add"),
+            attributes: String::from("reqd_work_group_size(64,1,1)"),
+            args: vec![
+                arg(
+                    "a",
+                    "float*",
+                    AddressQualifier::Global,
+                    CL_KERNEL_ARG_TYPE_CONST,
+                ),
+                arg("scratch", "float*", AddressQualifier::Local, 0),
+                arg("scale", "float4", AddressQualifier::Private, 0),
+                arg("n", "uint", AddressQualifier::Private, 0),
+                arg("params", "struct Params", AddressQualifier::Private, 0),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_kernel_arg() {
+        let signature = signature();
+        let args = &signature.args;
+        assert_eq!(ArgKind::Buffer, args[0].kind());
+        assert_eq!("global const float* a", args[0].declaration());
+        assert_eq!(ArgKind::Local, args[1].kind());
+        assert_eq!(
+            ArgKind::Value(String::from("[cl_float; 4]")),
+            args[2].kind()
+        );
+        assert_eq!(ArgKind::Value(String::from("cl_uint")), args[3].kind());
+        assert_eq!(ArgKind::Other, args[4].kind());
+        assert_eq!(Some(3), signature.arg_index("n"));
+        assert_eq!(None, signature.arg_index("m"));
+
+        let mut image = arg("image", "image2d_t", AddressQualifier::Global, 0);
+        image.access_qualifier = AccessQualifier::ReadOnly;
+        assert_eq!(ArgKind::Image, image.kind());
+        assert_eq!("global read_only image2d_t image", image.declaration());
+        let pipe = arg(
+            "p",
+            "int",
+            AddressQualifier::Global,
+            CL_KERNEL_ARG_TYPE_PIPE,
+        );
+        assert_eq!(ArgKind::Pipe, pipe.kind());
+
+        assert_eq!(Some(String::from("[cl_int; 4]")), value_type("int3"));
+        assert_eq!(None, value_type("int5"));
+        assert_eq!(None, value_type("bool"));
+    }
+
+    #[test]
+    fn test_generate_launchers() {
+        assert_eq!("VectorAdd", camel_case("vector_add"));
+        assert_eq!("Kernel2d", camel_case("2d"));
+
+        let code = generate_launchers(&[signature()]).unwrap();
+        assert!(code.contains("pub struct VectorAdd {"));
+        assert!(code.contains("/// Attributes: `reqd_work_group_size(64,1,1)`"));
+        assert!(code.contains("pub const NAME: &'static str = \"vector_add\";"));
+        assert!(code.contains("pub const ARG_N: cl3::types::cl_uint = 3;"));
+        assert!(code.contains("Self::ARG_A,"));
+        assert!(code.contains("create_kernel(program, c\"vector_add\")"));
+        assert!(code.contains("pub fn set_a(&self, value: cl3::types::cl_mem)"));
+        assert!(code.contains("pub fn set_scratch_size(&self, size: usize)"));
+        assert!(code.contains("pub fn set_scale(&self, value: [cl3::types::cl_float; 4])"));
+        assert!(code.contains("pub unsafe fn set_params<T>(&self, value: &T)"));
+
+        // An argument named `name` does not clash with the `NAME` constant
+        let mut named = signature();
+        named.args[0].name = String::from("name");
+        let code = named.generate_launcher().unwrap();
+        assert!(code.contains("pub const ARG_NAME: cl3::types::cl_uint = 0;"));
+    }
+
+    #[test]
+    fn test_generate_launcher_duplicate_identifiers() {
+        let mut duplicate = signature();
+        duplicate.args[3].name = String::from("A");
+        assert_eq!(
+            Err(LauncherError::DuplicateIdentifier {
+                kernel: String::from("vector_add"),
+                first: 0,
+                second: 3,
+                identifier: String::from("ARG_A"),
+            }),
+            duplicate.generate_launcher()
+        );
+
+        let mut duplicate = signature();
+        duplicate.args[2].name = String::from("scratch_size");
+        duplicate.args[2].address_qualifier = AddressQualifier::Global;
+        assert!(matches!(
+            generate_launchers(&[duplicate]),
+            Err(LauncherError::DuplicateIdentifier {
+                first: 1,
+                second: 2,
+                ..
+            })
+        ));
+    }
+}
@@ -45,13 +45,57 @@
 //! They contain Rust adapter functions for the `OpenCL` API C functions defined
 //! in those sections with their associated types and constants. The exceptions are:
 //!
+//! * [`build_log`] - contains a parser for program build log diagnostics.
+//! * [`build_options`] - contains a builder for typed program build options
+//!   that can be validated against the `OpenCL` C versions of a device.
+//! * [`command_buffer`] - contains a recorder for `cl_khr_command_buffer`
+//!   command-buffers with typed sync points and a dependency graph.
+//! * [`enqueue`] - contains versions of the enqueue functions that take event
+//!   wait lists as slices and may optionally not create an event.
 //! * [`error_codes`] - contains the `OpenCL` API error codes from cl.h and a function
 //!   (`error_text`) to convert an error code to it's enum name from cl.h.
+//! * [`event_future`] - contains an executor agnostic `Future` for `OpenCL`
+//!   events, driven by event callbacks.
+//! * [`external_memory`] - contains the import of buffers and images from
+//!   dma-buf and opaque file descriptors with `cl_khr_external_memory`.
+//! * [`image`] - contains the names and element sizes of image formats and
+//!   an image descriptor builder.
 //! * [`info_type`] - contains a Rust enum (`InfoType`) to hold the `OpenCL` types
 //!   that can be returned from `OpenCL` "Info" functions, e.g. clGetPlatformInfo,
 //!   clGetDeviceInfo, clGetProgramInfo, etc.
+//! * [`kernel_parser`] - contains an offline parser for the kernel signatures
+//!   in `OpenCL` C source.
+//! * [`kernel_signature`] - contains `KernelSignature`s of the kernels in a
+//!   program and a generator for typed Rust kernel launchers.
 //! * [`macros`] - contains Rust macros to call the `OpenCL` "Info" functions and
 //!   return the appropriate `InfoType` in a Rust Result.
+//! * [`map`] - contains guards for mapped buffer and image regions that are
+//!   unmapped when they are dropped.
+//! * [`mutable_dispatch`] - contains typed updates of
+//!   `cl_khr_command_buffer_mutable_dispatch` commands.
+//! * [`nd_range`] - contains an `NDRange` type to hold the work sizes of a
+//!   kernel launch and validate them against device and kernel limits.
+//! * [`profiling`] - contains a `Profile` of the profiling timestamps of an
+//!   event, a collector to export named profiles as a Chrome trace and a
+//!   correlation of device timestamps with the host clocks.
+//! * [`program_cache`] - contains an on-disk cache of program binaries.
+//! * [`properties`] - contains typed builders and parsers of the zero
+//!   terminated property lists of contexts, queues, memory objects, samplers,
+//!   pipes and semaphores.
+//! * [`rect`] - contains `Origin3` and `Region3` types, pitched host views and
+//!   bounds checked rectangular buffer and image transfers.
+//! * [`semaphore`] - contains an owned `cl_khr_semaphore` semaphore with
+//!   Linux sync file import and export.
+//! * [`source_loader`] - contains a loader for `OpenCL` C source that resolves
+//!   `#include` directives into header programs for `compile_program`.
+//! * [`spec_constants`] - contains typed setters for SPIR-V specialization
+//!   constants, checked against the types declared in the module.
+//! * [`spirv`] - contains a SPIR-V module reader to check modules against the
+//!   SPIR-V support of a device before `create_program_with_il`.
+//! * [`svm`] - contains an owned shared virtual memory vector with map guards
+//!   for coarse grained SVM.
+//! * [`usm`] - contains owned `cl_intel_unified_shared_memory` host, device
+//!   and shared allocations.
 //!
 //! It is vital to call the correct `InfoType` method type when decoding the
 //! result of "Info" functions, since the methods will panic if called with the
@@ -83,6 +127,10 @@ mod dynamic_library;
 #[cfg(not(feature = "dynamic"))]
 mod static_library;
 
+pub mod build_log;
+pub mod build_options;
+#[cfg(any(feature = "cl_khr_command_buffer", feature = "dynamic"))]
+pub mod command_buffer;
 pub mod command_queue;
 pub mod context;
 pub mod d3d10;
@@ -90,18 +138,51 @@ pub mod d3d11;
 pub mod device;
 pub mod dx9_media_sharing;
 pub mod egl;
+pub mod enqueue;
 pub mod error_codes;
 pub mod event;
+pub mod event_future;
 pub mod ext;
+#[cfg(any(
+    all(feature = "CL_VERSION_3_0", feature = "cl_khr_external_memory"),
+    feature = "dynamic"
+))]
+pub mod external_memory;
 pub mod gl;
+pub mod image;
 pub mod info_type;
 pub mod kernel;
+pub mod kernel_parser;
+pub mod kernel_signature;
 #[cfg(any(feature = "cl_loader_layers", feature = "dynamic"))]
 pub mod layer;
 pub mod macros;
+pub mod map;
 pub mod memory;
+#[cfg(any(
+    all(
+        feature = "cl_khr_command_buffer",
+        feature = "cl_khr_command_buffer_mutable_dispatch"
+    ),
+    feature = "dynamic"
+))]
+pub mod mutable_dispatch;
+pub mod nd_range;
 pub mod platform;
+pub mod profiling;
 pub mod program;
+pub mod program_cache;
+pub mod properties;
+pub mod rect;
 pub mod sampler;
+#[cfg(any(feature = "cl_khr_semaphore", feature = "dynamic"))]
+pub mod semaphore;
+pub mod source_loader;
+pub mod spec_constants;
+pub mod spirv;
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "dynamic"))]
+pub mod svm;
+#[cfg(any(feature = "cl_intel_unified_shared_memory", feature = "dynamic"))]
+pub mod usm;
 
 pub mod types;
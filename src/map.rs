@@ -0,0 +1,502 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Guards for mapped buffer and image regions.
+//!
+//! [`map_buffer`] and [`map_image`] map a region of a memory object and
+//! return a guard that enqueues `clEnqueueUnmapMemObject` on the same
+//! command-queue when it is dropped.
+//!
+//! The elements of a [`BufferMap`] are [`Pod`], since a map may expose bytes
+//! that were written by a kernel or, with [`MapWriteInvalidate`], bytes
+//! that are undefined.
+//!
+//! The host access of a map is a type parameter of the guard: e.g. a
+//! [`BufferMap<T, MapRead>`](BufferMap) only provides `&[T]` and a
+//! [`BufferMap<T, MapWrite>`](BufferMap) only provides `&mut [T]`.
+//! An [`ImageMap`] provides the rows of the mapped image region as bytes,
+//! using the row and slice pitches returned by the map.
+//!
+//! If a map is non-blocking, the first access through the guard waits for
+//! the map command to complete.
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{
+    CL_MAP_READ, CL_MAP_WRITE, CL_MAP_WRITE_INVALIDATE_REGION, cl_command_queue, cl_event, cl_int,
+    cl_map_flags, cl_mem,
+};
+
+use super::enqueue::{enqueue_map_buffer, enqueue_map_image, enqueue_unmap_mem_object};
+use super::event::{release_event, wait_for_events};
+use super::memory::{CL_IMAGE_ELEMENT_SIZE, get_image_info};
+use super::types::Pod;
+use libc::{c_void, size_t};
+use opencl_sys::{CL_BLOCKING, CL_INVALID_VALUE, CL_NON_BLOCKING};
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::slice;
+
+/// The host access of a map.
+pub trait MapAccess {
+    /// The `cl_map_flags` of the map.
+    const FLAGS: cl_map_flags;
+}
+
+/// A map access that allows the host to read the mapped region.
+pub trait MapReadable: MapAccess {}
+
+/// A map access that allows the host to write the mapped region.
+pub trait MapWritable: MapAccess {}
+
+/// Map for reading: `CL_MAP_READ`.
+#[derive(Clone, Copy, Debug)]
+pub struct MapRead;
+
+/// Map for writing: `CL_MAP_WRITE`.
+#[derive(Clone, Copy, Debug)]
+pub struct MapWrite;
+
+/// Map for reading and writing: `CL_MAP_READ | CL_MAP_WRITE`.
+#[derive(Clone, Copy, Debug)]
+pub struct MapReadWrite;
+
+/// Map for overwriting the whole region: `CL_MAP_WRITE_INVALIDATE_REGION`.
+///
+/// The contents of the region are undefined until they are written.
+#[derive(Clone, Copy, Debug)]
+pub struct MapWriteInvalidate;
+
+impl MapAccess for MapRead {
+    const FLAGS: cl_map_flags = CL_MAP_READ;
+}
+
+impl MapAccess for MapWrite {
+    const FLAGS: cl_map_flags = CL_MAP_WRITE;
+}
+
+impl MapAccess for MapReadWrite {
+    const FLAGS: cl_map_flags = CL_MAP_READ | CL_MAP_WRITE;
+}
+
+impl MapAccess for MapWriteInvalidate {
+    const FLAGS: cl_map_flags = CL_MAP_WRITE_INVALIDATE_REGION;
+}
+
+impl MapReadable for MapRead {}
+impl MapReadable for MapReadWrite {}
+impl MapWritable for MapWrite {}
+impl MapWritable for MapReadWrite {}
+impl MapWritable for MapWriteInvalidate {}
+
+/// A mapped region of a memory object, unmapped when it is dropped.
+#[derive(Debug)]
+struct Mapping {
+    command_queue: cl_command_queue,
+    memobj: cl_mem,
+    ptr: *mut c_void,
+    /// The event of a non-blocking map command that may not have completed.
+    event: Cell<cl_event>,
+}
+
+impl Mapping {
+    /// Wait for a non-blocking map command to complete.
+    fn wait(&self) -> Result<(), cl_int> {
+        let event = self.event.get();
+        if !event.is_null() {
+            wait_for_events(&[event])?;
+            self.event.set(ptr::null_mut());
+            unsafe { release_event(event)? };
+        }
+        Ok(())
+    }
+
+    /// Enqueue the unmap command, after the map command if it is pending.
+    fn unmap(&mut self) -> Result<(), cl_int> {
+        let event = self.event.replace(ptr::null_mut());
+        let wait_list: &[cl_event] = if event.is_null() { &[] } else { &[event] };
+        let result = unsafe {
+            enqueue_unmap_mem_object(self.command_queue, self.memobj, self.ptr, wait_list, false)
+        };
+        if !event.is_null() {
+            unsafe {
+                let _ = release_event(event);
+            }
+        }
+        self.ptr = ptr::null_mut();
+        result.map(|_| ())
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() {
+            let _ = self.unmap();
+        }
+    }
+}
+
+/// A mapped region of a buffer, see [`map_buffer`].
+#[derive(Debug)]
+pub struct BufferMap<T: Pod, A: MapAccess> {
+    mapping: Mapping,
+    len: usize,
+    _marker: PhantomData<(T, A)>,
+}
+
+impl<T: Pod, A: MapAccess> BufferMap<T, A> {
+    /// The number of mapped elements.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Wait for the map command to complete, if it was non-blocking.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    pub fn wait(&self) -> Result<(), cl_int> {
+        self.mapping.wait()
+    }
+
+    /// Enqueue the unmap command, returning any error.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    pub fn unmap(mut self) -> Result<(), cl_int> {
+        self.mapping.unmap()
+    }
+}
+
+impl<T: Pod, A: MapReadable> BufferMap<T, A> {
+    /// The mapped elements, after the map command has completed.
+    ///
+    /// returns a Result containing the elements
+    /// or the error code from the `OpenCL` C API function.
+    pub fn as_slice(&self) -> Result<&[T], cl_int> {
+        self.wait()?;
+        Ok(unsafe { slice::from_raw_parts(self.mapping.ptr.cast::<T>(), self.len) })
+    }
+}
+
+impl<T: Pod, A: MapWritable> BufferMap<T, A> {
+    /// The mutable mapped elements, after the map command has completed.
+    ///
+    /// returns a Result containing the elements
+    /// or the error code from the `OpenCL` C API function.
+    pub fn as_mut_slice(&mut self) -> Result<&mut [T], cl_int> {
+        self.wait()?;
+        Ok(unsafe { slice::from_raw_parts_mut(self.mapping.ptr.cast::<T>(), self.len) })
+    }
+}
+
+/// Map `len` elements of a buffer from element `offset`.
+/// Calls `clEnqueueMapBuffer` with the flags of `A`.
+///
+/// * `command_queue` - the `OpenCL` command-queue, the unmap is enqueued on it.
+/// * `buffer` - the `OpenCL` buffer.
+/// * `blocking` - whether to wait for the map to complete before returning.
+/// * `offset` - the first element to map.
+/// * `len` - the number of elements to map.
+/// * `event_wait_list` - the events that must complete before the map.
+///
+/// returns a Result containing the guard of the mapped elements
+/// or the error code from the `OpenCL` C API function.
+/// `CL_INVALID_VALUE` if the mapped region is not aligned for `T`.
+///
+/// # Safety
+///
+/// `buffer` must not be released before the guard is dropped.
+pub unsafe fn map_buffer<T: Pod, A: MapAccess>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking: bool,
+    offset: usize,
+    len: usize,
+    event_wait_list: &[cl_event],
+) -> Result<BufferMap<T, A>, cl_int> {
+    let element_size = mem::size_of::<T>();
+    let byte_offset = offset.checked_mul(element_size).ok_or(CL_INVALID_VALUE)?;
+    let size = len.checked_mul(element_size).ok_or(CL_INVALID_VALUE)?;
+
+    let mut mapped_ptr: *mut c_void = ptr::null_mut();
+    let event = unsafe {
+        enqueue_map_buffer(
+            command_queue,
+            buffer,
+            if blocking {
+                CL_BLOCKING
+            } else {
+                CL_NON_BLOCKING
+            },
+            A::FLAGS,
+            byte_offset,
+            size,
+            &mut mapped_ptr,
+            event_wait_list,
+            !blocking,
+        )?
+    };
+    let map = BufferMap {
+        mapping: Mapping {
+            command_queue,
+            memobj: buffer,
+            ptr: mapped_ptr,
+            event: Cell::new(event.unwrap_or(ptr::null_mut())),
+        },
+        len,
+        _marker: PhantomData,
+    };
+    match check_aligned::<T>(mapped_ptr) {
+        Ok(()) => Ok(map),
+        Err(error) => {
+            map.unmap()?;
+            Err(error)
+        }
+    }
+}
+
+/// Check that a mapped pointer is aligned for `T`.
+///
+/// returns an empty Result or `CL_INVALID_VALUE` if it is not aligned.
+fn check_aligned<T>(mapped_ptr: *const c_void) -> Result<(), cl_int> {
+    if mapped_ptr.cast::<T>().is_aligned() {
+        Ok(())
+    } else {
+        Err(CL_INVALID_VALUE)
+    }
+}
+
+/// A mapped region of an image, see [`map_image`].
+#[derive(Debug)]
+pub struct ImageMap<A: MapAccess> {
+    mapping: Mapping,
+    region: [size_t; 3],
+    row_size: size_t,
+    row_pitch: size_t,
+    slice_pitch: size_t,
+    _marker: PhantomData<A>,
+}
+
+impl<A: MapAccess> ImageMap<A> {
+    /// The width, height and depth of the mapped region in pixels.
+    #[must_use]
+    pub const fn region(&self) -> [size_t; 3] {
+        self.region
+    }
+
+    /// The size in bytes of a row of the mapped region.
+    #[must_use]
+    pub const fn row_size(&self) -> size_t {
+        self.row_size
+    }
+
+    /// The number of bytes between the starts of consecutive rows.
+    #[must_use]
+    pub const fn row_pitch(&self) -> size_t {
+        self.row_pitch
+    }
+
+    /// The number of bytes between the starts of consecutive slices,
+    /// zero for 1D and 2D images.
+    #[must_use]
+    pub const fn slice_pitch(&self) -> size_t {
+        self.slice_pitch
+    }
+
+    /// The offset in bytes of a row, if it is within the mapped region.
+    const fn row_offset(&self, y: size_t, z: size_t) -> Result<size_t, cl_int> {
+        if y < self.region[1] && z < self.region[2] {
+            Ok(z * self.slice_pitch + y * self.row_pitch)
+        } else {
+            Err(CL_INVALID_VALUE)
+        }
+    }
+
+    /// Wait for the map command to complete, if it was non-blocking.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    pub fn wait(&self) -> Result<(), cl_int> {
+        self.mapping.wait()
+    }
+
+    /// Enqueue the unmap command, returning any error.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    pub fn unmap(mut self) -> Result<(), cl_int> {
+        self.mapping.unmap()
+    }
+}
+
+impl<A: MapReadable> ImageMap<A> {
+    /// The pixels of a row of the mapped region, after the map command has completed.
+    ///
+    /// * `y` - the row in the mapped region.
+    /// * `z` - the slice in the mapped region, zero for 1D and 2D images.
+    ///
+    /// returns a Result containing the bytes of the row, `CL_INVALID_VALUE`
+    /// if the row is not in the mapped region
+    /// or the error code from the `OpenCL` C API function.
+    pub fn row(&self, y: size_t, z: size_t) -> Result<&[u8], cl_int> {
+        let offset = self.row_offset(y, z)?;
+        self.wait()?;
+        Ok(unsafe {
+            slice::from_raw_parts(self.mapping.ptr.cast::<u8>().add(offset), self.row_size)
+        })
+    }
+}
+
+impl<A: MapWritable> ImageMap<A> {
+    /// The mutable pixels of a row of the mapped region, after the map
+    /// command has completed.
+    ///
+    /// * `y` - the row in the mapped region.
+    /// * `z` - the slice in the mapped region, zero for 1D and 2D images.
+    ///
+    /// returns a Result containing the bytes of the row, `CL_INVALID_VALUE`
+    /// if the row is not in the mapped region
+    /// or the error code from the `OpenCL` C API function.
+    pub fn row_mut(&mut self, y: size_t, z: size_t) -> Result<&mut [u8], cl_int> {
+        let offset = self.row_offset(y, z)?;
+        self.wait()?;
+        Ok(unsafe {
+            slice::from_raw_parts_mut(self.mapping.ptr.cast::<u8>().add(offset), self.row_size)
+        })
+    }
+}
+
+/// Map a region of an image.
+/// Calls `clEnqueueMapImage` with the flags of `A`.
+///
+/// * `command_queue` - the `OpenCL` command-queue, the unmap is enqueued on it.
+/// * `image` - the `OpenCL` image.
+/// * `blocking` - whether to wait for the map to complete before returning.
+/// * `origin` - the x, y, z offset in pixels of the region.
+/// * `region` - the width, height and depth in pixels of the region.
+/// * `event_wait_list` - the events that must complete before the map.
+///
+/// returns a Result containing the guard of the mapped region
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `image` must not be released before the guard is dropped.
+pub unsafe fn map_image<A: MapAccess>(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking: bool,
+    origin: &[size_t; 3],
+    region: &[size_t; 3],
+    event_wait_list: &[cl_event],
+) -> Result<ImageMap<A>, cl_int> {
+    let element_size = get_image_info(image, CL_IMAGE_ELEMENT_SIZE)?.to_size();
+    let row_size = region[0]
+        .checked_mul(element_size)
+        .ok_or(CL_INVALID_VALUE)?;
+
+    let mut row_pitch: size_t = 0;
+    let mut slice_pitch: size_t = 0;
+    let mut mapped_ptr: *mut c_void = ptr::null_mut();
+    let event = unsafe {
+        enqueue_map_image(
+            command_queue,
+            image,
+            if blocking {
+                CL_BLOCKING
+            } else {
+                CL_NON_BLOCKING
+            },
+            A::FLAGS,
+            origin,
+            region,
+            &mut row_pitch,
+            &mut slice_pitch,
+            &mut mapped_ptr,
+            event_wait_list,
+            !blocking,
+        )?
+    };
+    Ok(ImageMap {
+        mapping: Mapping {
+            command_queue,
+            memobj: image,
+            ptr: mapped_ptr,
+            event: Cell::new(event.unwrap_or(ptr::null_mut())),
+        },
+        region: *region,
+        row_size,
+        row_pitch,
+        slice_pitch,
+        _marker: PhantomData,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_access_flags() {
+        assert_eq!(CL_MAP_READ, MapRead::FLAGS);
+        assert_eq!(CL_MAP_WRITE, MapWrite::FLAGS);
+        assert_eq!(CL_MAP_READ | CL_MAP_WRITE, MapReadWrite::FLAGS);
+        assert_eq!(CL_MAP_WRITE_INVALIDATE_REGION, MapWriteInvalidate::FLAGS);
+    }
+
+    #[test]
+    fn test_check_aligned() {
+        let values = [0_u32; 2];
+        let ptr = values.as_ptr().cast::<c_void>();
+        assert!(check_aligned::<u32>(ptr).is_ok());
+        assert!(check_aligned::<u8>(ptr.wrapping_byte_add(1)).is_ok());
+        assert_eq!(
+            CL_INVALID_VALUE,
+            check_aligned::<u32>(ptr.wrapping_byte_add(1)).unwrap_err()
+        );
+        assert_eq!(
+            CL_INVALID_VALUE,
+            check_aligned::<u16>(ptr.wrapping_byte_add(3)).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_image_map_row_offset() {
+        // A null mapped pointer is not unmapped when the guard is dropped.
+        let map = ImageMap::<MapRead> {
+            mapping: Mapping {
+                command_queue: ptr::null_mut(),
+                memobj: ptr::null_mut(),
+                ptr: ptr::null_mut(),
+                event: Cell::new(ptr::null_mut()),
+            },
+            region: [4, 3, 2],
+            row_size: 16,
+            row_pitch: 64,
+            slice_pitch: 256,
+            _marker: PhantomData,
+        };
+        assert_eq!(0, map.row_offset(0, 0).unwrap());
+        assert_eq!(128, map.row_offset(2, 0).unwrap());
+        assert_eq!(256 + 64, map.row_offset(1, 1).unwrap());
+        assert_eq!(256 + 128, map.row_offset(2, 1).unwrap());
+        assert_eq!(CL_INVALID_VALUE, map.row_offset(3, 0).unwrap_err());
+        assert_eq!(CL_INVALID_VALUE, map.row_offset(0, 2).unwrap_err());
+        assert_eq!(CL_INVALID_VALUE, map.row(3, 1).unwrap_err());
+    }
+}
@@ -48,6 +48,7 @@ use super::info_type::InfoType;
 use super::{api_info_size, api_info_value, api_info_vector};
 use libc::{c_void, intptr_t, size_t};
 use std::mem;
+use std::panic::{self, AssertUnwindSafe};
 use std::ptr;
 
 /// Create an `OpenCL` buffer object for a `context`.
@@ -583,6 +584,47 @@ pub unsafe fn set_mem_object_destructor_callback(
     }
 }
 
+/// A boxed closure called by [`set_mem_object_destructor_callback_with_closure`].
+type MemObjectDestructor = Box<dyn FnOnce(cl_mem) + Send>;
+
+/// The memory object destructor trampoline: `user_data` is a
+/// `Box<MemObjectDestructor>` which is consumed.
+extern "C" fn mem_object_destructor_trampoline(memobj: cl_mem, user_data: *mut c_void) {
+    let callback = unsafe { Box::from_raw(user_data.cast::<MemObjectDestructor>()) };
+    // Catch any panic so that it can't unwind across the FFI boundary.
+    let _ = panic::catch_unwind(AssertUnwindSafe(move || callback(memobj)));
+}
+
+/// Register a closure with an `OpenCL` memory object that is called when the
+/// memory object is destroyed.
+/// Calls `clSetMemObjectDestructorCallback` with a callback function that calls the closure.
+///
+/// The closure is dropped after it has been called.
+/// A panic in the closure is caught, it does not unwind into the `OpenCL` library.
+///
+/// * `memobj` - the `OpenCL` memory object.
+/// * `callback` - the closure, called with the memory object when it is destroyed.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+#[inline]
+pub fn set_mem_object_destructor_callback_with_closure<F>(
+    memobj: cl_mem,
+    callback: F,
+) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_mem) + Send + 'static,
+{
+    let user_data = Box::into_raw(Box::new(Box::new(callback) as MemObjectDestructor));
+    unsafe {
+        set_mem_object_destructor_callback(
+            memobj,
+            mem_object_destructor_trampoline,
+            user_data.cast::<c_void>(),
+        )
+    }
+    .inspect_err(|_| drop(unsafe { Box::from_raw(user_data) }))
+}
+
 /// Allocate a shared virtual memory (SVM) buffer that can be shared by the
 /// host and all devices in an `OpenCL` context.
 /// Calls `clSVMAlloc`.
@@ -0,0 +1,548 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed updates of `cl_khr_command_buffer_mutable_dispatch` commands.
+//!
+//! A [`MutableDispatchUpdate`] builds the `cl_mutable_dispatch_config_khr`
+//! of a [`MutableCommand`]: its kernel arguments, SVM arguments, exec info
+//! and work sizes. Each change is checked against the
+//! `CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR` of the command, or the
+//! `CL_DEVICE_MUTABLE_DISPATCH_CAPABILITIES_KHR` of its device if the command
+//! did not specify them, and the work dimension of the command when it is
+//! added to the update.
+//!
+//! [`MutableDispatchInfo`] reads back the current state of a command.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub use opencl_sys::{
+    CL_DEVICE_MUTABLE_DISPATCH_CAPABILITIES_KHR, CL_INVALID_MUTABLE_COMMAND_KHR,
+    CL_INVALID_OPERATION, CL_INVALID_VALUE, CL_MUTABLE_COMMAND_COMMAND_QUEUE_KHR,
+    CL_MUTABLE_COMMAND_PROPERTIES_ARRAY_KHR, CL_MUTABLE_DISPATCH_ARGUMENTS_KHR,
+    CL_MUTABLE_DISPATCH_DIMENSIONS_KHR, CL_MUTABLE_DISPATCH_EXEC_INFO_KHR,
+    CL_MUTABLE_DISPATCH_GLOBAL_OFFSET_KHR, CL_MUTABLE_DISPATCH_GLOBAL_SIZE_KHR,
+    CL_MUTABLE_DISPATCH_GLOBAL_WORK_OFFSET_KHR, CL_MUTABLE_DISPATCH_GLOBAL_WORK_SIZE_KHR,
+    CL_MUTABLE_DISPATCH_KERNEL_KHR, CL_MUTABLE_DISPATCH_LOCAL_SIZE_KHR,
+    CL_MUTABLE_DISPATCH_LOCAL_WORK_SIZE_KHR, CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR,
+    CL_STRUCTURE_TYPE_MUTABLE_DISPATCH_CONFIG_KHR, cl_command_buffer_update_type_khr,
+    cl_command_queue, cl_device_id, cl_int, cl_kernel, cl_mutable_command_info_khr,
+    cl_mutable_command_khr, cl_mutable_dispatch_arg_khr, cl_mutable_dispatch_config_khr,
+    cl_mutable_dispatch_exec_info_khr, cl_mutable_dispatch_fields_khr, cl_uint, cl_ulong,
+};
+
+use super::command_buffer::{CommandBuffer, CommandBufferError, MutableCommand};
+use super::command_queue::{CL_QUEUE_DEVICE, get_command_queue_info};
+use super::device::get_device_data;
+use super::error_codes::{ClError, error_text};
+use super::ext::{get_command_buffer_mutable_dispatch_data, update_mutable_commands_khr};
+use super::nd_range::NDRange;
+use libc::{c_void, size_t};
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use thiserror::Error;
+
+/// The reasons that a [`MutableDispatchUpdate`] may be rejected.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum MutableDispatchError {
+    #[error("field {0:#x} is not in the CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR of the command")]
+    NotUpdatable(cl_mutable_dispatch_fields_khr),
+    #[error("work size has {found} dimensions, the command has {expected}")]
+    DimensionMismatch { expected: usize, found: usize },
+    #[error("mutable command was not recorded in the command-buffer")]
+    ForeignMutableCommand,
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl MutableDispatchError {
+    /// The `OpenCL` error code that `clUpdateMutableCommandsKHR` would have
+    /// returned for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::NotUpdatable(_) => CL_INVALID_OPERATION,
+            Self::DimensionMismatch { .. } => CL_INVALID_VALUE,
+            Self::ForeignMutableCommand => CL_INVALID_MUTABLE_COMMAND_KHR,
+            Self::Cl(error) => *error,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for MutableDispatchError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait
+impl From<CommandBufferError> for MutableDispatchError {
+    fn from(error: CommandBufferError) -> Self {
+        match error {
+            CommandBufferError::ForeignMutableCommand => Self::ForeignMutableCommand,
+            error => Self::Cl(error.error_code()),
+        }
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<MutableDispatchError> for ClError {
+    fn from(error: MutableDispatchError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// Convert info data into `size_t` values.
+fn to_sizes(data: &[u8]) -> Vec<size_t> {
+    data.chunks_exact(mem::size_of::<size_t>())
+        .map(|chunk| {
+            let mut bytes = [0; mem::size_of::<size_t>()];
+            bytes.copy_from_slice(chunk);
+            size_t::from_ne_bytes(bytes)
+        })
+        .collect()
+}
+
+/// The pointer to a list for the `OpenCL` C API: null if the list is empty.
+const fn list_ptr<T>(list: &[T]) -> *const T {
+    if list.is_empty() {
+        ptr::null()
+    } else {
+        list.as_ptr()
+    }
+}
+
+/// Convert info data into `cl_ulong` values.
+fn to_ulongs(data: &[u8]) -> Vec<cl_ulong> {
+    data.chunks_exact(mem::size_of::<cl_ulong>())
+        .filter_map(|chunk| chunk.try_into().ok().map(cl_ulong::from_ne_bytes))
+        .collect()
+}
+
+/// Find the `CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR` in a command
+/// properties array, None if it is not present.
+fn updatable_fields(properties: &[cl_ulong]) -> Option<cl_mutable_dispatch_fields_khr> {
+    properties
+        .chunks_exact(2)
+        .take_while(|pair| 0 != pair[0])
+        .find(|pair| CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR == pair[0])
+        .map(|pair| pair[1])
+}
+
+/// Query the `CL_DEVICE_MUTABLE_DISPATCH_CAPABILITIES_KHR` of the device of
+/// a mutable command: the fields that are updatable if the command was
+/// recorded without `CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR`.
+fn device_capabilities(
+    command: cl_mutable_command_khr,
+) -> Result<cl_mutable_dispatch_fields_khr, cl_int> {
+    let queue =
+        get_command_buffer_mutable_dispatch_data(command, CL_MUTABLE_COMMAND_COMMAND_QUEUE_KHR)?;
+    let queue = to_sizes(&queue)
+        .first()
+        .map_or(ptr::null_mut(), |queue| *queue as cl_command_queue);
+    let device = isize::from(get_command_queue_info(queue, CL_QUEUE_DEVICE)?) as cl_device_id;
+    let capabilities = get_device_data(device, CL_DEVICE_MUTABLE_DISPATCH_CAPABILITIES_KHR)?;
+    Ok(to_ulongs(&capabilities).first().copied().unwrap_or(0))
+}
+
+/// The current state of a mutable dispatch command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MutableDispatchInfo {
+    pub kernel: cl_kernel,
+    pub work_dim: cl_uint,
+    pub global_work_offset: Vec<size_t>,
+    pub global_work_size: Vec<size_t>,
+    pub local_work_size: Vec<size_t>,
+    /// The `CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR` of the command, or the
+    /// `CL_DEVICE_MUTABLE_DISPATCH_CAPABILITIES_KHR` of its device.
+    pub updatable_fields: cl_mutable_dispatch_fields_khr,
+}
+
+impl MutableDispatchInfo {
+    /// Query the state of a mutable dispatch command.
+    /// Calls `clGetMutableCommandInfoKHR`.
+    ///
+    /// returns a Result containing the `MutableDispatchInfo`
+    /// or the error code from the `OpenCL` C API function.
+    pub fn get(command: cl_mutable_command_khr) -> Result<Self, cl_int> {
+        let get_sizes = |param_name: cl_mutable_command_info_khr| {
+            get_command_buffer_mutable_dispatch_data(command, param_name)
+                .map(|data| to_sizes(&data))
+        };
+        let kernel = get_sizes(CL_MUTABLE_DISPATCH_KERNEL_KHR)?
+            .first()
+            .map_or(ptr::null_mut(), |kernel| *kernel as cl_kernel);
+        let work_dim =
+            get_command_buffer_mutable_dispatch_data(command, CL_MUTABLE_DISPATCH_DIMENSIONS_KHR)?;
+        let work_dim = work_dim
+            .get(..mem::size_of::<cl_uint>())
+            .and_then(|bytes| bytes.try_into().ok())
+            .map_or(0, cl_uint::from_ne_bytes);
+        let properties = get_command_buffer_mutable_dispatch_data(
+            command,
+            CL_MUTABLE_COMMAND_PROPERTIES_ARRAY_KHR,
+        )?;
+        let updatable_fields = match updatable_fields(&to_ulongs(&properties)) {
+            Some(fields) => fields,
+            None => device_capabilities(command)?,
+        };
+
+        Ok(Self {
+            kernel,
+            work_dim,
+            global_work_offset: get_sizes(CL_MUTABLE_DISPATCH_GLOBAL_WORK_OFFSET_KHR)?,
+            global_work_size: get_sizes(CL_MUTABLE_DISPATCH_GLOBAL_WORK_SIZE_KHR)?,
+            local_work_size: get_sizes(CL_MUTABLE_DISPATCH_LOCAL_WORK_SIZE_KHR)?,
+            updatable_fields,
+        })
+    }
+}
+
+/// An update of a mutable dispatch command, see the module documentation.
+///
+/// The update borrows the argument and exec info values until it is applied.
+#[derive(Debug)]
+pub struct MutableDispatchUpdate<'a> {
+    command: MutableCommand,
+    updatable_fields: cl_mutable_dispatch_fields_khr,
+    work_dim: usize,
+    args: Vec<cl_mutable_dispatch_arg_khr>,
+    svm_args: Vec<cl_mutable_dispatch_arg_khr>,
+    exec_infos: Vec<cl_mutable_dispatch_exec_info_khr>,
+    global_work_offset: Option<Vec<size_t>>,
+    global_work_size: Option<Vec<size_t>>,
+    local_work_size: Option<Vec<size_t>>,
+    values: PhantomData<&'a c_void>,
+}
+
+impl<'a> MutableDispatchUpdate<'a> {
+    /// Create an update for a mutable command, querying its updatable fields
+    /// and work dimension.
+    ///
+    /// returns a Result containing the new `MutableDispatchUpdate`
+    /// or a `MutableDispatchError`.
+    pub fn new(command: &MutableCommand) -> Result<Self, MutableDispatchError> {
+        let info = MutableDispatchInfo::get(command.handle())?;
+        Ok(Self::with_fields(
+            command,
+            info.updatable_fields,
+            info.work_dim as usize,
+        ))
+    }
+
+    /// Create an update for a mutable command with known updatable fields and
+    /// work dimension, e.g. those used to record the command.
+    #[must_use]
+    pub const fn with_fields(
+        command: &MutableCommand,
+        updatable_fields: cl_mutable_dispatch_fields_khr,
+        work_dim: usize,
+    ) -> Self {
+        Self {
+            command: *command,
+            updatable_fields,
+            work_dim,
+            args: Vec::new(),
+            svm_args: Vec::new(),
+            exec_infos: Vec::new(),
+            global_work_offset: None,
+            global_work_size: None,
+            local_work_size: None,
+            values: PhantomData,
+        }
+    }
+
+    const fn check_field(
+        &self,
+        field: cl_mutable_dispatch_fields_khr,
+    ) -> Result<(), MutableDispatchError> {
+        if field == self.updatable_fields & field {
+            Ok(())
+        } else {
+            Err(MutableDispatchError::NotUpdatable(field))
+        }
+    }
+
+    fn check_sizes(
+        &self,
+        field: cl_mutable_dispatch_fields_khr,
+        sizes: &[size_t],
+    ) -> Result<Vec<size_t>, MutableDispatchError> {
+        self.check_field(field)?;
+        if sizes.len() == self.work_dim {
+            Ok(sizes.to_vec())
+        } else {
+            Err(MutableDispatchError::DimensionMismatch {
+                expected: self.work_dim,
+                found: sizes.len(),
+            })
+        }
+    }
+
+    /// The mutable command that the update applies to.
+    #[must_use]
+    pub const fn command(&self) -> &MutableCommand {
+        &self.command
+    }
+
+    /// Update a kernel argument with a value.
+    pub fn arg<T>(
+        mut self,
+        arg_index: cl_uint,
+        value: &'a T,
+    ) -> Result<Self, MutableDispatchError> {
+        self.check_field(CL_MUTABLE_DISPATCH_ARGUMENTS_KHR)?;
+        self.args.push(cl_mutable_dispatch_arg_khr {
+            arg_index,
+            arg_size: mem::size_of::<T>(),
+            arg_value: ptr::from_ref(value).cast::<c_void>(),
+        });
+        Ok(self)
+    }
+
+    /// Update the size of a `local` memory kernel argument.
+    pub fn arg_local_size(
+        mut self,
+        arg_index: cl_uint,
+        size: size_t,
+    ) -> Result<Self, MutableDispatchError> {
+        self.check_field(CL_MUTABLE_DISPATCH_ARGUMENTS_KHR)?;
+        self.args.push(cl_mutable_dispatch_arg_khr {
+            arg_index,
+            arg_size: size,
+            arg_value: ptr::null(),
+        });
+        Ok(self)
+    }
+
+    /// Update an SVM pointer kernel argument.
+    pub fn svm_arg(
+        mut self,
+        arg_index: cl_uint,
+        svm_ptr: *const c_void,
+    ) -> Result<Self, MutableDispatchError> {
+        self.check_field(CL_MUTABLE_DISPATCH_ARGUMENTS_KHR)?;
+        self.svm_args.push(cl_mutable_dispatch_arg_khr {
+            arg_index,
+            arg_size: 0,
+            arg_value: svm_ptr,
+        });
+        Ok(self)
+    }
+
+    /// Update kernel exec info, e.g. `CL_KERNEL_EXEC_INFO_SVM_PTRS`.
+    pub fn exec_info<T: ?Sized>(
+        mut self,
+        param_name: cl_uint,
+        value: &'a T,
+    ) -> Result<Self, MutableDispatchError> {
+        self.check_field(CL_MUTABLE_DISPATCH_EXEC_INFO_KHR)?;
+        self.exec_infos.push(cl_mutable_dispatch_exec_info_khr {
+            param_name,
+            param_value_size: mem::size_of_val(value),
+            param_value: ptr::from_ref(value).cast::<c_void>(),
+        });
+        Ok(self)
+    }
+
+    /// Update the global work offset.
+    pub fn global_work_offset(mut self, offset: &[size_t]) -> Result<Self, MutableDispatchError> {
+        self.global_work_offset =
+            Some(self.check_sizes(CL_MUTABLE_DISPATCH_GLOBAL_OFFSET_KHR, offset)?);
+        Ok(self)
+    }
+
+    /// Update the global work size.
+    pub fn global_work_size(mut self, global: &[size_t]) -> Result<Self, MutableDispatchError> {
+        self.global_work_size =
+            Some(self.check_sizes(CL_MUTABLE_DISPATCH_GLOBAL_SIZE_KHR, global)?);
+        Ok(self)
+    }
+
+    /// Update the local work size.
+    pub fn local_work_size(mut self, local: &[size_t]) -> Result<Self, MutableDispatchError> {
+        self.local_work_size = Some(self.check_sizes(CL_MUTABLE_DISPATCH_LOCAL_SIZE_KHR, local)?);
+        Ok(self)
+    }
+
+    /// Update the work sizes from an `NDRange`: the global work size and,
+    /// if they are set, the global work offset and local work size.
+    pub fn range(self, range: &NDRange) -> Result<Self, MutableDispatchError> {
+        let mut update = self.global_work_size(range.global())?;
+        if let Some(offset) = range.offset() {
+            update = update.global_work_offset(offset)?;
+        }
+        if let Some(local) = range.local() {
+            update = update.local_work_size(local)?;
+        }
+        Ok(update)
+    }
+
+    /// The `cl_mutable_dispatch_config_khr` of the update, it points into the update.
+    #[allow(clippy::cast_possible_truncation)]
+    fn config(&self) -> cl_mutable_dispatch_config_khr {
+        let sizes_ptr =
+            |sizes: &Option<Vec<size_t>>| sizes.as_ref().map_or(ptr::null(), Vec::as_ptr);
+        cl_mutable_dispatch_config_khr {
+            command: self.command.handle(),
+            num_args: self.args.len() as cl_uint,
+            num_svm_args: self.svm_args.len() as cl_uint,
+            num_exec_infos: self.exec_infos.len() as cl_uint,
+            work_dim: self.work_dim as cl_uint,
+            arg_list: list_ptr(&self.args),
+            arg_svm_list: list_ptr(&self.svm_args),
+            exec_info_list: list_ptr(&self.exec_infos),
+            global_work_offset: sizes_ptr(&self.global_work_offset),
+            global_work_size: sizes_ptr(&self.global_work_size),
+            local_work_size: sizes_ptr(&self.local_work_size),
+        }
+    }
+
+    /// Apply the update to its command, see [`update_mutable_commands`].
+    ///
+    /// # Safety
+    ///
+    /// The argument and exec info values must match the kernel arguments.
+    pub unsafe fn apply(&self, command_buffer: &CommandBuffer) -> Result<(), MutableDispatchError> {
+        unsafe { update_mutable_commands(command_buffer, &[self]) }
+    }
+}
+
+/// Apply updates to mutable commands of a finalized command-buffer.
+/// Calls `clUpdateMutableCommandsKHR`.
+///
+/// * `command_buffer` - the command-buffer that the commands were recorded in.
+/// * `updates` - the updates to apply.
+///
+/// returns an empty Result or a `MutableDispatchError`.
+///
+/// # Safety
+///
+/// The argument and exec info values must match the kernel arguments.
+#[allow(clippy::cast_possible_truncation)]
+pub unsafe fn update_mutable_commands(
+    command_buffer: &CommandBuffer,
+    updates: &[&MutableDispatchUpdate],
+) -> Result<(), MutableDispatchError> {
+    for update in updates {
+        command_buffer.mutable_handle(update.command())?;
+    }
+    if updates.is_empty() {
+        return Ok(());
+    }
+
+    let configs: Vec<cl_mutable_dispatch_config_khr> =
+        updates.iter().map(|update| update.config()).collect();
+    let config_types: Vec<cl_command_buffer_update_type_khr> =
+        vec![CL_STRUCTURE_TYPE_MUTABLE_DISPATCH_CONFIG_KHR; configs.len()];
+    let mut config_ptrs: Vec<*const c_void> = configs
+        .iter()
+        .map(|config| ptr::from_ref(config).cast::<c_void>())
+        .collect();
+    unsafe {
+        update_mutable_commands_khr(
+            command_buffer.get(),
+            configs.len() as cl_uint,
+            config_types.as_ptr(),
+            config_ptrs.as_mut_ptr(),
+        )
+    }?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_updatable_fields() {
+        let properties = [
+            CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR,
+            CL_MUTABLE_DISPATCH_ARGUMENTS_KHR | CL_MUTABLE_DISPATCH_GLOBAL_SIZE_KHR,
+            0,
+        ];
+        assert_eq!(
+            Some(CL_MUTABLE_DISPATCH_ARGUMENTS_KHR | CL_MUTABLE_DISPATCH_GLOBAL_SIZE_KHR),
+            updatable_fields(&properties)
+        );
+        assert_eq!(None, updatable_fields(&[]));
+        assert_eq!(
+            None,
+            updatable_fields(&[0, CL_MUTABLE_DISPATCH_UPDATABLE_FIELDS_KHR, 1])
+        );
+        assert_eq!(
+            vec![64, 1],
+            to_sizes(&[64_usize, 1].map(usize::to_ne_bytes).concat())
+        );
+        assert_eq!(vec![7], to_ulongs(&7_u64.to_ne_bytes()));
+    }
+
+    #[test]
+    fn test_mutable_dispatch_update() {
+        let command = MutableCommand::unrecorded(ptr::null_mut());
+        let fields = CL_MUTABLE_DISPATCH_ARGUMENTS_KHR | CL_MUTABLE_DISPATCH_GLOBAL_SIZE_KHR;
+        let update = MutableDispatchUpdate::with_fields(&command, fields, 2);
+        assert_eq!(&command, update.command());
+        assert_eq!(fields, update.updatable_fields);
+        assert_eq!(2, update.work_dim);
+
+        assert_eq!(
+            Ok(()),
+            update.check_field(CL_MUTABLE_DISPATCH_ARGUMENTS_KHR)
+        );
+        assert_eq!(Ok(()), update.check_field(fields));
+        assert_eq!(
+            Err(MutableDispatchError::NotUpdatable(
+                CL_MUTABLE_DISPATCH_LOCAL_SIZE_KHR
+            )),
+            update.check_field(CL_MUTABLE_DISPATCH_LOCAL_SIZE_KHR)
+        );
+        assert_eq!(
+            Err(MutableDispatchError::NotUpdatable(
+                CL_MUTABLE_DISPATCH_ARGUMENTS_KHR | CL_MUTABLE_DISPATCH_EXEC_INFO_KHR
+            )),
+            update
+                .check_field(CL_MUTABLE_DISPATCH_ARGUMENTS_KHR | CL_MUTABLE_DISPATCH_EXEC_INFO_KHR)
+        );
+
+        let value: cl_uint = 1;
+        let update = update
+            .arg(0, &value)
+            .unwrap()
+            .global_work_size(&[64, 4])
+            .unwrap();
+        assert_eq!(1, update.args.len());
+        assert_eq!(Some(vec![64, 4]), update.global_work_size);
+
+        let error = update.global_work_size(&[64]).unwrap_err();
+        assert_eq!(
+            MutableDispatchError::DimensionMismatch {
+                expected: 2,
+                found: 1
+            },
+            error
+        );
+        assert_eq!(CL_INVALID_VALUE, error.error_code());
+
+        let update = MutableDispatchUpdate::with_fields(&command, fields, 1);
+        let error = update.local_work_size(&[8]).unwrap_err();
+        assert_eq!(
+            MutableDispatchError::NotUpdatable(CL_MUTABLE_DISPATCH_LOCAL_SIZE_KHR),
+            error
+        );
+        assert_eq!(CL_INVALID_OPERATION, error.error_code());
+    }
+}
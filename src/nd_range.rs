@@ -0,0 +1,494 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` `NDRange` kernel launch dimensions.
+//!
+//! An [`NDRange`] holds the global work size, optional global work offset and
+//! optional local work size of a kernel launch. It can be validated against
+//! the [`WorkGroupLimits`] of a kernel on a device before calling
+//! `clEnqueueNDRangeKernel`, so that an invalid launch is reported with the
+//! constraint that failed rather than just `CL_INVALID_WORK_GROUP_SIZE`.
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{
+    CL_INVALID_GLOBAL_OFFSET, CL_INVALID_VALUE, CL_INVALID_WORK_DIMENSION,
+    CL_INVALID_WORK_GROUP_SIZE, CL_INVALID_WORK_ITEM_SIZE, cl_command_queue, cl_device_id,
+    cl_event, cl_int, cl_kernel, cl_uint,
+};
+
+use super::command_queue::{CL_QUEUE_DEVICE, get_command_queue_info};
+use super::device::{
+    CL_DEVICE_MAX_WORK_GROUP_SIZE, CL_DEVICE_MAX_WORK_ITEM_SIZES,
+    CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT, get_device_info,
+};
+use super::enqueue::enqueue_nd_range_kernel;
+use super::error_codes::{ClError, error_text};
+use super::kernel::{CL_KERNEL_WORK_GROUP_SIZE, get_kernel_work_group_info};
+use libc::{intptr_t, size_t};
+use thiserror::Error;
+
+/// The maximum number of work dimensions supported by `OpenCL`.
+pub const MAX_WORK_DIMENSIONS: usize = 3;
+
+/// The reasons that an [`NDRange`] may be rejected.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum NDRangeError {
+    #[error("work dimension {0} is not in the range 1 to 3")]
+    InvalidWorkDimension(usize),
+    #[error("global work offset has {found} dimensions, the global work size has {expected}")]
+    OffsetDimensionMismatch { expected: usize, found: usize },
+    #[error("local work size has {found} dimensions, the global work size has {expected}")]
+    LocalDimensionMismatch { expected: usize, found: usize },
+    #[error(
+        "global work offset {offset} plus global work size {global} overflows in dimension {dim}"
+    )]
+    GlobalOffsetOverflow {
+        dim: usize,
+        global: size_t,
+        offset: size_t,
+    },
+    #[error("local work size is zero in dimension {dim}")]
+    ZeroLocalSize { dim: usize },
+    #[error(
+        "local work size {local} exceeds CL_DEVICE_MAX_WORK_ITEM_SIZES {max} in dimension {dim}"
+    )]
+    WorkItemSizeExceeded {
+        dim: usize,
+        local: size_t,
+        max: size_t,
+    },
+    #[error("work-group size {size} exceeds CL_DEVICE_MAX_WORK_GROUP_SIZE {max}")]
+    DeviceWorkGroupSizeExceeded { size: size_t, max: size_t },
+    #[error("work-group size {size} exceeds CL_KERNEL_WORK_GROUP_SIZE {max}")]
+    KernelWorkGroupSizeExceeded { size: size_t, max: size_t },
+    #[error(
+        "global work size {global} is not a multiple of local work size {local} in dimension {dim} and the device does not support non-uniform work-groups"
+    )]
+    NonUniformWorkGroup {
+        dim: usize,
+        global: size_t,
+        local: size_t,
+    },
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl NDRangeError {
+    /// The `OpenCL` error code that `clEnqueueNDRangeKernel` would have
+    /// returned for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::InvalidWorkDimension(_) => CL_INVALID_WORK_DIMENSION,
+            Self::OffsetDimensionMismatch { .. } | Self::LocalDimensionMismatch { .. } => {
+                CL_INVALID_VALUE
+            }
+            Self::GlobalOffsetOverflow { .. } => CL_INVALID_GLOBAL_OFFSET,
+            Self::WorkItemSizeExceeded { .. } => CL_INVALID_WORK_ITEM_SIZE,
+            Self::ZeroLocalSize { .. }
+            | Self::DeviceWorkGroupSizeExceeded { .. }
+            | Self::KernelWorkGroupSizeExceeded { .. }
+            | Self::NonUniformWorkGroup { .. } => CL_INVALID_WORK_GROUP_SIZE,
+            Self::Cl(error) => *error,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for NDRangeError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<NDRangeError> for ClError {
+    fn from(error: NDRangeError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// The work-group limits of a kernel on a specific device.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WorkGroupLimits {
+    /// `CL_DEVICE_MAX_WORK_ITEM_SIZES`
+    pub max_work_item_sizes: Vec<size_t>,
+    /// `CL_DEVICE_MAX_WORK_GROUP_SIZE`
+    pub max_device_work_group_size: size_t,
+    /// `CL_KERNEL_WORK_GROUP_SIZE`
+    pub max_kernel_work_group_size: size_t,
+    /// `CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT`
+    pub non_uniform_work_group_support: bool,
+}
+
+impl WorkGroupLimits {
+    /// Query the work-group limits of a kernel on a device.
+    ///
+    /// `CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT` is a `CL_VERSION_3_0` query,
+    /// if the device does not support it, non-uniform work-groups are
+    /// assumed to be unsupported.
+    ///
+    /// * `kernel` - the `OpenCL` kernel.
+    /// * `device` - a specific device in the list of devices associated with kernel.
+    ///
+    /// returns a Result containing the `WorkGroupLimits`
+    /// or the error code from the `OpenCL` C API function.
+    pub fn query(kernel: cl_kernel, device: cl_device_id) -> Result<Self, cl_int> {
+        let max_work_item_sizes =
+            Vec::<size_t>::from(get_device_info(device, CL_DEVICE_MAX_WORK_ITEM_SIZES)?);
+        let max_device_work_group_size =
+            size_t::from(get_device_info(device, CL_DEVICE_MAX_WORK_GROUP_SIZE)?);
+        let max_kernel_work_group_size = size_t::from(get_kernel_work_group_info(
+            kernel,
+            device,
+            CL_KERNEL_WORK_GROUP_SIZE,
+        )?);
+        let non_uniform_work_group_support =
+            get_device_info(device, CL_DEVICE_NON_UNIFORM_WORK_GROUP_SUPPORT)
+                .is_ok_and(|value| 0 != cl_uint::from(value));
+        Ok(Self {
+            max_work_item_sizes,
+            max_device_work_group_size,
+            max_kernel_work_group_size,
+            non_uniform_work_group_support,
+        })
+    }
+}
+
+/// The global work size, global work offset and local work size of an
+/// `OpenCL` kernel launch in 1, 2 or 3 dimensions.
+///
+/// A missing global work offset is treated as zero by `OpenCL`, and a missing
+/// local work size is chosen by the `OpenCL` implementation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NDRange {
+    work_dim: usize,
+    global: [size_t; MAX_WORK_DIMENSIONS],
+    offset: Option<[size_t; MAX_WORK_DIMENSIONS]>,
+    local: Option<[size_t; MAX_WORK_DIMENSIONS]>,
+}
+
+/// Copy 1 to 3 sizes into a 3 dimensional array, padded with ones.
+fn to_dims(sizes: &[size_t]) -> Result<[size_t; MAX_WORK_DIMENSIONS], NDRangeError> {
+    if sizes.is_empty() || MAX_WORK_DIMENSIONS < sizes.len() {
+        return Err(NDRangeError::InvalidWorkDimension(sizes.len()));
+    }
+    let mut dims = [1; MAX_WORK_DIMENSIONS];
+    dims[..sizes.len()].copy_from_slice(sizes);
+    Ok(dims)
+}
+
+impl NDRange {
+    /// Create an `NDRange` from a global work size of 1 to 3 dimensions.
+    ///
+    /// * `global` - the global work size in each dimension.
+    ///
+    /// returns a Result containing the new `NDRange`
+    /// or `NDRangeError::InvalidWorkDimension`.
+    pub fn new(global: &[size_t]) -> Result<Self, NDRangeError> {
+        Ok(Self {
+            work_dim: global.len(),
+            global: to_dims(global)?,
+            offset: None,
+            local: None,
+        })
+    }
+
+    /// Set the global work offset, it must have the same number of
+    /// dimensions as the global work size.
+    pub fn with_offset(mut self, offset: &[size_t]) -> Result<Self, NDRangeError> {
+        if offset.len() != self.work_dim {
+            return Err(NDRangeError::OffsetDimensionMismatch {
+                expected: self.work_dim,
+                found: offset.len(),
+            });
+        }
+        self.offset = Some(to_dims(offset)?);
+        Ok(self)
+    }
+
+    /// Set the local work size, it must have the same number of
+    /// dimensions as the global work size.
+    pub fn with_local(mut self, local: &[size_t]) -> Result<Self, NDRangeError> {
+        if local.len() != self.work_dim {
+            return Err(NDRangeError::LocalDimensionMismatch {
+                expected: self.work_dim,
+                found: local.len(),
+            });
+        }
+        self.local = Some(to_dims(local)?);
+        Ok(self)
+    }
+
+    /// The number of work dimensions: 1, 2 or 3.
+    #[must_use]
+    pub const fn work_dim(&self) -> usize {
+        self.work_dim
+    }
+
+    /// The global work size in each work dimension.
+    #[must_use]
+    pub fn global(&self) -> &[size_t] {
+        &self.global[..self.work_dim]
+    }
+
+    /// The global work offset in each work dimension, if set.
+    #[must_use]
+    pub fn offset(&self) -> Option<&[size_t]> {
+        self.offset.as_ref().map(|offset| &offset[..self.work_dim])
+    }
+
+    /// The local work size in each work dimension, if set.
+    #[must_use]
+    pub fn local(&self) -> Option<&[size_t]> {
+        self.local.as_ref().map(|local| &local[..self.work_dim])
+    }
+
+    /// The number of work-items in a work-group, if the local work size is set.
+    #[must_use]
+    pub fn work_group_size(&self) -> Option<size_t> {
+        self.local().map(|local| {
+            local
+                .iter()
+                .fold(1, |size: size_t, &l| size.saturating_mul(l))
+        })
+    }
+
+    /// Validate the `NDRange` against the work-group limits of a kernel on a device.
+    ///
+    /// * `limits` - the work-group limits, see [`WorkGroupLimits::query`].
+    ///
+    /// returns an empty Result or the first constraint that the `NDRange` failed.
+    pub fn validate(&self, limits: &WorkGroupLimits) -> Result<(), NDRangeError> {
+        if let Some(offset) = self.offset() {
+            for (dim, (&global, &offset)) in self.global().iter().zip(offset).enumerate() {
+                if global.checked_add(offset).is_none() {
+                    return Err(NDRangeError::GlobalOffsetOverflow {
+                        dim,
+                        global,
+                        offset,
+                    });
+                }
+            }
+        }
+
+        if let Some(local) = self.local() {
+            for (dim, (&global, &local)) in self.global().iter().zip(local).enumerate() {
+                if 0 == local {
+                    return Err(NDRangeError::ZeroLocalSize { dim });
+                }
+
+                if let Some(&max) = limits.max_work_item_sizes.get(dim)
+                    && max < local
+                {
+                    return Err(NDRangeError::WorkItemSizeExceeded { dim, local, max });
+                }
+
+                if !limits.non_uniform_work_group_support && 0 != global % local {
+                    return Err(NDRangeError::NonUniformWorkGroup { dim, global, local });
+                }
+            }
+
+            let size = self.work_group_size().unwrap_or(1);
+            if limits.max_device_work_group_size < size {
+                return Err(NDRangeError::DeviceWorkGroupSizeExceeded {
+                    size,
+                    max: limits.max_device_work_group_size,
+                });
+            }
+            if limits.max_kernel_work_group_size < size {
+                return Err(NDRangeError::KernelWorkGroupSizeExceeded {
+                    size,
+                    max: limits.max_kernel_work_group_size,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the `NDRange` for a kernel and enqueue it on a command-queue.
+    /// The device is the `CL_QUEUE_DEVICE` of the command-queue.
+    /// Calls `enqueue::enqueue_nd_range_kernel` if the `NDRange` is valid.
+    ///
+    /// * `command_queue` - the `OpenCL` command-queue.
+    /// * `kernel` - the `OpenCL` kernel, with all of its arguments set.
+    /// * `event_wait_list` - the events to complete before the kernel is executed.
+    /// * `return_event` - whether to create an event for the command.
+    ///
+    /// returns a Result containing the new `OpenCL` event if `return_event` is true,
+    /// or the constraint that the `NDRange` failed
+    /// or the error code from the `OpenCL` C API function.
+    ///
+    /// # Safety
+    ///
+    /// This function is unsafe because the kernel arguments must be valid.
+    pub unsafe fn enqueue(
+        &self,
+        command_queue: cl_command_queue,
+        kernel: cl_kernel,
+        event_wait_list: &[cl_event],
+        return_event: bool,
+    ) -> Result<Option<cl_event>, NDRangeError> {
+        let device =
+            intptr_t::from(get_command_queue_info(command_queue, CL_QUEUE_DEVICE)?) as cl_device_id;
+        let limits = WorkGroupLimits::query(kernel, device)?;
+        self.validate(&limits)?;
+
+        unsafe {
+            Ok(enqueue_nd_range_kernel(
+                command_queue,
+                kernel,
+                self.offset(),
+                self.global(),
+                self.local(),
+                event_wait_list,
+                return_event,
+            )?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> WorkGroupLimits {
+        WorkGroupLimits {
+            max_work_item_sizes: vec![1024, 1024, 64],
+            max_device_work_group_size: 1024,
+            max_kernel_work_group_size: 256,
+            non_uniform_work_group_support: false,
+        }
+    }
+
+    #[test]
+    fn test_nd_range_dimensions() {
+        let range = NDRange::new(&[1024, 768]).unwrap();
+        assert_eq!(2, range.work_dim());
+        assert_eq!(&[1024, 768], range.global());
+        assert!(range.offset().is_none());
+        assert!(range.local().is_none());
+        assert!(range.work_group_size().is_none());
+
+        let range = range
+            .with_offset(&[8, 8])
+            .unwrap()
+            .with_local(&[16, 16])
+            .unwrap();
+        assert_eq!(Some(&[8, 8][..]), range.offset());
+        assert_eq!(Some(&[16, 16][..]), range.local());
+        assert_eq!(Some(256), range.work_group_size());
+
+        assert_eq!(
+            Err(NDRangeError::InvalidWorkDimension(0)),
+            NDRange::new(&[])
+        );
+        assert_eq!(
+            Err(NDRangeError::InvalidWorkDimension(4)),
+            NDRange::new(&[1, 2, 3, 4])
+        );
+        assert_eq!(
+            Err(NDRangeError::LocalDimensionMismatch {
+                expected: 2,
+                found: 1
+            }),
+            NDRange::new(&[1024, 768]).unwrap().with_local(&[16])
+        );
+        assert_eq!(
+            Err(NDRangeError::OffsetDimensionMismatch {
+                expected: 1,
+                found: 3
+            }),
+            NDRange::new(&[1024]).unwrap().with_offset(&[0, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_nd_range_validate() {
+        let limits = limits();
+
+        let range = NDRange::new(&[1024, 768]).unwrap();
+        assert_eq!(Ok(()), range.validate(&limits));
+
+        let range = range.with_local(&[16, 16]).unwrap();
+        assert_eq!(Ok(()), range.validate(&limits));
+
+        let range = NDRange::new(&[64, 64, 128])
+            .unwrap()
+            .with_local(&[1, 1, 128])
+            .unwrap();
+        assert_eq!(
+            Err(NDRangeError::WorkItemSizeExceeded {
+                dim: 2,
+                local: 128,
+                max: 64
+            }),
+            range.validate(&limits)
+        );
+
+        let range = NDRange::new(&[1024, 1024])
+            .unwrap()
+            .with_local(&[32, 32])
+            .unwrap();
+        let error = range.validate(&limits).unwrap_err();
+        assert_eq!(
+            NDRangeError::KernelWorkGroupSizeExceeded {
+                size: 1024,
+                max: 256
+            },
+            error
+        );
+        assert_eq!(CL_INVALID_WORK_GROUP_SIZE, error.error_code());
+        println!("{error}");
+
+        let range = NDRange::new(&[4096]).unwrap().with_local(&[2048]).unwrap();
+        let mut large_limits = limits.clone();
+        large_limits.max_work_item_sizes = vec![4096];
+        assert_eq!(
+            Err(NDRangeError::DeviceWorkGroupSizeExceeded {
+                size: 2048,
+                max: 1024
+            }),
+            range.validate(&large_limits)
+        );
+
+        let range = NDRange::new(&[1000]).unwrap().with_local(&[64]).unwrap();
+        assert_eq!(
+            Err(NDRangeError::NonUniformWorkGroup {
+                dim: 0,
+                global: 1000,
+                local: 64
+            }),
+            range.validate(&limits)
+        );
+        let mut non_uniform_limits = limits.clone();
+        non_uniform_limits.non_uniform_work_group_support = true;
+        assert_eq!(Ok(()), range.validate(&non_uniform_limits));
+
+        let range = NDRange::new(&[1000]).unwrap().with_local(&[0]).unwrap();
+        assert_eq!(
+            Err(NDRangeError::ZeroLocalSize { dim: 0 }),
+            range.validate(&limits)
+        );
+
+        let range = NDRange::new(&[1000])
+            .unwrap()
+            .with_offset(&[size_t::MAX])
+            .unwrap();
+        let error = range.validate(&limits).unwrap_err();
+        assert_eq!(CL_INVALID_GLOBAL_OFFSET, error.error_code());
+    }
+}
@@ -33,6 +33,8 @@ use super::info_type::InfoType;
 use super::{api_info_size, api_info_value, api_info_vector};
 
 use libc::{c_void, size_t};
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+use std::ffi::CStr;
 use std::mem;
 use std::ptr;
 
@@ -163,11 +165,117 @@ pub fn get_platform_info(
     }
 }
 
+/// Convert an extension function address into a function pointer of type `F`.
+///
+/// # Safety
+///
+/// `F` must be a function pointer type matching the function at `address`.
+const unsafe fn function_pointer<F: Copy>(address: *mut c_void) -> Option<F> {
+    const { assert!(mem::size_of::<F>() == mem::size_of::<*mut c_void>()) };
+    if address.is_null() {
+        None
+    } else {
+        Some(unsafe { mem::transmute_copy::<*mut c_void, F>(&address) })
+    }
+}
+
+/// Get the address of an extension function for a platform.
+/// Calls `clGetExtensionFunctionAddressForPlatform`.
+/// `CL_VERSION_1_2`
+///
+/// The function pointer type is specified by the caller, e.g.:
+/// ```no_run
+/// use cl3::platform::{cl_platform_id, get_extension_function_address_for_platform};
+/// use std::ffi::c_void;
+///
+/// type clGetFooKHR_fn = unsafe extern "C" fn(platform: cl_platform_id, foo: *mut c_void) -> i32;
+///
+/// # let platform: cl_platform_id = std::ptr::null_mut();
+/// let get_foo = unsafe {
+///     get_extension_function_address_for_platform::<clGetFooKHR_fn>(platform, c"clGetFooKHR")
+/// }
+/// .unwrap();
+/// ```
+///
+/// * `platform` - the `cl_platform_id` of the `OpenCL` platform.
+/// * `func_name` - the name of the extension function.
+///
+/// returns a Result containing the function pointer, or None if the platform
+/// does not support the function, or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// This function is unsafe because `F` must be an `unsafe extern "C" fn` type
+/// matching the signature of the extension function.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+#[inline]
+pub unsafe fn get_extension_function_address_for_platform<F: Copy>(
+    platform: cl_platform_id,
+    func_name: &CStr,
+) -> Result<Option<F>, cl_int> {
+    let address = cl_call!(clGetExtensionFunctionAddressForPlatform(
+        platform,
+        func_name.as_ptr()
+    ));
+    Ok(unsafe { function_pointer(address) })
+}
+
+/// Get the address of an extension function.
+/// Calls `clGetExtensionFunctionAddress`.
+///
+/// Only available with the `dynamic` feature, since the static declaration
+/// of `clGetExtensionFunctionAddress` does not return the address.
+///
+/// * `func_name` - the name of the extension function.
+///
+/// returns a Result containing the function pointer, or None if the
+/// function is not supported, or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// This function is unsafe because `F` must be an `unsafe extern "C" fn` type
+/// matching the signature of the extension function.
+#[cfg(feature = "dynamic")]
+#[cfg_attr(
+    any(
+        feature = "CL_VERSION_1_2",
+        feature = "CL_VERSION_2_0",
+        feature = "CL_VERSION_2_1",
+        feature = "CL_VERSION_2_2",
+        feature = "CL_VERSION_3_0"
+    ),
+    deprecated(
+        since = "0.1.0",
+        note = "From CL_VERSION_1_2 use get_extension_function_address_for_platform"
+    )
+)]
+#[inline]
+pub unsafe fn get_extension_function_address<F: Copy>(
+    func_name: &CStr,
+) -> Result<Option<F>, cl_int> {
+    let address = cl_call!(clGetExtensionFunctionAddress(func_name.as_ptr()));
+    Ok(unsafe { function_pointer(address) })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::error_codes::error_text;
 
+    #[test]
+    fn test_function_pointer() {
+        unsafe extern "C" fn add_one(value: cl_int) -> cl_int {
+            value + 1
+        }
+        type AddOne = unsafe extern "C" fn(cl_int) -> cl_int;
+
+        let address = add_one as AddOne as *mut c_void;
+        let function = unsafe { function_pointer::<AddOne>(address) }.unwrap();
+        assert_eq!(2, unsafe { function(1) });
+
+        assert!(unsafe { function_pointer::<AddOne>(ptr::null_mut()) }.is_none());
+    }
+
     #[test]
     fn test_get_platform_info() {
         let platform_ids = get_platform_ids().unwrap();
@@ -0,0 +1,615 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` event profiling.
+//!
+//! A [`Profile`] holds all of the profiling timestamps of an event.
+//! A [`ProfileCollector`] collects named profiles and exports them in the
+//! Chrome trace event JSON format, which can be viewed in `chrome://tracing`
+//! or [Perfetto](https://ui.perfetto.dev/).
+//!
+//! A [`ClockCorrelation`] maps device profiling timestamps onto the host
+//! timeline, so that device activity can be aligned with host tracing.
+//!
+//! Note: the command queue must be created with `CL_QUEUE_PROFILING_ENABLE`.
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{
+    CL_EVENT_COMMAND_QUEUE, CL_EVENT_COMMAND_TYPE, CL_PROFILING_COMMAND_COMPLETE,
+    CL_PROFILING_COMMAND_END, CL_PROFILING_COMMAND_QUEUED, CL_PROFILING_COMMAND_START,
+    CL_PROFILING_COMMAND_SUBMIT, cl_command_queue, cl_command_type, cl_event, cl_int, cl_ulong,
+};
+
+#[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+use super::device::get_device_and_host_timer;
+use super::event::{command_type_text, get_event_info, get_event_profiling_info};
+#[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+use opencl_sys::cl_device_id;
+use std::fmt::Write as _;
+use std::io;
+use std::time::{Duration, Instant};
+
+/// The profiling timestamps of an `OpenCL` command, in nanoseconds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// When the command was enqueued by the host.
+    pub queued: cl_ulong,
+    /// When the command was submitted to the device.
+    pub submit: cl_ulong,
+    /// When the command started execution on the device.
+    pub start: cl_ulong,
+    /// When the command finished execution on the device.
+    pub end: cl_ulong,
+    /// When the command and any child commands completed.
+    pub complete: cl_ulong,
+}
+
+impl Profile {
+    /// Get the profiling timestamps of an event.
+    /// Calls clGetEventProfilingInfo for each of the `CL_PROFILING_COMMAND_*` values.
+    ///
+    /// `CL_PROFILING_COMMAND_COMPLETE` is `CL_VERSION_2_0`, if it is not
+    /// supported `complete` is set to `end`.
+    ///
+    /// * `event` - the `OpenCL` event of a command in a profiling command queue.
+    ///
+    /// returns a Result containing the `Profile`
+    /// or the error code from the `OpenCL` C API function.
+    pub fn get(event: cl_event) -> Result<Self, cl_int> {
+        let timestamp = |param_name| {
+            get_event_profiling_info(event, param_name).map(super::info_type::InfoType::to_ulong)
+        };
+        let end = timestamp(CL_PROFILING_COMMAND_END)?;
+        Ok(Self {
+            queued: timestamp(CL_PROFILING_COMMAND_QUEUED)?,
+            submit: timestamp(CL_PROFILING_COMMAND_SUBMIT)?,
+            start: timestamp(CL_PROFILING_COMMAND_START)?,
+            end,
+            complete: timestamp(CL_PROFILING_COMMAND_COMPLETE).unwrap_or(end),
+        })
+    }
+
+    /// The time the command waited in the host queue: `submit - queued`.
+    #[must_use]
+    pub const fn queue_duration(&self) -> cl_ulong {
+        self.submit.saturating_sub(self.queued)
+    }
+
+    /// The time between submission and the start of execution: `start - submit`.
+    #[must_use]
+    pub const fn submit_duration(&self) -> cl_ulong {
+        self.start.saturating_sub(self.submit)
+    }
+
+    /// The execution time of the command on the device: `end - start`.
+    #[must_use]
+    pub const fn execution_duration(&self) -> cl_ulong {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// The total time from enqueuing the command to its completion: `complete - queued`.
+    #[must_use]
+    pub const fn total_duration(&self) -> cl_ulong {
+        self.complete.saturating_sub(self.queued)
+    }
+}
+
+/// The profile of a named `OpenCL` command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProfileRecord {
+    /// The name given to the command.
+    pub name: String,
+    /// The command queue of the command.
+    pub command_queue: cl_command_queue,
+    /// The command type, see [`command_type_text`].
+    pub command_type: cl_command_type,
+    /// The profiling timestamps of the command.
+    pub profile: Profile,
+}
+
+impl ProfileRecord {
+    /// Get the profile of a named `OpenCL` event.
+    ///
+    /// * `name` - the name to give the command.
+    /// * `event` - the `OpenCL` event of a command in a profiling command queue.
+    ///
+    /// returns a Result containing the `ProfileRecord`
+    /// or the error code from the `OpenCL` C API function.
+    pub fn get(name: impl Into<String>, event: cl_event) -> Result<Self, cl_int> {
+        let command_queue = get_event_info(event, CL_EVENT_COMMAND_QUEUE)?.to_ptr();
+        let command_type = get_event_info(event, CL_EVENT_COMMAND_TYPE)?.to_uint();
+        Ok(Self {
+            name: name.into(),
+            command_queue: command_queue as cl_command_queue,
+            command_type,
+            profile: Profile::get(event)?,
+        })
+    }
+}
+
+/// Collects the profiles of named `OpenCL` commands and exports them as a
+/// Chrome trace.
+#[derive(Clone, Debug, Default)]
+pub struct ProfileCollector {
+    records: Vec<ProfileRecord>,
+}
+
+impl ProfileCollector {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            records: Vec::new(),
+        }
+    }
+
+    /// Get the profile of a completed event and add it to the collector.
+    ///
+    /// * `name` - the name to give the command.
+    /// * `event` - the `OpenCL` event of a completed command in a profiling command queue.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    pub fn add(&mut self, name: impl Into<String>, event: cl_event) -> Result<(), cl_int> {
+        self.records.push(ProfileRecord::get(name, event)?);
+        Ok(())
+    }
+
+    /// Add a `ProfileRecord` to the collector.
+    pub fn push(&mut self, record: ProfileRecord) {
+        self.records.push(record);
+    }
+
+    /// The collected profile records.
+    #[must_use]
+    pub fn records(&self) -> &[ProfileRecord] {
+        &self.records
+    }
+
+    /// Remove all of the collected profile records.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// The distinct command queues of the records, in order of first appearance.
+    fn command_queues(&self) -> Vec<cl_command_queue> {
+        let mut queues: Vec<cl_command_queue> = Vec::new();
+        for record in &self.records {
+            if !queues.contains(&record.command_queue) {
+                queues.push(record.command_queue);
+            }
+        }
+        queues
+    }
+
+    /// Export the records in the Chrome trace event JSON format.
+    ///
+    /// Each command queue is a separate thread in the trace and each command
+    /// is a complete event labeled with its name and categorised by its
+    /// command type. Timestamps are relative to the earliest queued time.
+    #[must_use]
+    pub fn to_chrome_trace(&self) -> String {
+        let origin = self
+            .records
+            .iter()
+            .map(|record| record.profile.queued)
+            .min()
+            .unwrap_or_default();
+        let queues = self.command_queues();
+
+        let mut events = vec![String::from(
+            r#"{"name":"process_name","ph":"M","pid":1,"tid":0,"args":{"name":"OpenCL"}}"#,
+        )];
+        // The tracks of the command-queues are numbered from 1: tid 0 is the process.
+        for (tid, queue) in (1..).zip(queues.iter()) {
+            events.push(format!(
+                r#"{{"name":"thread_name","ph":"M","pid":1,"tid":{tid},"args":{{"name":"Command queue {tid} ({:p})"}}}}"#,
+                *queue
+            ));
+        }
+
+        for record in &self.records {
+            let tid = queues
+                .iter()
+                .position(|&queue| queue == record.command_queue)
+                .unwrap_or_default()
+                + 1;
+            let profile = &record.profile;
+            let mut event = String::new();
+            let _ = write!(
+                event,
+                r#"{{"name":"{}","cat":"{}","ph":"X","pid":1,"tid":{},"ts":{},"dur":{},"#,
+                escape_json(&record.name),
+                command_type_text(record.command_type),
+                tid,
+                microseconds(profile.start.saturating_sub(origin)),
+                microseconds(profile.execution_duration())
+            );
+            let _ = write!(
+                event,
+                r#""args":{{"queued_ns":{},"submit_ns":{},"start_ns":{},"end_ns":{},"complete_ns":{},"queue_duration_ns":{},"submit_duration_ns":{},"execution_duration_ns":{}}}}}"#,
+                profile.queued,
+                profile.submit,
+                profile.start,
+                profile.end,
+                profile.complete,
+                profile.queue_duration(),
+                profile.submit_duration(),
+                profile.execution_duration()
+            );
+            events.push(event);
+        }
+
+        format!(
+            r#"{{"displayTimeUnit":"ns","traceEvents":[{}]}}"#,
+            events.join(",")
+        )
+    }
+
+    /// Write the records in the Chrome trace event JSON format, see [`Self::to_chrome_trace`].
+    ///
+    /// * `writer` - the destination of the JSON, e.g. a `File`.
+    ///
+    /// returns an empty Result or the `io::Error`.
+    pub fn write_chrome_trace<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.to_chrome_trace().as_bytes())
+    }
+}
+
+/// Simultaneous readings of a device timer, the `OpenCL` host timer and
+/// the Rust monotonic clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockSample {
+    /// The device timer, in the domain of the profiling timestamps, in nanoseconds.
+    pub device: cl_ulong,
+    /// The `OpenCL` host timer, in nanoseconds.
+    pub host: cl_ulong,
+    /// The host `Instant` corresponding to `host`.
+    pub instant: Instant,
+}
+
+impl ClockSample {
+    /// Sample the device and host clocks.
+    /// Calls `clGetDeviceAndHostTimer` between two readings of `Instant::now`
+    /// and takes their midpoint as the `instant` of the sample.
+    /// `CL_VERSION_2_1`
+    ///
+    /// * `device` - a valid `OpenCL` device.
+    ///
+    /// returns a Result containing the `ClockSample`
+    /// or the error code from the `OpenCL` C API function.
+    #[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+    pub fn get(device: cl_device_id) -> Result<Self, cl_int> {
+        let before = Instant::now();
+        let [device, host] = get_device_and_host_timer(device)?;
+        let after = Instant::now();
+        Ok(Self {
+            device,
+            host,
+            instant: before + (after - before) / 2,
+        })
+    }
+}
+
+/// A correlation between a device timer and the host clocks.
+///
+/// The offset between the clocks is taken from the latest sample and the
+/// drift of the device timer relative to the host timer is estimated from
+/// the first and latest samples.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockCorrelation {
+    first: ClockSample,
+    latest: ClockSample,
+}
+
+impl ClockCorrelation {
+    /// Create a correlation from two samples of the clocks.
+    /// If the samples have the same device time, no drift is estimated.
+    ///
+    /// * `first` - the earlier sample.
+    /// * `latest` - the later sample.
+    #[must_use]
+    pub const fn from_samples(first: ClockSample, latest: ClockSample) -> Self {
+        Self { first, latest }
+    }
+
+    /// Create a correlation from a single sample of the clocks, without drift.
+    /// `CL_VERSION_2_1`
+    ///
+    /// * `device` - a valid `OpenCL` device.
+    ///
+    /// returns a Result containing the `ClockCorrelation`
+    /// or the error code from the `OpenCL` C API function.
+    #[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+    pub fn new(device: cl_device_id) -> Result<Self, cl_int> {
+        let sample = ClockSample::get(device)?;
+        Ok(Self::from_samples(sample, sample))
+    }
+
+    /// Create a correlation from two samples of the clocks, `interval` apart.
+    /// A longer interval gives a better estimate of the drift.
+    /// `CL_VERSION_2_1`
+    ///
+    /// * `device` - a valid `OpenCL` device.
+    /// * `interval` - the time to sleep between the samples.
+    ///
+    /// returns a Result containing the `ClockCorrelation`
+    /// or the error code from the `OpenCL` C API function.
+    #[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+    pub fn calibrate(device: cl_device_id, interval: Duration) -> Result<Self, cl_int> {
+        let first = ClockSample::get(device)?;
+        std::thread::sleep(interval);
+        Ok(Self::from_samples(first, ClockSample::get(device)?))
+    }
+
+    /// Take a new latest sample of the clocks, to update the offset and drift.
+    /// `CL_VERSION_2_1`
+    ///
+    /// * `device` - the `OpenCL` device of the correlation.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    #[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+    pub fn resample(&mut self, device: cl_device_id) -> Result<(), cl_int> {
+        self.latest = ClockSample::get(device)?;
+        Ok(())
+    }
+
+    /// The first sample of the correlation.
+    #[must_use]
+    pub const fn first(&self) -> &ClockSample {
+        &self.first
+    }
+
+    /// The latest sample of the correlation.
+    #[must_use]
+    pub const fn latest(&self) -> &ClockSample {
+        &self.latest
+    }
+
+    /// The offset of the `OpenCL` host timer from the device timer at the
+    /// latest sample, `host - device`, in nanoseconds.
+    #[must_use]
+    pub fn offset(&self) -> i128 {
+        i128::from(self.latest.host) - i128::from(self.latest.device)
+    }
+
+    /// The estimated drift of the device timer relative to the host timer,
+    /// in parts per billion. Positive if the device timer runs slow.
+    #[must_use]
+    pub fn drift_ppb(&self) -> i128 {
+        let (host_delta, device_delta) = self.deltas();
+        (host_delta - device_delta) * 1_000_000_000 / device_delta
+    }
+
+    /// The changes in the host and device timers between the samples,
+    /// or one to one if there is no change in the device timer.
+    fn deltas(&self) -> (i128, i128) {
+        let device_delta = i128::from(self.latest.device) - i128::from(self.first.device);
+        if 0 == device_delta {
+            (1, 1)
+        } else {
+            (
+                i128::from(self.latest.host) - i128::from(self.first.host),
+                device_delta,
+            )
+        }
+    }
+
+    /// The signed host time, in nanoseconds, of a device timestamp
+    /// relative to the latest sample.
+    fn host_delta(&self, device_timestamp: cl_ulong) -> i128 {
+        let (host_delta, device_delta) = self.deltas();
+        (i128::from(device_timestamp) - i128::from(self.latest.device)) * host_delta / device_delta
+    }
+
+    /// Convert a device timestamp, e.g. a `CL_PROFILING_COMMAND_*` value,
+    /// to the `OpenCL` host timer.
+    ///
+    /// * `device_timestamp` - the device timestamp in nanoseconds.
+    ///
+    /// returns the `OpenCL` host time in nanoseconds, saturated to the range of `cl_ulong`.
+    #[must_use]
+    pub fn to_host_nanos(&self, device_timestamp: cl_ulong) -> cl_ulong {
+        let host = i128::from(self.latest.host) + self.host_delta(device_timestamp);
+        cl_ulong::try_from(host.max(0)).unwrap_or(cl_ulong::MAX)
+    }
+
+    /// Convert a device timestamp, e.g. a `CL_PROFILING_COMMAND_*` value,
+    /// to a host `Instant`.
+    ///
+    /// * `device_timestamp` - the device timestamp in nanoseconds.
+    ///
+    /// returns the host `Instant`, or None if it can't be represented.
+    #[must_use]
+    pub fn to_instant(&self, device_timestamp: cl_ulong) -> Option<Instant> {
+        let delta = self.host_delta(device_timestamp);
+        let duration = Duration::from_nanos(u64::try_from(delta.unsigned_abs()).ok()?);
+        if delta < 0 {
+            self.latest.instant.checked_sub(duration)
+        } else {
+            self.latest.instant.checked_add(duration)
+        }
+    }
+
+    /// Convert the device timestamps of a `Profile` to the `OpenCL` host timer.
+    #[must_use]
+    pub fn to_host_profile(&self, profile: &Profile) -> Profile {
+        Profile {
+            queued: self.to_host_nanos(profile.queued),
+            submit: self.to_host_nanos(profile.submit),
+            start: self.to_host_nanos(profile.start),
+            end: self.to_host_nanos(profile.end),
+            complete: self.to_host_nanos(profile.complete),
+        }
+    }
+}
+
+/// Format nanoseconds as microseconds with three decimal places.
+fn microseconds(nanoseconds: cl_ulong) -> String {
+    format!("{}.{:03}", nanoseconds / 1000, nanoseconds % 1000)
+}
+
+/// Escape a string for a JSON string value.
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", u32::from(c));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opencl_sys::{CL_COMMAND_NDRANGE_KERNEL, CL_COMMAND_READ_BUFFER};
+    use std::ptr;
+
+    #[test]
+    fn test_profile_durations() {
+        let profile = Profile {
+            queued: 1_000,
+            submit: 1_500,
+            start: 3_000,
+            end: 10_000,
+            complete: 10_500,
+        };
+        assert_eq!(500, profile.queue_duration());
+        assert_eq!(1_500, profile.submit_duration());
+        assert_eq!(7_000, profile.execution_duration());
+        assert_eq!(9_500, profile.total_duration());
+
+        // Some devices don't report every timestamp
+        let profile = Profile {
+            end: 10,
+            ..Profile::default()
+        };
+        assert_eq!(10, profile.execution_duration());
+        assert_eq!(0, profile.total_duration());
+    }
+
+    #[test]
+    fn test_chrome_trace() {
+        let queue_0: cl_command_queue = ptr::without_provenance_mut(0x1000);
+        let queue_1: cl_command_queue = ptr::without_provenance_mut(0x2000);
+
+        let mut collector = ProfileCollector::new();
+        collector.push(ProfileRecord {
+            name: String::from("saxpy \"1\""),
+            command_queue: queue_0,
+            command_type: CL_COMMAND_NDRANGE_KERNEL,
+            profile: Profile {
+                queued: 1_000,
+                submit: 1_500,
+                start: 3_000,
+                end: 10_250,
+                complete: 10_250,
+            },
+        });
+        collector.push(ProfileRecord {
+            name: String::from("read"),
+            command_queue: queue_1,
+            command_type: CL_COMMAND_READ_BUFFER,
+            profile: Profile {
+                queued: 2_000,
+                submit: 2_000,
+                start: 11_000,
+                end: 12_000,
+                complete: 12_000,
+            },
+        });
+        assert_eq!(2, collector.records().len());
+
+        let trace = collector.to_chrome_trace();
+        println!("{trace}");
+        assert!(trace.starts_with(r#"{"displayTimeUnit":"ns","traceEvents":["#));
+        assert!(trace.contains(
+            r#"{"name":"thread_name","ph":"M","pid":1,"tid":2,"args":{"name":"Command queue 2 (0x2000)"}}"#
+        ));
+        assert!(trace.contains(
+            r#"{"name":"saxpy \"1\"","cat":"CL_COMMAND_NDRANGE_KERNEL","ph":"X","pid":1,"tid":1,"ts":2.000,"dur":7.250,"#
+        ));
+        assert!(trace.contains(
+            r#"{"name":"read","cat":"CL_COMMAND_READ_BUFFER","ph":"X","pid":1,"tid":2,"ts":10.000,"dur":1.000,"#
+        ));
+        assert!(trace.ends_with("}]}"));
+
+        let mut buffer = Vec::new();
+        collector.write_chrome_trace(&mut buffer).unwrap();
+        assert_eq!(trace.as_bytes(), buffer.as_slice());
+
+        collector.clear();
+        assert!(collector.records().is_empty());
+    }
+
+    #[test]
+    fn test_clock_correlation() {
+        let instant = Instant::now();
+        let first = ClockSample {
+            device: 1_000_000,
+            host: 5_000_000,
+            instant,
+        };
+
+        // A single sample: a fixed offset without drift
+        let correlation = ClockCorrelation::from_samples(first, first);
+        assert_eq!(4_000_000, correlation.offset());
+        assert_eq!(0, correlation.drift_ppb());
+        assert_eq!(5_000_500, correlation.to_host_nanos(1_000_500));
+        assert_eq!(
+            Some(instant + Duration::from_nanos(500)),
+            correlation.to_instant(1_000_500)
+        );
+        assert_eq!(4_000_000, correlation.to_host_nanos(0));
+
+        // The device timer runs 0.1% slow relative to the host timer
+        let latest = ClockSample {
+            device: 2_000_000,
+            host: 6_001_000,
+            instant: instant + Duration::from_micros(1_001),
+        };
+        let correlation = ClockCorrelation::from_samples(first, latest);
+        assert_eq!(4_001_000, correlation.offset());
+        assert_eq!(1_000_000, correlation.drift_ppb());
+        assert_eq!(6_002_001, correlation.to_host_nanos(2_001_000));
+        assert_eq!(5_000_000, correlation.to_host_nanos(1_000_000));
+        assert_eq!(Some(instant), correlation.to_instant(1_000_000));
+
+        let profile = Profile {
+            queued: 1_000_000,
+            submit: 1_000_000,
+            start: 2_000_000,
+            end: 2_001_000,
+            complete: 2_001_000,
+        };
+        let host_profile = correlation.to_host_profile(&profile);
+        assert_eq!(5_000_000, host_profile.queued);
+        assert_eq!(6_001_000, host_profile.start);
+        assert_eq!(1_001, host_profile.execution_duration());
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!("plain", escape_json("plain"));
+        assert_eq!(r#"a\"b\\c\nd\u0001"#, escape_json("a\"b\\c\nd\u{1}"));
+    }
+}
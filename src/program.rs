@@ -19,8 +19,9 @@
 #![allow(clippy::not_unsafe_ptr_arg_deref, clippy::wildcard_in_or_patterns)]
 
 pub use opencl_sys::{
-    CL_BUILD_ERROR, CL_BUILD_IN_PROGRESS, CL_BUILD_NONE, CL_BUILD_SUCCESS, CL_FALSE,
-    CL_INVALID_VALUE, CL_PROGRAM_BINARIES, CL_PROGRAM_BINARY_SIZES, CL_PROGRAM_BINARY_TYPE,
+    CL_BUILD_ERROR, CL_BUILD_IN_PROGRESS, CL_BUILD_NONE, CL_BUILD_PROGRAM_FAILURE,
+    CL_BUILD_SUCCESS, CL_COMPILE_PROGRAM_FAILURE, CL_FALSE, CL_INVALID_VALUE,
+    CL_LINK_PROGRAM_FAILURE, CL_PROGRAM_BINARIES, CL_PROGRAM_BINARY_SIZES, CL_PROGRAM_BINARY_TYPE,
     CL_PROGRAM_BINARY_TYPE_COMPILED_OBJECT, CL_PROGRAM_BINARY_TYPE_EXECUTABLE,
     CL_PROGRAM_BINARY_TYPE_LIBRARY, CL_PROGRAM_BINARY_TYPE_NONE,
     CL_PROGRAM_BUILD_GLOBAL_VARIABLE_TOTAL_SIZE, CL_PROGRAM_BUILD_LOG, CL_PROGRAM_BUILD_OPTIONS,
@@ -38,7 +39,9 @@ use super::{
 use libc::{c_char, c_uchar, c_void, intptr_t, size_t};
 use std::ffi::CStr;
 use std::mem;
+use std::panic::{self, AssertUnwindSafe};
 use std::ptr;
+use std::sync::{Arc, Mutex};
 
 // Missing from cl_sys
 pub const CL_PROGRAM_SCOPE_GLOBAL_CTORS_PRESENT: cl_program_info = 0x116A;
@@ -260,6 +263,87 @@ pub fn build_program(
     }
 }
 
+/// A program notification closure, shared by the caller of an `OpenCL`
+/// function and the notification trampoline.
+type ProgramCallback = Mutex<Option<Box<dyn FnOnce(cl_program) + Send>>>;
+
+/// The program notification trampoline: `user_data` is the trampoline's
+/// reference to an `Arc<ProgramCallback>`, which is consumed.
+extern "C" fn program_callback_trampoline(program: cl_program, user_data: *mut c_void) {
+    let state = unsafe { Arc::from_raw(user_data.cast::<ProgramCallback>().cast_const()) };
+    let callback = state.lock().ok().and_then(|mut callback| callback.take());
+    if let Some(callback) = callback {
+        // Catch any panic so that it can't unwind across the FFI boundary.
+        let _ = panic::catch_unwind(AssertUnwindSafe(move || callback(program)));
+    }
+}
+
+/// Share a program notification closure with the trampoline.
+///
+/// returns the caller's reference to the closure and the `user_data` of the
+/// trampoline, which owns the trampoline's reference.
+fn program_callback<F>(callback: F) -> (Arc<ProgramCallback>, *mut c_void)
+where
+    F: FnOnce(cl_program) + Send + 'static,
+{
+    let state: Arc<ProgramCallback> = Arc::new(Mutex::new(Some(Box::new(callback))));
+    let user_data = Arc::into_raw(Arc::clone(&state))
+        .cast_mut()
+        .cast::<c_void>();
+    (state, user_data)
+}
+
+/// Reclaim the trampoline's reference to a program notification closure
+/// after the `OpenCL` function returned an error.
+///
+/// The closure is taken by whichever of the trampoline and this function
+/// runs first. If the trampoline was called before the error was returned,
+/// e.g. with `CL_BUILD_PROGRAM_FAILURE`, it has already released its
+/// reference. Otherwise the notification was not registered, so the closure
+/// and the trampoline's reference are dropped here.
+fn reclaim_program_callback(state: &ProgramCallback, user_data: *mut c_void) {
+    let uncalled = state
+        .lock()
+        .is_ok_and(|mut callback| callback.take().is_some());
+    if uncalled {
+        drop(unsafe { Arc::from_raw(user_data.cast::<ProgramCallback>().cast_const()) });
+    }
+}
+
+/// Build (compile & link) a program executable, calling a closure when the
+/// build has completed.
+/// Calls `clBuildProgram` with a notification routine that calls the closure.
+///
+/// The closure is called at most once and is dropped after it has been called.
+/// A panic in the closure is caught, it does not unwind into the `OpenCL` library.
+///
+/// * `program` - a valid `OpenCL` program.
+/// * `devices` - a slice of devices that are in context.
+/// * `options` - the build options in a null-terminated string.
+/// * `callback` - the closure, called with the program when the build has completed.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+#[inline]
+pub fn build_program_with_closure<F>(
+    program: cl_program,
+    devices: &[cl_device_id],
+    options: &CStr,
+    callback: F,
+) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_program) + Send + 'static,
+{
+    let (state, user_data) = program_callback(callback);
+    build_program(
+        program,
+        devices,
+        options,
+        Some(program_callback_trampoline),
+        user_data,
+    )
+    .inspect_err(|_| reclaim_program_callback(&state, user_data))
+}
+
 /// Compile a program’s source for the devices the `OpenCL` context associated
 /// with the program.
 /// Calls clCompileProgram to compile an `OpenCL` program object.
@@ -322,6 +406,52 @@ pub fn compile_program(
     }
 }
 
+/// Compile a program’s source, calling a closure when the compilation has completed.
+/// Calls clCompileProgram with a notification routine that calls the closure.
+///
+/// The closure is called at most once and is dropped after it has been called.
+/// A panic in the closure is caught, it does not unwind into the `OpenCL` library.
+///
+/// * `program` - a valid `OpenCL` program.
+/// * `devices` - a slice of devices that are in context.
+/// * `options` - the compilation options in a null-terminated string.
+/// * `input_headers` - a slice of programs that describe headers in the `input_headers`.
+/// * `header_include_names` - an array that has a one to one correspondence with
+///   `input_headers`.
+/// * `callback` - the closure, called with the program when the compilation has completed.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+///
+/// # Panics
+///
+/// Panics if `input_headers.len()` != `header_include_names.len()`.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+#[inline]
+pub fn compile_program_with_closure<F>(
+    program: cl_program,
+    devices: &[cl_device_id],
+    options: &CStr,
+    input_headers: &[cl_program],
+    header_include_names: &[&CStr],
+    callback: F,
+) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_program) + Send + 'static,
+{
+    assert!(input_headers.len() == header_include_names.len());
+    let (state, user_data) = program_callback(callback);
+    compile_program(
+        program,
+        devices,
+        options,
+        input_headers,
+        header_include_names,
+        Some(program_callback_trampoline),
+        user_data,
+    )
+    .inspect_err(|_| reclaim_program_callback(&state, user_data))
+}
+
 /// Link a set of compiled program objects and libraries for the devices in the
 /// `OpenCL` context associated with the program.
 /// Calls clLinkProgram to link an `OpenCL` program object.
@@ -374,6 +504,56 @@ pub unsafe fn link_program(
     }
 }
 
+/// Link a set of compiled program objects and libraries, calling a closure
+/// when the link has completed.
+/// Calls clLinkProgram with a notification routine that calls the closure.
+///
+/// The closure is called at most once and is dropped after it has been called.
+/// A panic in the closure is caught, it does not unwind into the `OpenCL` library.
+///
+/// * `context` - a valid `OpenCL` context.
+/// * `devices` - a slice of devices that are in context.
+/// * `options` - the link options in a null-terminated string.
+/// * `input_programs` - a slice of programs that are to be linked to create the program executable.
+/// * `callback` - the closure, called with the new program when the link has completed.
+///
+/// returns a Result containing the new `OpenCL` program object
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Panics
+///
+/// Panics if `input_programs.is_empty()`.
+///
+/// # Safety
+///
+/// This is unsafe when a device is not a member of context.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+#[inline]
+pub unsafe fn link_program_with_closure<F>(
+    context: cl_context,
+    devices: &[cl_device_id],
+    options: &CStr,
+    input_programs: &[cl_program],
+    callback: F,
+) -> Result<cl_program, cl_int>
+where
+    F: FnOnce(cl_program) + Send + 'static,
+{
+    assert!(!input_programs.is_empty());
+    let (state, user_data) = program_callback(callback);
+    unsafe {
+        link_program(
+            context,
+            devices,
+            options,
+            input_programs,
+            Some(program_callback_trampoline),
+            user_data,
+        )
+    }
+    .inspect_err(|_| reclaim_program_callback(&state, user_data))
+}
+
 /// Set the value of a specialization constant.
 /// Calls `clSetProgramSpecializationConstant`.
 /// `CL_VERSION_2_2`
@@ -407,6 +587,68 @@ pub unsafe fn set_program_specialization_constant(
     }
 }
 
+/// Register a callback function with a program object that is called when
+/// the program object is destroyed.
+/// Calls `clSetProgramReleaseCallback`.
+/// `CL_VERSION_2_2`
+///
+/// * `program` - the program.
+/// * `pfn_notify` - callback function to be registered by the application.
+/// * `user_data` - passed as the `user_data` argument when `pfn_notify` is called.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+#[cfg(any(feature = "CL_VERSION_2_2", feature = "dynamic"))]
+#[cfg_attr(
+    feature = "CL_VERSION_3_0",
+    deprecated(since = "0.1.0", note = "From CL_VERSION_3_0")
+)]
+#[allow(deprecated)]
+#[inline]
+pub fn set_program_release_callback(
+    program: cl_program,
+    pfn_notify: Option<unsafe extern "C" fn(cl_program, *mut c_void)>,
+    user_data: *mut c_void,
+) -> Result<(), cl_int> {
+    let status: cl_int =
+        unsafe { cl_call!(clSetProgramReleaseCallback(program, pfn_notify, user_data)) };
+    if CL_SUCCESS == status {
+        Ok(())
+    } else {
+        Err(status)
+    }
+}
+
+/// Register a closure with a program object that is called when the program
+/// object is destroyed.
+/// Calls `clSetProgramReleaseCallback` with a callback function that calls the closure.
+/// `CL_VERSION_2_2`
+///
+/// The closure is dropped after it has been called.
+/// A panic in the closure is caught, it does not unwind into the `OpenCL` library.
+///
+/// * `program` - the program.
+/// * `callback` - the closure, called with the program when it is destroyed.
+///
+/// returns an empty Result or the error code from the `OpenCL` C API function.
+#[cfg(any(feature = "CL_VERSION_2_2", feature = "dynamic"))]
+#[cfg_attr(
+    feature = "CL_VERSION_3_0",
+    deprecated(since = "0.1.0", note = "From CL_VERSION_3_0")
+)]
+#[allow(deprecated)]
+#[inline]
+pub fn set_program_release_callback_with_closure<F>(
+    program: cl_program,
+    callback: F,
+) -> Result<(), cl_int>
+where
+    F: FnOnce(cl_program) + Send + 'static,
+{
+    let (state, user_data) = program_callback(callback);
+    set_program_release_callback(program, Some(program_callback_trampoline), user_data)
+        .inspect_err(|_| reclaim_program_callback(&state, user_data))
+}
+
 /// Release the resources allocated by the `OpenCL` compiler for platform.
 /// Calls clUnloadPlatformCompiler.
 ///
@@ -611,6 +853,36 @@ mod tests {
     use crate::error_codes::error_text;
     use crate::platform::get_platform_ids;
     use std::ffi::CString;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_program_callback_trampoline() {
+        let called = Arc::new(());
+        let flag = Arc::clone(&called);
+        let (state, user_data) = program_callback(move |program| {
+            assert!(program.is_null());
+            drop(flag);
+        });
+        program_callback_trampoline(ptr::null_mut(), user_data);
+        assert_eq!(1, Arc::strong_count(&called));
+        assert_eq!(1, Arc::strong_count(&state));
+
+        // A closure that has been called is not reclaimed after an error
+        reclaim_program_callback(&state, user_data);
+        assert_eq!(1, Arc::strong_count(&state));
+
+        // The closure is dropped if it was not registered
+        let flag = Arc::clone(&called);
+        let (state, user_data) = program_callback(move |_| drop(flag));
+        assert_eq!(2, Arc::strong_count(&state));
+        reclaim_program_callback(&state, user_data);
+        assert_eq!(1, Arc::strong_count(&called));
+        assert_eq!(1, Arc::strong_count(&state));
+
+        // A panic in the closure must not unwind out of the trampoline
+        let (_state, user_data) = program_callback(|_| panic!("program callback"));
+        program_callback_trampoline(ptr::null_mut(), user_data);
+    }
 
     #[test]
     fn test_program() {
@@ -0,0 +1,656 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An on-disk cache of `OpenCL` program binaries.
+//!
+//! A [`ProgramCache`] builds programs for a device from source or IL and
+//! stores the program binaries in a directory. Entries are keyed by a
+//! [`ProgramCacheKey`] of the source, build options, device name, driver
+//! version and platform version, so a driver update invalidates the cache.
+//!
+//! Entries are written atomically (written to a temporary file and renamed),
+//! entries larger than the maximum entry size are not stored and the least
+//! recently used entries are removed when the cache exceeds its maximum size.
+//! Each entry contains a hash of its program binary, which is checked before
+//! the binary is passed to the `OpenCL` implementation. If a cached binary is
+//! rejected by the device with `CL_INVALID_BINARY` or fails to build, the
+//! entry is removed and the program is built from source.
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{
+    CL_BUILD_PROGRAM_FAILURE, CL_INVALID_BINARY, CL_PROGRAM_BINARIES, CL_PROGRAM_BUILD_LOG,
+    CL_PROGRAM_DEVICES, cl_context, cl_device_id, cl_int, cl_program,
+};
+
+use super::device::{CL_DEVICE_NAME, CL_DEVICE_PLATFORM, CL_DRIVER_VERSION, get_device_info};
+use super::error_codes::{ClError, error_text};
+use super::platform::{CL_PLATFORM_VERSION, get_platform_info};
+#[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+use super::program::create_program_with_il;
+use super::program::{
+    build_program, create_program_with_binary, create_program_with_source, get_program_build_info,
+    get_program_info, release_program,
+};
+use libc::intptr_t;
+use std::ffi::CStr;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::SystemTime;
+use thiserror::Error;
+
+/// The default maximum size of a cache entry: 64 MiB.
+pub const DEFAULT_MAX_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The default maximum size of all of the entries in a cache: 1 GiB.
+pub const DEFAULT_MAX_TOTAL_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// The file extension of cache entries.
+const ENTRY_EXTENSION: &str = "clbin";
+
+/// The first bytes of a cache entry, including the entry format version.
+const ENTRY_MAGIC: &[u8; 8] = b"CL3PBIN2";
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// The 64 bit FNV-1a hash of `bytes`, continuing from `hash`.
+/// A stable hash is required since the cache is persistent.
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// The source of a program to build.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgramSource<'a> {
+    /// `OpenCL` C source code.
+    Source(&'a str),
+    /// An intermediate language, e.g. SPIR-V.
+    #[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+    Il(&'a [u8]),
+}
+
+impl ProgramSource<'_> {
+    /// A tag to distinguish source code from IL with the same bytes.
+    const fn tag(&self) -> u8 {
+        match self {
+            Self::Source(_) => b'S',
+            #[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+            Self::Il(_) => b'I',
+        }
+    }
+
+    const fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Source(source) => source.as_bytes(),
+            #[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+            Self::Il(il) => il,
+        }
+    }
+
+    /// Create a program in a context from the source.
+    fn create_program(&self, context: cl_context) -> Result<cl_program, cl_int> {
+        match self {
+            Self::Source(source) => create_program_with_source(context, &[source]),
+            #[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+            Self::Il(il) => create_program_with_il(context, il),
+        }
+    }
+}
+
+/// The identity of a cache entry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProgramCacheKey {
+    /// The FNV-1a hash of the program source or IL.
+    pub source_hash: u64,
+    /// The length of the program source or IL in bytes.
+    pub source_len: u64,
+    /// The build options.
+    pub options: String,
+    /// `CL_DEVICE_NAME`
+    pub device_name: String,
+    /// `CL_DRIVER_VERSION`
+    pub driver_version: String,
+    /// `CL_PLATFORM_VERSION`
+    pub platform_version: String,
+}
+
+impl ProgramCacheKey {
+    /// Create the key of a program for a device.
+    /// Calls clGetDeviceInfo and clGetPlatformInfo to get the device name,
+    /// driver version and platform version.
+    ///
+    /// * `device` - the `OpenCL` device.
+    /// * `source` - the program source or IL.
+    /// * `options` - the build options.
+    ///
+    /// returns a Result containing the key
+    /// or the error code from the `OpenCL` C API function.
+    pub fn new(
+        device: cl_device_id,
+        source: ProgramSource<'_>,
+        options: &CStr,
+    ) -> Result<Self, cl_int> {
+        let platform = get_device_info(device, CL_DEVICE_PLATFORM)?.to_ptr();
+        Ok(Self {
+            source_hash: fnv1a(fnv1a(FNV_OFFSET_BASIS, &[source.tag()]), source.bytes()),
+            source_len: source.bytes().len() as u64,
+            options: options.to_string_lossy().into_owned(),
+            device_name: get_device_info(device, CL_DEVICE_NAME)?.into(),
+            driver_version: get_device_info(device, CL_DRIVER_VERSION)?.into(),
+            platform_version: get_platform_info(platform as _, CL_PLATFORM_VERSION)?.into(),
+        })
+    }
+
+    /// Encode the key as length prefixed fields.
+    fn encode(&self, buffer: &mut Vec<u8>) {
+        buffer.extend_from_slice(&self.source_hash.to_le_bytes());
+        buffer.extend_from_slice(&self.source_len.to_le_bytes());
+        for field in [
+            &self.options,
+            &self.device_name,
+            &self.driver_version,
+            &self.platform_version,
+        ] {
+            encode_bytes(buffer, field.as_bytes());
+        }
+    }
+
+    /// Decode a key encoded by `encode`.
+    fn decode(data: &mut &[u8]) -> Option<Self> {
+        let source_hash = decode_u64(data)?;
+        let source_len = decode_u64(data)?;
+        let mut field = || String::from_utf8(decode_bytes(data)?.to_vec()).ok();
+        Some(Self {
+            source_hash,
+            source_len,
+            options: field()?,
+            device_name: field()?,
+            driver_version: field()?,
+            platform_version: field()?,
+        })
+    }
+
+    /// The stable hash of the key.
+    #[must_use]
+    pub fn hash(&self) -> u64 {
+        let mut buffer = Vec::new();
+        self.encode(&mut buffer);
+        fnv1a(FNV_OFFSET_BASIS, &buffer)
+    }
+
+    /// The name of the cache entry file of the key.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        format!("{:016x}.{ENTRY_EXTENSION}", self.hash())
+    }
+}
+
+fn encode_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    buffer.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    buffer.extend_from_slice(bytes);
+}
+
+fn decode_u64(data: &mut &[u8]) -> Option<u64> {
+    let (value, rest) = data.split_first_chunk::<8>()?;
+    *data = rest;
+    Some(u64::from_le_bytes(*value))
+}
+
+fn decode_bytes<'a>(data: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = usize::try_from(decode_u64(data)?).ok()?;
+    if len > data.len() {
+        return None;
+    }
+    let (bytes, rest) = data.split_at(len);
+    *data = rest;
+    Some(bytes)
+}
+
+/// Encode a cache entry: the magic bytes, the key, the program binary and
+/// the hash of the program binary.
+fn encode_entry(key: &ProgramCacheKey, binary: &[u8]) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(binary.len() + 256);
+    buffer.extend_from_slice(ENTRY_MAGIC);
+    key.encode(&mut buffer);
+    encode_bytes(&mut buffer, binary);
+    buffer.extend_from_slice(&fnv1a(FNV_OFFSET_BASIS, binary).to_le_bytes());
+    buffer
+}
+
+/// Decode the program binary of a cache entry, if the entry is for `key`
+/// and the binary matches its hash.
+fn decode_entry<'a>(key: &ProgramCacheKey, data: &'a [u8]) -> Option<&'a [u8]> {
+    let mut data = data.strip_prefix(ENTRY_MAGIC)?;
+    if ProgramCacheKey::decode(&mut data)? != *key {
+        return None;
+    }
+    let binary = decode_bytes(&mut data)?;
+    let hash = decode_u64(&mut data)?;
+    (data.is_empty() && hash == fnv1a(FNV_OFFSET_BASIS, binary)).then_some(binary)
+}
+
+/// How a program was obtained from a [`ProgramCache`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CacheOutcome {
+    /// The program was created from a cached binary.
+    Hit,
+    /// The program was built from source.
+    Miss,
+    /// The cached binary was rejected by the device or failed to build,
+    /// so the program was built from source.
+    Rejected,
+}
+
+/// The reasons that a [`ProgramCache`] may fail to provide a program.
+#[derive(Debug, Error)]
+pub enum ProgramCacheError {
+    #[error("program build failed: {}\n{log}", error_text(*status))]
+    Build { status: cl_int, log: String },
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+/// Implement the From trait
+impl From<cl_int> for ProgramCacheError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<ProgramCacheError> for ClError {
+    fn from(error: ProgramCacheError) -> Self {
+        match error {
+            ProgramCacheError::Build { status, .. } | ProgramCacheError::Cl(status) => Self(status),
+        }
+    }
+}
+
+/// An on-disk cache of `OpenCL` program binaries, see the module documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramCache {
+    directory: PathBuf,
+    max_entry_size: u64,
+    max_total_size: u64,
+}
+
+impl ProgramCache {
+    /// Create a cache in a directory, the directory is created when the
+    /// first entry is stored.
+    ///
+    /// * `directory` - the cache directory.
+    #[must_use]
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            max_entry_size: DEFAULT_MAX_ENTRY_SIZE,
+            max_total_size: DEFAULT_MAX_TOTAL_SIZE,
+        }
+    }
+
+    /// Set the maximum size of a cache entry in bytes, larger binaries are not stored.
+    #[must_use]
+    pub const fn with_max_entry_size(mut self, max_entry_size: u64) -> Self {
+        self.max_entry_size = max_entry_size;
+        self
+    }
+
+    /// Set the maximum size in bytes of all of the entries in the cache.
+    #[must_use]
+    pub const fn with_max_total_size(mut self, max_total_size: u64) -> Self {
+        self.max_total_size = max_total_size;
+        self
+    }
+
+    /// The cache directory.
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// The path of the cache entry for a key.
+    #[must_use]
+    pub fn entry_path(&self, key: &ProgramCacheKey) -> PathBuf {
+        self.directory.join(key.file_name())
+    }
+
+    /// Get a built program for a device, from the cache if possible.
+    ///
+    /// On a cache hit, the program is created from the cached binary with
+    /// clCreateProgramWithBinary and built. On a miss, the program is created
+    /// from `source` and built, and the program binary is stored in the cache.
+    /// A cached binary that is rejected or fails to build is a miss and its
+    /// entry is removed.
+    /// Failures to store an entry are ignored, since the cache is an optimisation.
+    ///
+    /// The hash in an entry detects corrupt entries, not malicious ones: the
+    /// cache directory must only be writable by trusted users.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `device` - a device in the context to build the program for.
+    /// * `source` - the program source or IL.
+    /// * `options` - the build options.
+    ///
+    /// returns a Result containing the built program and how it was obtained,
+    /// or a `ProgramCacheError`.
+    pub fn build(
+        &self,
+        context: cl_context,
+        device: cl_device_id,
+        source: ProgramSource<'_>,
+        options: &CStr,
+    ) -> Result<(cl_program, CacheOutcome), ProgramCacheError> {
+        let key = ProgramCacheKey::new(device, source, options)?;
+        let path = self.entry_path(&key);
+
+        let mut outcome = CacheOutcome::Miss;
+        if let Some(binary) = self.load(&key, &path) {
+            match build_from_binary(context, device, &binary, options) {
+                Ok(program) => {
+                    touch(&path);
+                    return Ok((program, CacheOutcome::Hit));
+                }
+                Err(
+                    ProgramCacheError::Cl(CL_INVALID_BINARY)
+                    | ProgramCacheError::Build {
+                        status: CL_INVALID_BINARY | CL_BUILD_PROGRAM_FAILURE,
+                        ..
+                    },
+                ) => {
+                    let _ = fs::remove_file(&path);
+                    outcome = CacheOutcome::Rejected;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        let program = source.create_program(context)?;
+        build(program, device, options)?;
+        if let Some(binary) = program_binary(program, device) {
+            let _ = self.store(&key, &path, &binary);
+        }
+        Ok((program, outcome))
+    }
+
+    /// Load the program binary of a key, if it is in the cache.
+    fn load(&self, key: &ProgramCacheKey, path: &Path) -> Option<Vec<u8>> {
+        let metadata = fs::metadata(path).ok()?;
+        if metadata.len() > self.max_entry_size.saturating_add(4096) {
+            return None;
+        }
+        let data = fs::read(path).ok()?;
+        decode_entry(key, &data).map(<[u8]>::to_vec)
+    }
+
+    /// Store the program binary of a key atomically, then trim the cache.
+    fn store(&self, key: &ProgramCacheKey, path: &Path, binary: &[u8]) -> io::Result<()> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        if binary.is_empty() || binary.len() as u64 > self.max_entry_size {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.directory)?;
+
+        let temp_path = self.directory.join(format!(
+            ".{}.{}.{}.tmp",
+            key.file_name(),
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result = File::create(&temp_path).and_then(|mut file| {
+            file.write_all(&encode_entry(key, binary))?;
+            file.sync_all()
+        });
+        let result = result.and_then(|()| fs::rename(&temp_path, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result?;
+        self.trim()
+    }
+
+    /// Remove the least recently used entries until the cache is no larger
+    /// than its maximum size.
+    ///
+    /// returns an empty Result or the `io::Error`.
+    pub fn trim(&self) -> io::Result<()> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|&(_, _, modified)| modified);
+        for (path, size, _) in entries {
+            if total <= self.max_total_size {
+                break;
+            }
+            remove_entry(&path)?;
+            total -= size;
+        }
+        Ok(())
+    }
+
+    /// Remove all of the entries in the cache.
+    ///
+    /// returns an empty Result or the `io::Error`.
+    pub fn clear(&self) -> io::Result<()> {
+        for (path, _, _) in self.entries()? {
+            remove_entry(&path)?;
+        }
+        Ok(())
+    }
+
+    /// The paths, sizes and modification times of the cache entries.
+    fn entries(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let read_dir = match fs::read_dir(&self.directory) {
+            Ok(read_dir) => read_dir,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+        let mut entries = Vec::new();
+        for entry in read_dir {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == ENTRY_EXTENSION) {
+                // Another process may have removed the entry.
+                let metadata = match fs::metadata(&path) {
+                    Ok(metadata) => metadata,
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                    Err(error) => return Err(error),
+                };
+                entries.push((
+                    path,
+                    metadata.len(),
+                    metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                ));
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Remove an entry, ignoring an entry that another process has already removed.
+fn remove_entry(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+/// Update the modification time of an entry, so that it is recently used.
+fn touch(path: &Path) {
+    let _ = File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+}
+
+/// Build a program for a device, releasing the program if the build fails.
+fn build(
+    program: cl_program,
+    device: cl_device_id,
+    options: &CStr,
+) -> Result<(), ProgramCacheError> {
+    build_program(program, &[device], options, None, ptr::null_mut()).map_err(|status| {
+        let log = get_program_build_info(program, device, CL_PROGRAM_BUILD_LOG)
+            .map(String::from)
+            .unwrap_or_default();
+        unsafe {
+            let _ = release_program(program);
+        }
+        ProgramCacheError::Build { status, log }
+    })
+}
+
+/// Create a program from a binary and build it for a device.
+fn build_from_binary(
+    context: cl_context,
+    device: cl_device_id,
+    binary: &[u8],
+    options: &CStr,
+) -> Result<cl_program, ProgramCacheError> {
+    let program = unsafe { create_program_with_binary(context, &[device], &[binary])? };
+    build(program, device, options)?;
+    Ok(program)
+}
+
+/// The program binary of a built program for a device.
+fn program_binary(program: cl_program, device: cl_device_id) -> Option<Vec<u8>> {
+    let devices = get_program_info(program, CL_PROGRAM_DEVICES)
+        .ok()?
+        .to_vec_intptr();
+    let index = devices.iter().position(|&id| id == device as intptr_t)?;
+    let mut binaries = get_program_info(program, CL_PROGRAM_BINARIES)
+        .ok()?
+        .to_vec_vec_uchar();
+    (index < binaries.len()).then(|| binaries.swap_remove(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    fn test_key(options: &str) -> ProgramCacheKey {
+        let source = ProgramSource::Source("kernel void noop() {}");
+        ProgramCacheKey {
+            source_hash: fnv1a(fnv1a(FNV_OFFSET_BASIS, &[source.tag()]), source.bytes()),
+            source_len: source.bytes().len() as u64,
+            options: String::from(options),
+            device_name: String::from("Test Device"),
+            driver_version: String::from("1.0"),
+            platform_version: String::from("OpenCL 3.0 Test"),
+        }
+    }
+
+    fn test_cache(name: &str) -> ProgramCache {
+        let directory =
+            std::env::temp_dir().join(format!("cl3_program_cache_{name}_{}", process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        ProgramCache::new(directory)
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(FNV_OFFSET_BASIS, fnv1a(FNV_OFFSET_BASIS, b""));
+        assert_eq!(0xaf63_dc4c_8601_ec8c, fnv1a(FNV_OFFSET_BASIS, b"a"));
+        assert_eq!(0x8594_4171_f739_67e8, fnv1a(FNV_OFFSET_BASIS, b"foobar"));
+    }
+
+    #[test]
+    fn test_program_cache_key() {
+        let key = test_key("-cl-fast-relaxed-math");
+        assert_eq!(key, test_key("-cl-fast-relaxed-math"));
+        assert_eq!(key.hash(), test_key("-cl-fast-relaxed-math").hash());
+        assert_ne!(key.hash(), test_key("").hash());
+        assert_eq!(
+            Some("clbin"),
+            Path::new(&key.file_name())
+                .extension()
+                .and_then(|ext| ext.to_str())
+        );
+        assert_eq!(22, key.file_name().len());
+
+        let mut buffer = Vec::new();
+        key.encode(&mut buffer);
+        let mut data = buffer.as_slice();
+        assert_eq!(Some(key), ProgramCacheKey::decode(&mut data));
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_cache_entry() {
+        let key = test_key("");
+        let binary = [1_u8, 2, 3, 4];
+        let entry = encode_entry(&key, &binary);
+        assert_eq!(Some(&binary[..]), decode_entry(&key, &entry));
+
+        // A different key, a truncated entry or a bad magic are rejected
+        assert!(decode_entry(&test_key("-O0"), &entry).is_none());
+        assert!(decode_entry(&key, &entry[..entry.len() - 1]).is_none());
+        let mut bad_magic = entry.clone();
+        bad_magic[0] = b'X';
+        assert!(decode_entry(&key, &bad_magic).is_none());
+
+        // A corrupt binary does not match its hash
+        let mut corrupt = entry;
+        let index = corrupt.len() - 9;
+        corrupt[index] ^= 0xff;
+        assert!(decode_entry(&key, &corrupt).is_none());
+    }
+
+    #[test]
+    fn test_store_load_and_trim() {
+        let cache = test_cache("store").with_max_entry_size(64);
+        let key_0 = test_key("0");
+        let key_1 = test_key("1");
+        let path_0 = cache.entry_path(&key_0);
+        let path_1 = cache.entry_path(&key_1);
+
+        assert!(cache.load(&key_0, &path_0).is_none());
+        cache.store(&key_0, &path_0, &[0; 32]).unwrap();
+        assert_eq!(Some(vec![0; 32]), cache.load(&key_0, &path_0));
+        // The wrong key for an entry is a miss
+        assert!(cache.load(&key_1, &path_0).is_none());
+
+        // Entries larger than the maximum entry size are not stored
+        cache.store(&key_1, &path_1, &[1; 65]).unwrap();
+        assert!(cache.load(&key_1, &path_1).is_none());
+
+        thread::sleep(Duration::from_millis(20));
+        cache.store(&key_1, &path_1, &[1; 64]).unwrap();
+        assert_eq!(2, cache.entries().unwrap().len());
+
+        // Trimming removes the least recently used entry
+        let entry_size = fs::metadata(&path_1).unwrap().len();
+        let cache = cache.with_max_total_size(entry_size);
+        cache.trim().unwrap();
+        assert!(!path_0.exists());
+        assert!(path_1.exists());
+
+        // An entry removed by another process is ignored
+        remove_entry(&path_1).unwrap();
+        remove_entry(&path_1).unwrap();
+        cache.trim().unwrap();
+
+        cache.store(&key_0, &path_0, &[0; 32]).unwrap();
+        cache.clear().unwrap();
+        assert!(cache.entries().unwrap().is_empty());
+        let _ = fs::remove_dir_all(cache.directory());
+    }
+}
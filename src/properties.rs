@@ -0,0 +1,776 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed builders and parsers of `OpenCL` property lists.
+//!
+//! Functions such as `create_context`, `create_command_queue_with_properties`,
+//! `create_buffer_with_properties`, `create_sampler_with_properties` and
+//! `create_semaphore_with_properties_khr` take zero terminated arrays of
+//! property name and value pairs, where some values are themselves zero
+//! terminated lists. [`Properties`] holds typed entries, e.g. [`QueueProperty`],
+//! and [`Properties::to_vec`] encodes them into a correctly terminated array.
+//!
+//! [`Properties::parse`] decodes the arrays returned by queries such as
+//! `CL_CONTEXT_PROPERTIES` and `CL_QUEUE_PROPERTIES_ARRAY` back into typed
+//! entries. Properties that are not known to this module are held as `Other`
+//! name and value pairs.
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{
+    CL_CONTEXT_INTEROP_USER_SYNC, CL_CONTEXT_PLATFORM, CL_FALSE, CL_INVALID_PROPERTY,
+    CL_MEM_DEVICE_HANDLE_LIST_END_KHR, CL_MEM_DEVICE_HANDLE_LIST_KHR, CL_QUEUE_PRIORITY_KHR,
+    CL_QUEUE_PROPERTIES, CL_QUEUE_SIZE, CL_QUEUE_THROTTLE_KHR, CL_SAMPLER_ADDRESSING_MODE,
+    CL_SAMPLER_FILTER_MODE, CL_SAMPLER_MIP_FILTER_MODE_KHR, CL_SAMPLER_NORMALIZED_COORDS,
+    CL_SEMAPHORE_DEVICE_HANDLE_LIST_END_KHR, CL_SEMAPHORE_DEVICE_HANDLE_LIST_KHR,
+    CL_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR, CL_SEMAPHORE_EXPORT_HANDLE_TYPES_LIST_END_KHR,
+    CL_SEMAPHORE_TYPE_KHR, CL_TRUE, cl_addressing_mode, cl_command_queue,
+    cl_command_queue_properties, cl_context, cl_context_properties, cl_device_id,
+    cl_external_semaphore_handle_type_khr, cl_filter_mode, cl_int, cl_mem, cl_mem_properties,
+    cl_pipe_properties, cl_platform_id, cl_queue_priority_khr, cl_queue_properties,
+    cl_queue_throttle_khr, cl_sampler, cl_sampler_properties, cl_semaphore_properties_khr,
+    cl_semaphore_type_khr, cl_uint, cl_ulong,
+};
+
+use super::command_queue::get_command_queue_info;
+use super::context::get_context_info;
+use super::error_codes::{ClError, error_text};
+use super::memory::get_mem_object_info;
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "dynamic"))]
+use super::memory::get_pipe_info;
+use super::sampler::get_sampler_info;
+use libc::intptr_t;
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "dynamic"))]
+use opencl_sys::CL_PIPE_PROPERTIES;
+use opencl_sys::{
+    CL_CONTEXT_PROPERTIES, CL_MEM_PROPERTIES, CL_QUEUE_PROPERTIES_ARRAY, CL_SAMPLER_PROPERTIES,
+};
+use std::fmt::Debug;
+use std::ptr;
+use thiserror::Error;
+
+/// The reasons that a property list may fail to parse.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum PropertyError {
+    #[error("the property list is not zero terminated")]
+    Unterminated,
+    #[error("property {0:#x} has no value")]
+    MissingValue(cl_ulong),
+    #[error("the value list of property {0:#x} is not zero terminated")]
+    UnterminatedList(cl_ulong),
+    #[error("property {0:#x} has an invalid value")]
+    InvalidValue(cl_ulong),
+    #[error("property {0:#x} is specified more than once")]
+    Duplicate(cl_ulong),
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl PropertyError {
+    /// The `OpenCL` error code that a create function would have returned
+    /// for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::Cl(error) => *error,
+            _ => CL_INVALID_PROPERTY,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for PropertyError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<PropertyError> for ClError {
+    fn from(error: PropertyError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// The integer type of the elements of a property list:
+/// `cl_ulong` or `intptr_t`.
+pub trait PropertyValue: Copy + Debug + Eq {
+    /// The value as a 64 bit pattern.
+    fn to_bits(self) -> cl_ulong;
+    /// The value from a 64 bit pattern.
+    fn from_bits(bits: cl_ulong) -> Self;
+}
+
+impl PropertyValue for cl_ulong {
+    fn to_bits(self) -> cl_ulong {
+        self
+    }
+
+    fn from_bits(bits: cl_ulong) -> Self {
+        bits
+    }
+}
+
+#[allow(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap
+)]
+impl PropertyValue for intptr_t {
+    fn to_bits(self) -> cl_ulong {
+        self as cl_ulong
+    }
+
+    fn from_bits(bits: cl_ulong) -> Self {
+        bits as Self
+    }
+}
+
+/// A typed entry of a property list.
+pub trait Property: Sized {
+    /// The element type of the property list.
+    type Value: PropertyValue;
+
+    /// The property name.
+    fn name(&self) -> Self::Value;
+
+    /// Append the property name and value(s) to a property list.
+    fn encode(&self, list: &mut Vec<Self::Value>);
+
+    /// Decode a property from its name and the elements that follow it.
+    ///
+    /// returns a Result containing the property and the number of elements
+    /// of `values` that it used or a `PropertyError`.
+    fn decode(name: Self::Value, values: &[Self::Value]) -> Result<(Self, usize), PropertyError>;
+}
+
+/// The typed entries of a property list, see the module documentation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Properties<P> {
+    entries: Vec<P>,
+}
+
+impl<P> Default for Properties<P> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<P: Property> Properties<P> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a property, replacing any property with the same name.
+    #[must_use]
+    pub fn with(mut self, property: P) -> Self {
+        self.push(property);
+        self
+    }
+
+    /// Add a property, replacing any property with the same name.
+    pub fn push(&mut self, property: P) {
+        let name = property.name();
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.name() == name) {
+            *entry = property;
+        } else {
+            self.entries.push(property);
+        }
+    }
+
+    /// The property with a name, if present.
+    #[must_use]
+    pub fn get(&self, name: P::Value) -> Option<&P> {
+        self.entries.iter().find(|entry| entry.name() == name)
+    }
+
+    #[must_use]
+    pub fn entries(&self) -> &[P] {
+        &self.entries
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The zero terminated property list.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<P::Value> {
+        let mut list = Vec::new();
+        for entry in &self.entries {
+            entry.encode(&mut list);
+        }
+        list.push(P::Value::from_bits(0));
+        list
+    }
+
+    /// Parse a property list, e.g. from `CL_QUEUE_PROPERTIES_ARRAY`.
+    ///
+    /// An empty list contains no properties, otherwise the list must be zero
+    /// terminated. Any elements after the terminator are ignored.
+    ///
+    /// returns a Result containing the properties or a `PropertyError`.
+    pub fn parse(values: &[P::Value]) -> Result<Self, PropertyError> {
+        let mut properties = Self::new();
+        let mut index = 0;
+        while let Some(name) = values.get(index) {
+            if name.to_bits() == 0 {
+                return Ok(properties);
+            }
+            if properties.get(*name).is_some() {
+                return Err(PropertyError::Duplicate(name.to_bits()));
+            }
+            let (property, used) = P::decode(*name, &values[index + 1..])?;
+            properties.entries.push(property);
+            index += 1 + used;
+        }
+
+        if values.is_empty() {
+            Ok(properties)
+        } else {
+            Err(PropertyError::Unterminated)
+        }
+    }
+}
+
+impl<P: Property> FromIterator<P> for Properties<P> {
+    fn from_iter<I: IntoIterator<Item = P>>(iter: I) -> Self {
+        let mut properties = Self::new();
+        for property in iter {
+            properties.push(property);
+        }
+        properties
+    }
+}
+
+/// Compare a property name with a name constant.
+fn is<V: PropertyValue>(name: V, constant: impl Into<cl_ulong>) -> bool {
+    name.to_bits() == constant.into()
+}
+
+/// The single value of a property.
+fn single_value<V: PropertyValue>(name: V, values: &[V]) -> Result<V, PropertyError> {
+    values
+        .first()
+        .copied()
+        .ok_or_else(|| PropertyError::MissingValue(name.to_bits()))
+}
+
+/// The single `cl_uint` value of a property.
+fn uint_value<V: PropertyValue>(name: V, values: &[V]) -> Result<cl_uint, PropertyError> {
+    cl_uint::try_from(single_value(name, values)?.to_bits())
+        .map_err(|_| PropertyError::InvalidValue(name.to_bits()))
+}
+
+/// The single `cl_bool` value of a property.
+fn bool_value<V: PropertyValue>(name: V, values: &[V]) -> Result<bool, PropertyError> {
+    match uint_value(name, values)? {
+        CL_FALSE => Ok(false),
+        CL_TRUE => Ok(true),
+        _ => Err(PropertyError::InvalidValue(name.to_bits())),
+    }
+}
+
+/// The zero terminated value list of a property.
+///
+/// returns a Result containing the values without the terminator and the
+/// number of elements used, including the terminator, or a `PropertyError`.
+fn value_list<V: PropertyValue>(name: V, values: &[V]) -> Result<(&[V], usize), PropertyError> {
+    values
+        .iter()
+        .position(|value| value.to_bits() == 0)
+        .map(|end| (&values[..end], end + 1))
+        .ok_or_else(|| PropertyError::UnterminatedList(name.to_bits()))
+}
+
+/// Encode a `cl_bool` property value.
+fn bool_bits(value: bool) -> cl_ulong {
+    cl_ulong::from(if value { CL_TRUE } else { CL_FALSE })
+}
+
+/// Encode an `OpenCL` object handle property value.
+fn handle_bits<T>(handle: *mut T) -> cl_ulong {
+    handle.expose_provenance() as cl_ulong
+}
+
+/// Decode an `OpenCL` object handle property value.
+#[allow(clippy::cast_possible_truncation)]
+const fn handle<T>(bits: cl_ulong) -> *mut T {
+    ptr::with_exposed_provenance_mut(bits as usize)
+}
+
+/// Encode a zero terminated list of handles.
+fn encode_handles<V: PropertyValue, T>(list: &mut Vec<V>, handles: &[*mut T]) {
+    list.extend(handles.iter().map(|h| V::from_bits(handle_bits(*h))));
+    list.push(V::from_bits(0));
+}
+
+/// Decode a zero terminated list of handles.
+fn decode_handles<V: PropertyValue, T>(
+    name: V,
+    values: &[V],
+) -> Result<(Vec<*mut T>, usize), PropertyError> {
+    let (list, used) = value_list(name, values)?;
+    Ok((list.iter().map(|v| handle(v.to_bits())).collect(), used))
+}
+
+/// An entry of a `cl_context_properties` list, see `create_context`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextProperty {
+    /// `CL_CONTEXT_PLATFORM`
+    Platform(cl_platform_id),
+    /// `CL_CONTEXT_INTEROP_USER_SYNC`
+    InteropUserSync(bool),
+    /// Any other property name and value, e.g. an interop device handle.
+    Other(cl_context_properties, cl_context_properties),
+}
+
+impl Property for ContextProperty {
+    type Value = cl_context_properties;
+
+    fn name(&self) -> Self::Value {
+        match self {
+            Self::Platform(_) => CL_CONTEXT_PLATFORM,
+            Self::InteropUserSync(_) => CL_CONTEXT_INTEROP_USER_SYNC,
+            Self::Other(name, _) => *name,
+        }
+    }
+
+    fn encode(&self, list: &mut Vec<Self::Value>) {
+        list.push(self.name());
+        list.push(match self {
+            Self::Platform(platform) => Self::Value::from_bits(handle_bits(*platform)),
+            Self::InteropUserSync(value) => Self::Value::from_bits(bool_bits(*value)),
+            Self::Other(_, value) => *value,
+        });
+    }
+
+    fn decode(name: Self::Value, values: &[Self::Value]) -> Result<(Self, usize), PropertyError> {
+        let property = match name {
+            CL_CONTEXT_PLATFORM => Self::Platform(handle(single_value(name, values)?.to_bits())),
+            CL_CONTEXT_INTEROP_USER_SYNC => Self::InteropUserSync(bool_value(name, values)?),
+            _ => Self::Other(name, single_value(name, values)?),
+        };
+        Ok((property, 1))
+    }
+}
+
+/// An entry of a `cl_queue_properties` list,
+/// see `create_command_queue_with_properties`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QueueProperty {
+    /// `CL_QUEUE_PROPERTIES`, e.g. `CL_QUEUE_PROFILING_ENABLE`.
+    Properties(cl_command_queue_properties),
+    /// `CL_QUEUE_SIZE` of an on-device queue.
+    Size(cl_uint),
+    /// `CL_QUEUE_PRIORITY_KHR` from `cl_khr_priority_hints`.
+    Priority(cl_queue_priority_khr),
+    /// `CL_QUEUE_THROTTLE_KHR` from `cl_khr_throttle_hints`.
+    Throttle(cl_queue_throttle_khr),
+    /// Any other property name and value.
+    Other(cl_queue_properties, cl_queue_properties),
+}
+
+impl Property for QueueProperty {
+    type Value = cl_queue_properties;
+
+    fn name(&self) -> Self::Value {
+        match self {
+            Self::Properties(_) => CL_QUEUE_PROPERTIES.into(),
+            Self::Size(_) => CL_QUEUE_SIZE.into(),
+            Self::Priority(_) => CL_QUEUE_PRIORITY_KHR,
+            Self::Throttle(_) => CL_QUEUE_THROTTLE_KHR,
+            Self::Other(name, _) => *name,
+        }
+    }
+
+    fn encode(&self, list: &mut Vec<Self::Value>) {
+        list.push(self.name());
+        list.push(match self {
+            Self::Properties(value) | Self::Other(_, value) => *value,
+            Self::Size(value) | Self::Priority(value) | Self::Throttle(value) => (*value).into(),
+        });
+    }
+
+    fn decode(name: Self::Value, values: &[Self::Value]) -> Result<(Self, usize), PropertyError> {
+        let property = if is(name, CL_QUEUE_PROPERTIES) {
+            Self::Properties(single_value(name, values)?)
+        } else if is(name, CL_QUEUE_SIZE) {
+            Self::Size(uint_value(name, values)?)
+        } else if is(name, CL_QUEUE_PRIORITY_KHR) {
+            Self::Priority(uint_value(name, values)?)
+        } else if is(name, CL_QUEUE_THROTTLE_KHR) {
+            Self::Throttle(uint_value(name, values)?)
+        } else {
+            Self::Other(name, single_value(name, values)?)
+        };
+        Ok((property, 1))
+    }
+}
+
+/// An entry of a `cl_mem_properties` list, see `create_buffer_with_properties`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MemProperty {
+    /// `CL_MEM_DEVICE_HANDLE_LIST_KHR` from `cl_khr_external_memory`.
+    DeviceHandleList(Vec<cl_device_id>),
+    /// Any other property name and value, e.g. an external memory handle
+    /// type and handle.
+    Other(cl_mem_properties, cl_mem_properties),
+}
+
+impl Property for MemProperty {
+    type Value = cl_mem_properties;
+
+    fn name(&self) -> Self::Value {
+        match self {
+            Self::DeviceHandleList(_) => CL_MEM_DEVICE_HANDLE_LIST_KHR,
+            Self::Other(name, _) => *name,
+        }
+    }
+
+    fn encode(&self, list: &mut Vec<Self::Value>) {
+        list.push(self.name());
+        match self {
+            Self::DeviceHandleList(devices) => encode_handles(list, devices),
+            Self::Other(_, value) => list.push(*value),
+        }
+    }
+
+    fn decode(name: Self::Value, values: &[Self::Value]) -> Result<(Self, usize), PropertyError> {
+        if name == CL_MEM_DEVICE_HANDLE_LIST_KHR {
+            let (devices, used) = decode_handles(name, values)?;
+            Ok((Self::DeviceHandleList(devices), used))
+        } else {
+            Ok((Self::Other(name, single_value(name, values)?), 1))
+        }
+    }
+}
+
+/// An entry of a `cl_sampler_properties` list,
+/// see `create_sampler_with_properties`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SamplerProperty {
+    /// `CL_SAMPLER_NORMALIZED_COORDS`
+    NormalizedCoords(bool),
+    /// `CL_SAMPLER_ADDRESSING_MODE`, e.g. `CL_ADDRESS_CLAMP`.
+    AddressingMode(cl_addressing_mode),
+    /// `CL_SAMPLER_FILTER_MODE`, e.g. `CL_FILTER_LINEAR`.
+    FilterMode(cl_filter_mode),
+    /// `CL_SAMPLER_MIP_FILTER_MODE_KHR` from `cl_khr_mipmap_image`.
+    MipFilterMode(cl_filter_mode),
+    /// Any other property name and value.
+    Other(cl_sampler_properties, cl_sampler_properties),
+}
+
+impl Property for SamplerProperty {
+    type Value = cl_sampler_properties;
+
+    fn name(&self) -> Self::Value {
+        match self {
+            Self::NormalizedCoords(_) => CL_SAMPLER_NORMALIZED_COORDS.into(),
+            Self::AddressingMode(_) => CL_SAMPLER_ADDRESSING_MODE.into(),
+            Self::FilterMode(_) => CL_SAMPLER_FILTER_MODE.into(),
+            Self::MipFilterMode(_) => CL_SAMPLER_MIP_FILTER_MODE_KHR,
+            Self::Other(name, _) => *name,
+        }
+    }
+
+    fn encode(&self, list: &mut Vec<Self::Value>) {
+        list.push(self.name());
+        list.push(match self {
+            Self::NormalizedCoords(value) => bool_bits(*value),
+            Self::AddressingMode(value) | Self::FilterMode(value) | Self::MipFilterMode(value) => {
+                (*value).into()
+            }
+            Self::Other(_, value) => *value,
+        });
+    }
+
+    fn decode(name: Self::Value, values: &[Self::Value]) -> Result<(Self, usize), PropertyError> {
+        let property = if is(name, CL_SAMPLER_NORMALIZED_COORDS) {
+            Self::NormalizedCoords(bool_value(name, values)?)
+        } else if is(name, CL_SAMPLER_ADDRESSING_MODE) {
+            Self::AddressingMode(uint_value(name, values)?)
+        } else if is(name, CL_SAMPLER_FILTER_MODE) {
+            Self::FilterMode(uint_value(name, values)?)
+        } else if is(name, CL_SAMPLER_MIP_FILTER_MODE_KHR) {
+            Self::MipFilterMode(uint_value(name, values)?)
+        } else {
+            Self::Other(name, single_value(name, values)?)
+        };
+        Ok((property, 1))
+    }
+}
+
+/// An entry of a `cl_pipe_properties` list, see `create_pipe`.
+/// `OpenCL` 3.0 does not define any pipe properties.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PipeProperty {
+    /// A property name and value.
+    Other(cl_pipe_properties, cl_pipe_properties),
+}
+
+impl Property for PipeProperty {
+    type Value = cl_pipe_properties;
+
+    fn name(&self) -> Self::Value {
+        let Self::Other(name, _) = self;
+        *name
+    }
+
+    fn encode(&self, list: &mut Vec<Self::Value>) {
+        let Self::Other(name, value) = self;
+        list.push(*name);
+        list.push(*value);
+    }
+
+    fn decode(name: Self::Value, values: &[Self::Value]) -> Result<(Self, usize), PropertyError> {
+        Ok((Self::Other(name, single_value(name, values)?), 1))
+    }
+}
+
+/// An entry of a `cl_semaphore_properties_khr` list,
+/// see `create_semaphore_with_properties_khr`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SemaphoreProperty {
+    /// `CL_SEMAPHORE_TYPE_KHR`, e.g. `CL_SEMAPHORE_TYPE_BINARY_KHR`.
+    Type(cl_semaphore_type_khr),
+    /// `CL_SEMAPHORE_DEVICE_HANDLE_LIST_KHR`
+    DeviceHandleList(Vec<cl_device_id>),
+    /// `CL_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR` from `cl_khr_external_semaphore`.
+    ExportHandleTypes(Vec<cl_external_semaphore_handle_type_khr>),
+    /// Any other property name and value, e.g. an external semaphore handle
+    /// type and handle to import.
+    Other(cl_semaphore_properties_khr, cl_semaphore_properties_khr),
+}
+
+impl Property for SemaphoreProperty {
+    type Value = cl_semaphore_properties_khr;
+
+    fn name(&self) -> Self::Value {
+        match self {
+            Self::Type(_) => CL_SEMAPHORE_TYPE_KHR.into(),
+            Self::DeviceHandleList(_) => CL_SEMAPHORE_DEVICE_HANDLE_LIST_KHR.into(),
+            Self::ExportHandleTypes(_) => CL_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR,
+            Self::Other(name, _) => *name,
+        }
+    }
+
+    fn encode(&self, list: &mut Vec<Self::Value>) {
+        list.push(self.name());
+        match self {
+            Self::Type(value) => list.push((*value).into()),
+            Self::DeviceHandleList(devices) => encode_handles(list, devices),
+            Self::ExportHandleTypes(handle_types) => {
+                list.extend(handle_types.iter().map(|t| Self::Value::from(*t)));
+                list.push(CL_SEMAPHORE_EXPORT_HANDLE_TYPES_LIST_END_KHR);
+            }
+            Self::Other(_, value) => list.push(*value),
+        }
+    }
+
+    fn decode(name: Self::Value, values: &[Self::Value]) -> Result<(Self, usize), PropertyError> {
+        if is(name, CL_SEMAPHORE_TYPE_KHR) {
+            Ok((Self::Type(uint_value(name, values)?), 1))
+        } else if is(name, CL_SEMAPHORE_DEVICE_HANDLE_LIST_KHR) {
+            let (devices, used) = decode_handles(name, values)?;
+            Ok((Self::DeviceHandleList(devices), used))
+        } else if name == CL_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR {
+            let (list, used) = value_list(name, values)?;
+            let handle_types = list
+                .iter()
+                .map(|value| {
+                    cl_uint::try_from(*value).map_err(|_| PropertyError::InvalidValue(name))
+                })
+                .collect::<Result<_, _>>()?;
+            Ok((Self::ExportHandleTypes(handle_types), used))
+        } else {
+            Ok((Self::Other(name, single_value(name, values)?), 1))
+        }
+    }
+}
+
+/// The properties of a context: `CL_CONTEXT_PROPERTIES`.
+///
+/// * `context` - the `OpenCL` context.
+///
+/// returns a Result containing the context properties
+/// or a `PropertyError`.
+pub fn get_context_properties(
+    context: cl_context,
+) -> Result<Properties<ContextProperty>, PropertyError> {
+    let values: Vec<intptr_t> = get_context_info(context, CL_CONTEXT_PROPERTIES)?.into();
+    Properties::parse(&values)
+}
+
+/// The properties that a command-queue was created with:
+/// `CL_QUEUE_PROPERTIES_ARRAY`, `CL_VERSION_3_0`.
+///
+/// * `queue` - the `OpenCL` command-queue.
+///
+/// returns a Result containing the command-queue properties
+/// or a `PropertyError`.
+pub fn get_queue_properties(
+    queue: cl_command_queue,
+) -> Result<Properties<QueueProperty>, PropertyError> {
+    let values: Vec<cl_ulong> = get_command_queue_info(queue, CL_QUEUE_PROPERTIES_ARRAY)?.into();
+    Properties::parse(&values)
+}
+
+/// The properties that a memory object was created with:
+/// `CL_MEM_PROPERTIES`, `CL_VERSION_3_0`.
+///
+/// * `memobj` - the `OpenCL` memory object.
+///
+/// returns a Result containing the memory object properties
+/// or a `PropertyError`.
+pub fn get_mem_properties(memobj: cl_mem) -> Result<Properties<MemProperty>, PropertyError> {
+    let values: Vec<cl_ulong> = get_mem_object_info(memobj, CL_MEM_PROPERTIES)?.into();
+    Properties::parse(&values)
+}
+
+/// The properties that a sampler was created with:
+/// `CL_SAMPLER_PROPERTIES`, `CL_VERSION_3_0`.
+///
+/// * `sampler` - the `OpenCL` sampler.
+///
+/// returns a Result containing the sampler properties
+/// or a `PropertyError`.
+pub fn get_sampler_properties(
+    sampler: cl_sampler,
+) -> Result<Properties<SamplerProperty>, PropertyError> {
+    let values: Vec<cl_ulong> = get_sampler_info(sampler, CL_SAMPLER_PROPERTIES)?.into();
+    Properties::parse(&values)
+}
+
+/// The properties that a pipe was created with:
+/// `CL_PIPE_PROPERTIES`, `CL_VERSION_3_0`.
+///
+/// * `pipe` - the `OpenCL` pipe object.
+///
+/// returns a Result containing the pipe properties
+/// or a `PropertyError`.
+#[cfg(any(feature = "CL_VERSION_2_0", feature = "dynamic"))]
+pub fn get_pipe_properties(pipe: cl_mem) -> Result<Properties<PipeProperty>, PropertyError> {
+    let values: Vec<intptr_t> = get_pipe_info(pipe, CL_PIPE_PROPERTIES)?.into();
+    Properties::parse(&values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opencl_sys::{
+        CL_ADDRESS_CLAMP, CL_FILTER_LINEAR, CL_QUEUE_PROFILING_ENABLE, CL_SEMAPHORE_TYPE_BINARY_KHR,
+    };
+
+    #[test]
+    fn test_properties_round_trip() {
+        let platform: cl_platform_id = ptr::without_provenance_mut::<libc::c_void>(0x1000).cast();
+        let context = Properties::new()
+            .with(ContextProperty::Platform(platform))
+            .with(ContextProperty::InteropUserSync(true))
+            .with(ContextProperty::InteropUserSync(false));
+        let values = context.to_vec();
+        assert_eq!(
+            vec![
+                CL_CONTEXT_PLATFORM,
+                0x1000,
+                CL_CONTEXT_INTEROP_USER_SYNC,
+                0,
+                0
+            ],
+            values
+        );
+        assert_eq!(
+            context.entries(),
+            Properties::<ContextProperty>::parse(&values)
+                .unwrap()
+                .entries()
+        );
+
+        let queue = Properties::new()
+            .with(QueueProperty::Properties(CL_QUEUE_PROFILING_ENABLE))
+            .with(QueueProperty::Size(1024));
+        let values = queue.to_vec();
+        assert_eq!(5, values.len());
+        assert_eq!(queue, Properties::parse(&values).unwrap());
+
+        let sampler: Properties<_> = [
+            SamplerProperty::NormalizedCoords(true),
+            SamplerProperty::AddressingMode(CL_ADDRESS_CLAMP),
+            SamplerProperty::FilterMode(CL_FILTER_LINEAR),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(sampler, Properties::parse(&sampler.to_vec()).unwrap());
+
+        let device: cl_device_id = ptr::without_provenance_mut::<libc::c_void>(0x2000).cast();
+        let semaphore = Properties::new()
+            .with(SemaphoreProperty::Type(CL_SEMAPHORE_TYPE_BINARY_KHR))
+            .with(SemaphoreProperty::DeviceHandleList(vec![device]))
+            .with(SemaphoreProperty::ExportHandleTypes(vec![0x2058]));
+        let values = semaphore.to_vec();
+        assert_eq!(9, values.len());
+        assert_eq!(semaphore, Properties::parse(&values).unwrap());
+
+        assert!(Properties::<MemProperty>::parse(&[]).unwrap().is_empty());
+        assert_eq!(vec![0], Properties::<PipeProperty>::new().to_vec());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(PropertyError::Unterminated),
+            Properties::<QueueProperty>::parse(&[CL_QUEUE_SIZE.into(), 16])
+        );
+        assert_eq!(
+            Err(PropertyError::MissingValue(CL_QUEUE_SIZE.into())),
+            Properties::<QueueProperty>::parse(&[CL_QUEUE_SIZE.into()])
+        );
+        assert_eq!(
+            Err(PropertyError::InvalidValue(CL_QUEUE_SIZE.into())),
+            Properties::<QueueProperty>::parse(&[CL_QUEUE_SIZE.into(), 1 << 40, 0])
+        );
+        assert_eq!(
+            Err(PropertyError::Duplicate(CL_QUEUE_SIZE.into())),
+            Properties::<QueueProperty>::parse(&[
+                CL_QUEUE_SIZE.into(),
+                16,
+                CL_QUEUE_SIZE.into(),
+                32,
+                0
+            ])
+        );
+        assert_eq!(
+            Err(PropertyError::UnterminatedList(
+                CL_MEM_DEVICE_HANDLE_LIST_KHR
+            )),
+            Properties::<MemProperty>::parse(&[CL_MEM_DEVICE_HANDLE_LIST_KHR, 0x1000])
+        );
+        assert_eq!(
+            Err(PropertyError::InvalidValue(
+                CL_SAMPLER_NORMALIZED_COORDS.into()
+            )),
+            Properties::<SamplerProperty>::parse(&[CL_SAMPLER_NORMALIZED_COORDS.into(), 2, 0])
+        );
+        assert_eq!(
+            CL_INVALID_PROPERTY,
+            PropertyError::Unterminated.error_code()
+        );
+    }
+}
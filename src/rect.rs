@@ -0,0 +1,1202 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bounds checked rectangular buffer and image transfers.
+//!
+//! The rectangular transfer functions in [`enqueue`](crate::enqueue) take
+//! their origins and region as three element arrays and their row and slice
+//! pitches as separate values, which `OpenCL` trusts. The functions in this
+//! module take an [`Origin3`] and a [`Region3`], describe host memory with a
+//! [`PitchedView`] or [`PitchedViewMut`] over a slice and check the whole
+//! transfer against the size of the slice and of the buffer or image before
+//! it is enqueued.
+//!
+//! The `x` origin, width and pitches of a [`RectLayout`] of a buffer or host
+//! view are in elements of `T`, image origins and regions are in pixels.
+//! A pitch of zero is the tightly packed pitch of the region, as in `OpenCL`.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::too_many_arguments, clippy::not_unsafe_ptr_arg_deref)]
+
+pub use opencl_sys::{
+    CL_INVALID_VALUE, cl_bool, cl_command_queue, cl_event, cl_int, cl_mem, cl_mem_object_type,
+};
+
+use super::enqueue::{
+    enqueue_copy_buffer_rect, enqueue_copy_buffer_to_image, enqueue_copy_image,
+    enqueue_copy_image_to_buffer, enqueue_read_buffer_rect, enqueue_read_image,
+    enqueue_write_buffer_rect, enqueue_write_image,
+};
+use super::error_codes::{ClError, error_text};
+use super::info_type::InfoType;
+use super::memory::{
+    CL_IMAGE_ARRAY_SIZE, CL_IMAGE_DEPTH, CL_IMAGE_ELEMENT_SIZE, CL_IMAGE_HEIGHT, CL_IMAGE_WIDTH,
+    CL_MEM_SIZE, CL_MEM_TYPE, get_image_info, get_mem_object_info,
+};
+use super::types::Pod;
+use libc::{c_void, size_t};
+use opencl_sys::{
+    CL_MEM_OBJECT_IMAGE1D_ARRAY, CL_MEM_OBJECT_IMAGE2D, CL_MEM_OBJECT_IMAGE2D_ARRAY,
+    CL_MEM_OBJECT_IMAGE3D,
+};
+use std::mem;
+use thiserror::Error;
+
+/// The origin of a rectangular region.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Origin3 {
+    pub x: size_t,
+    pub y: size_t,
+    pub z: size_t,
+}
+
+impl Origin3 {
+    /// The origin at zero.
+    pub const ZERO: Self = Self::new(0, 0, 0);
+
+    #[must_use]
+    pub const fn new(x: size_t, y: size_t, z: size_t) -> Self {
+        Self { x, y, z }
+    }
+
+    /// The origin as the three element array of the `OpenCL` C API.
+    #[must_use]
+    pub const fn as_array(&self) -> [size_t; 3] {
+        [self.x, self.y, self.z]
+    }
+}
+
+/// Implement the From trait
+impl From<[size_t; 3]> for Origin3 {
+    fn from(origin: [size_t; 3]) -> Self {
+        Self::new(origin[0], origin[1], origin[2])
+    }
+}
+
+/// The size of a rectangular region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Region3 {
+    pub width: size_t,
+    pub height: size_t,
+    pub depth: size_t,
+}
+
+impl Region3 {
+    #[must_use]
+    pub const fn new(width: size_t, height: size_t, depth: size_t) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+        }
+    }
+
+    /// A region of one row.
+    #[must_use]
+    pub const fn d1(width: size_t) -> Self {
+        Self::new(width, 1, 1)
+    }
+
+    /// A region of one slice.
+    #[must_use]
+    pub const fn d2(width: size_t, height: size_t) -> Self {
+        Self::new(width, height, 1)
+    }
+
+    /// The region as the three element array of the `OpenCL` C API.
+    #[must_use]
+    pub const fn as_array(&self) -> [size_t; 3] {
+        [self.width, self.height, self.depth]
+    }
+
+    /// Whether any dimension of the region is zero.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0 || self.depth == 0
+    }
+}
+
+/// Implement the From trait
+impl From<[size_t; 3]> for Region3 {
+    fn from(region: [size_t; 3]) -> Self {
+        Self::new(region[0], region[1], region[2])
+    }
+}
+
+/// The reasons that a rectangular transfer may be rejected.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum RectError {
+    #[error("the region dimensions must be greater than zero")]
+    ZeroRegion,
+    #[error("pitch {pitch} must be a multiple of {multiple} and at least {min}")]
+    InvalidPitch {
+        pitch: size_t,
+        min: size_t,
+        multiple: size_t,
+    },
+    #[error("the region ends at {end}, beyond the size {size}")]
+    OutOfBounds { end: size_t, size: size_t },
+    #[error("the region overflows size_t")]
+    Overflow,
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl RectError {
+    /// The `OpenCL` error code that the transfer would have returned
+    /// for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::ZeroRegion
+            | Self::InvalidPitch { .. }
+            | Self::OutOfBounds { .. }
+            | Self::Overflow => CL_INVALID_VALUE,
+            Self::Cl(error) => *error,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for RectError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<RectError> for ClError {
+    fn from(error: RectError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// The origin and pitches of a rectangular region in a buffer or host
+/// memory, in elements of the transfer type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RectLayout {
+    origin: Origin3,
+    row_pitch: size_t,
+    slice_pitch: size_t,
+}
+
+/// A `RectLayout` resolved for a region, in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ByteRect {
+    offset: size_t,
+    row_pitch: size_t,
+    slice_pitch: size_t,
+}
+
+impl RectLayout {
+    /// A tightly packed layout from `origin`.
+    #[must_use]
+    pub const fn new(origin: Origin3) -> Self {
+        Self {
+            origin,
+            row_pitch: 0,
+            slice_pitch: 0,
+        }
+    }
+
+    /// The row pitch in elements, zero for the width of the region.
+    #[must_use]
+    pub const fn row_pitch(mut self, row_pitch: size_t) -> Self {
+        self.row_pitch = row_pitch;
+        self
+    }
+
+    /// The slice pitch in elements, zero for the row pitch times the height
+    /// of the region.
+    #[must_use]
+    pub const fn slice_pitch(mut self, slice_pitch: size_t) -> Self {
+        self.slice_pitch = slice_pitch;
+        self
+    }
+
+    /// The origin with `x` in bytes, as `clEnqueueReadBufferRect` requires.
+    fn byte_origin(&self, unit: size_t) -> Result<[size_t; 3], RectError> {
+        let x = self.origin.x.checked_mul(unit).ok_or(RectError::Overflow)?;
+        Ok([x, self.origin.y, self.origin.z])
+    }
+
+    /// Resolve the layout for a region and check it against `size`.
+    ///
+    /// * `unit` - the size in bytes of an element of the layout.
+    /// * `row_size` - the size in bytes of a row of the region.
+    /// * `region` - the region, only its height and depth are used.
+    /// * `size` - the size in bytes of the memory.
+    /// * `aligned_slices` - whether the slice pitch must be a multiple of the
+    ///   row pitch, as `clEnqueueReadBufferRect` requires.
+    ///
+    /// returns a Result containing the offset and pitches in bytes or a
+    /// `RectError`.
+    fn resolve(
+        &self,
+        unit: size_t,
+        row_size: size_t,
+        region: Region3,
+        size: size_t,
+        aligned_slices: bool,
+    ) -> Result<ByteRect, RectError> {
+        if region.is_empty() {
+            return Err(RectError::ZeroRegion);
+        }
+        let row_pitch = checked_pitch(self.row_pitch, unit, row_size, 0)?;
+        let slice_min = row_pitch
+            .checked_mul(region.height)
+            .ok_or(RectError::Overflow)?;
+        let multiple = if aligned_slices { row_pitch } else { 0 };
+        let slice_pitch = checked_pitch(self.slice_pitch, unit, slice_min, multiple)?;
+
+        let [x, y, z] = self.byte_origin(unit)?;
+        let offset = byte_offset(x, y, z, row_pitch, slice_pitch)?;
+        let last = byte_offset(
+            row_size,
+            region.height - 1,
+            region.depth - 1,
+            row_pitch,
+            slice_pitch,
+        )?;
+        let end = offset.checked_add(last).ok_or(RectError::Overflow)?;
+        if end > size {
+            return Err(RectError::OutOfBounds { end, size });
+        }
+        Ok(ByteRect {
+            offset,
+            row_pitch,
+            slice_pitch,
+        })
+    }
+}
+
+/// A pitched view of host memory to write from.
+#[derive(Clone, Copy, Debug)]
+pub struct PitchedView<'a, T> {
+    data: &'a [T],
+    layout: RectLayout,
+}
+
+impl<'a, T> PitchedView<'a, T> {
+    /// A tightly packed view of `data` from its start.
+    #[must_use]
+    pub const fn new(data: &'a [T]) -> Self {
+        Self {
+            data,
+            layout: RectLayout::new(Origin3::ZERO),
+        }
+    }
+
+    /// The origin of the region in the view.
+    #[must_use]
+    pub const fn origin(mut self, origin: Origin3) -> Self {
+        self.layout.origin = origin;
+        self
+    }
+
+    /// The row pitch in elements, zero for the width of the region.
+    #[must_use]
+    pub const fn row_pitch(mut self, row_pitch: size_t) -> Self {
+        self.layout = self.layout.row_pitch(row_pitch);
+        self
+    }
+
+    /// The slice pitch in elements, zero for the row pitch times the height
+    /// of the region.
+    #[must_use]
+    pub const fn slice_pitch(mut self, slice_pitch: size_t) -> Self {
+        self.layout = self.layout.slice_pitch(slice_pitch);
+        self
+    }
+}
+
+/// A pitched view of host memory to read into.
+#[derive(Debug)]
+pub struct PitchedViewMut<'a, T> {
+    data: &'a mut [T],
+    layout: RectLayout,
+}
+
+impl<'a, T> PitchedViewMut<'a, T> {
+    /// A tightly packed view of `data` from its start.
+    #[must_use]
+    pub const fn new(data: &'a mut [T]) -> Self {
+        Self {
+            data,
+            layout: RectLayout::new(Origin3::ZERO),
+        }
+    }
+
+    /// The origin of the region in the view.
+    #[must_use]
+    pub const fn origin(mut self, origin: Origin3) -> Self {
+        self.layout.origin = origin;
+        self
+    }
+
+    /// The row pitch in elements, zero for the width of the region.
+    #[must_use]
+    pub const fn row_pitch(mut self, row_pitch: size_t) -> Self {
+        self.layout = self.layout.row_pitch(row_pitch);
+        self
+    }
+
+    /// The slice pitch in elements, zero for the row pitch times the height
+    /// of the region.
+    #[must_use]
+    pub const fn slice_pitch(mut self, slice_pitch: size_t) -> Self {
+        self.layout = self.layout.slice_pitch(slice_pitch);
+        self
+    }
+}
+
+/// Enqueue a command to read a rectangular region of a buffer object into
+/// a pitched view of host memory.
+///
+/// Calls `clEnqueueReadBufferRect` after checking the region against the
+/// size of the buffer and the view.
+///
+/// * `buffer_layout` - the origin and pitches of the region in the buffer.
+/// * `region` - the region to read, with the width in elements of `T`.
+/// * `host` - the host memory to read into.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or a `RectError`.
+///
+/// # Safety
+///
+/// If the read is non-blocking, the host memory must not be accessed or
+/// dropped until the command has completed. `T` must be [`Pod`], since the
+/// buffer may hold any bit pattern.
+pub unsafe fn read_buffer_rect<T: Pod>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_read: cl_bool,
+    buffer_layout: RectLayout,
+    region: Region3,
+    host: &mut PitchedViewMut<T>,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, RectError> {
+    let unit = mem::size_of::<T>();
+    let row_size = checked_row_size(region, unit)?;
+    let buffer_rect = buffer_layout.resolve(unit, row_size, region, buffer_size(buffer)?, true)?;
+    let host_rect =
+        host.layout
+            .resolve(unit, row_size, region, mem::size_of_val(host.data), true)?;
+    let event = unsafe {
+        enqueue_read_buffer_rect(
+            command_queue,
+            buffer,
+            blocking_read,
+            &buffer_layout.byte_origin(unit)?,
+            &host.layout.byte_origin(unit)?,
+            &[row_size, region.height, region.depth],
+            buffer_rect.row_pitch,
+            buffer_rect.slice_pitch,
+            host_rect.row_pitch,
+            host_rect.slice_pitch,
+            host.data.as_mut_ptr().cast::<c_void>(),
+            event_wait_list,
+            return_event,
+        )?
+    };
+    Ok(event)
+}
+
+/// Enqueue a command to write a rectangular region of a buffer object from
+/// a pitched view of host memory.
+///
+/// Calls `clEnqueueWriteBufferRect` after checking the region against the
+/// size of the buffer and the view.
+///
+/// * `buffer_layout` - the origin and pitches of the region in the buffer.
+/// * `region` - the region to write, with the width in elements of `T`.
+/// * `host` - the host memory to write from.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or a `RectError`.
+///
+/// # Safety
+///
+/// If the write is non-blocking, the host memory must not be modified or
+/// dropped until the command has completed.
+pub unsafe fn write_buffer_rect<T>(
+    command_queue: cl_command_queue,
+    buffer: cl_mem,
+    blocking_write: cl_bool,
+    buffer_layout: RectLayout,
+    region: Region3,
+    host: &PitchedView<T>,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, RectError> {
+    let unit = mem::size_of::<T>();
+    let row_size = checked_row_size(region, unit)?;
+    let buffer_rect = buffer_layout.resolve(unit, row_size, region, buffer_size(buffer)?, true)?;
+    let host_rect =
+        host.layout
+            .resolve(unit, row_size, region, mem::size_of_val(host.data), true)?;
+    let event = unsafe {
+        enqueue_write_buffer_rect(
+            command_queue,
+            buffer,
+            blocking_write,
+            &buffer_layout.byte_origin(unit)?,
+            &host.layout.byte_origin(unit)?,
+            &[row_size, region.height, region.depth],
+            buffer_rect.row_pitch,
+            buffer_rect.slice_pitch,
+            host_rect.row_pitch,
+            host_rect.slice_pitch,
+            host.data.as_ptr().cast::<c_void>(),
+            event_wait_list,
+            return_event,
+        )?
+    };
+    Ok(event)
+}
+
+/// Enqueue a command to copy a rectangular region between buffer objects.
+/// Calls `clEnqueueCopyBufferRect` after checking the region against the
+/// sizes of the buffers.
+///
+/// * `src_layout`, `dst_layout` - the origins and pitches of the region in
+///   the buffers, in elements of `T`.
+/// * `region` - the region to copy, with the width in elements of `T`.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or a `RectError`.
+///
+/// # Safety
+///
+/// `command_queue`, `src_buffer` and `dst_buffer` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+pub unsafe fn copy_buffer_rect<T>(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_buffer: cl_mem,
+    src_layout: RectLayout,
+    dst_layout: RectLayout,
+    region: Region3,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, RectError> {
+    let unit = mem::size_of::<T>();
+    let row_size = checked_row_size(region, unit)?;
+    let src_rect = src_layout.resolve(unit, row_size, region, buffer_size(src_buffer)?, true)?;
+    let dst_rect = dst_layout.resolve(unit, row_size, region, buffer_size(dst_buffer)?, true)?;
+    Ok(unsafe {
+        enqueue_copy_buffer_rect(
+            command_queue,
+            src_buffer,
+            dst_buffer,
+            &src_layout.byte_origin(unit)?,
+            &dst_layout.byte_origin(unit)?,
+            &[row_size, region.height, region.depth],
+            src_rect.row_pitch,
+            src_rect.slice_pitch,
+            dst_rect.row_pitch,
+            dst_rect.slice_pitch,
+            event_wait_list,
+            return_event,
+        )?
+    })
+}
+
+/// Enqueue a command to read a region of an image object into a pitched
+/// view of host memory.
+///
+/// Calls `clEnqueueReadImage` after checking the region against the
+/// dimensions of the image and the size of the view.
+///
+/// * `origin`, `region` - the region to read in pixels; the rows of a 1D
+///   image array are its images and the slices of a 2D image array are its
+///   images.
+/// * `host` - the host memory to read into.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or a `RectError`.
+///
+/// # Safety
+///
+/// If the read is non-blocking, the host memory must not be accessed or
+/// dropped until the command has completed. `T` must be [`Pod`], since the
+/// image may hold any bit pattern.
+pub unsafe fn read_image<T: Pod>(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_read: cl_bool,
+    origin: Origin3,
+    region: Region3,
+    host: &mut PitchedViewMut<T>,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, RectError> {
+    let (image_type, element_size) = check_image_region(image, origin, region)?;
+    let row_size = checked_row_size(region, element_size)?;
+    let host_rect = host.layout.resolve(
+        mem::size_of::<T>(),
+        row_size,
+        region,
+        mem::size_of_val(host.data),
+        false,
+    )?;
+    let (row_pitch, slice_pitch) = image_pitches(image_type, host_rect);
+    let event = unsafe {
+        enqueue_read_image(
+            command_queue,
+            image,
+            blocking_read,
+            &origin.as_array(),
+            &region.as_array(),
+            row_pitch,
+            slice_pitch,
+            host.data
+                .as_mut_ptr()
+                .cast::<u8>()
+                .add(host_rect.offset)
+                .cast::<c_void>(),
+            event_wait_list,
+            return_event,
+        )?
+    };
+    Ok(event)
+}
+
+/// Enqueue a command to write a region of an image object from a pitched
+/// view of host memory.
+///
+/// Calls `clEnqueueWriteImage` after checking the region against the
+/// dimensions of the image and the size of the view.
+///
+/// * `origin`, `region` - the region to write in pixels, see [`read_image`].
+/// * `host` - the host memory to write from.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or a `RectError`.
+///
+/// # Safety
+///
+/// If the write is non-blocking, the host memory must not be modified or
+/// dropped until the command has completed.
+pub unsafe fn write_image<T>(
+    command_queue: cl_command_queue,
+    image: cl_mem,
+    blocking_write: cl_bool,
+    origin: Origin3,
+    region: Region3,
+    host: &PitchedView<T>,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, RectError> {
+    let (image_type, element_size) = check_image_region(image, origin, region)?;
+    let row_size = checked_row_size(region, element_size)?;
+    let host_rect = host.layout.resolve(
+        mem::size_of::<T>(),
+        row_size,
+        region,
+        mem::size_of_val(host.data),
+        false,
+    )?;
+    let (row_pitch, slice_pitch) = image_pitches(image_type, host_rect);
+    let event = unsafe {
+        enqueue_write_image(
+            command_queue,
+            image,
+            blocking_write,
+            &origin.as_array(),
+            &region.as_array(),
+            row_pitch,
+            slice_pitch,
+            host.data
+                .as_ptr()
+                .cast::<u8>()
+                .add(host_rect.offset)
+                .cast::<c_void>(),
+            event_wait_list,
+            return_event,
+        )?
+    };
+    Ok(event)
+}
+
+/// Enqueue a command to copy a region between image objects.
+/// Calls `clEnqueueCopyImage` after checking the region against the
+/// dimensions of both images.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or a `RectError`.
+///
+/// # Safety
+///
+/// `command_queue`, `src_image` and `dst_image` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+pub unsafe fn copy_image(
+    command_queue: cl_command_queue,
+    src_image: cl_mem,
+    dst_image: cl_mem,
+    src_origin: Origin3,
+    dst_origin: Origin3,
+    region: Region3,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, RectError> {
+    check_image_region(src_image, src_origin, region)?;
+    check_image_region(dst_image, dst_origin, region)?;
+    Ok(unsafe {
+        enqueue_copy_image(
+            command_queue,
+            src_image,
+            dst_image,
+            &src_origin.as_array(),
+            &dst_origin.as_array(),
+            &region.as_array(),
+            event_wait_list,
+            return_event,
+        )?
+    })
+}
+
+/// Enqueue a command to copy a region of an image object to a buffer object.
+/// Calls `clEnqueueCopyImageToBuffer` after checking the region against the
+/// dimensions of the image and the size of the buffer.
+///
+/// * `dst_offset` - the offset in bytes in the buffer to copy to, the region
+///   is tightly packed in the buffer.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or a `RectError`.
+///
+/// # Safety
+///
+/// `command_queue`, `src_image` and `dst_buffer` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+pub unsafe fn copy_image_to_buffer(
+    command_queue: cl_command_queue,
+    src_image: cl_mem,
+    dst_buffer: cl_mem,
+    src_origin: Origin3,
+    region: Region3,
+    dst_offset: size_t,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, RectError> {
+    let (_, element_size) = check_image_region(src_image, src_origin, region)?;
+    check_packed_range(dst_offset, region, element_size, buffer_size(dst_buffer)?)?;
+    Ok(unsafe {
+        enqueue_copy_image_to_buffer(
+            command_queue,
+            src_image,
+            dst_buffer,
+            &src_origin.as_array(),
+            &region.as_array(),
+            dst_offset,
+            event_wait_list,
+            return_event,
+        )?
+    })
+}
+
+/// Enqueue a command to copy from a buffer object to a region of an image
+/// object.
+///
+/// Calls `clEnqueueCopyBufferToImage` after checking the region against the
+/// size of the buffer and the dimensions of the image.
+///
+/// * `src_offset` - the offset in bytes in the buffer to copy from, the
+///   region is tightly packed in the buffer.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or a `RectError`.
+///
+/// # Safety
+///
+/// `command_queue`, `src_buffer` and `dst_image` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+pub unsafe fn copy_buffer_to_image(
+    command_queue: cl_command_queue,
+    src_buffer: cl_mem,
+    dst_image: cl_mem,
+    src_offset: size_t,
+    dst_origin: Origin3,
+    region: Region3,
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, RectError> {
+    let (_, element_size) = check_image_region(dst_image, dst_origin, region)?;
+    check_packed_range(src_offset, region, element_size, buffer_size(src_buffer)?)?;
+    Ok(unsafe {
+        enqueue_copy_buffer_to_image(
+            command_queue,
+            src_buffer,
+            dst_image,
+            src_offset,
+            &dst_origin.as_array(),
+            &region.as_array(),
+            event_wait_list,
+            return_event,
+        )?
+    })
+}
+
+/// The size in bytes of a buffer object.
+fn buffer_size(buffer: cl_mem) -> Result<size_t, cl_int> {
+    Ok(get_mem_object_info(buffer, CL_MEM_SIZE)?.to_size())
+}
+
+/// The size in bytes of a row of a region.
+fn checked_row_size(region: Region3, element_size: size_t) -> Result<size_t, RectError> {
+    region
+        .width
+        .checked_mul(element_size)
+        .ok_or(RectError::Overflow)
+}
+
+/// A pitch in bytes: the pitch in elements of `unit` bytes if set,
+/// otherwise `min`.
+///
+/// returns a Result containing the pitch, or `RectError::InvalidPitch` if a
+/// set pitch is less than `min` or not a multiple of a non zero `multiple`.
+fn checked_pitch(
+    pitch: size_t,
+    unit: size_t,
+    min: size_t,
+    multiple: size_t,
+) -> Result<size_t, RectError> {
+    if pitch == 0 {
+        return Ok(min);
+    }
+    let pitch = pitch.checked_mul(unit).ok_or(RectError::Overflow)?;
+    if pitch < min || (multiple != 0 && !pitch.is_multiple_of(multiple)) {
+        Err(RectError::InvalidPitch {
+            pitch,
+            min,
+            multiple,
+        })
+    } else {
+        Ok(pitch)
+    }
+}
+
+/// The byte offset of `x` bytes, `y` rows and `z` slices.
+fn byte_offset(
+    x: size_t,
+    y: size_t,
+    z: size_t,
+    row_pitch: size_t,
+    slice_pitch: size_t,
+) -> Result<size_t, RectError> {
+    y.checked_mul(row_pitch)
+        .and_then(|rows| z.checked_mul(slice_pitch)?.checked_add(rows))
+        .and_then(|offset| offset.checked_add(x))
+        .ok_or(RectError::Overflow)
+}
+
+/// Check that a region fits within the `extent` of an image.
+fn check_extent(origin: Origin3, region: Region3, extent: [size_t; 3]) -> Result<(), RectError> {
+    if region.is_empty() {
+        return Err(RectError::ZeroRegion);
+    }
+    for ((origin, region), size) in origin
+        .as_array()
+        .into_iter()
+        .zip(region.as_array())
+        .zip(extent)
+    {
+        let end = origin.checked_add(region).ok_or(RectError::Overflow)?;
+        if end > size {
+            return Err(RectError::OutOfBounds { end, size });
+        }
+    }
+    Ok(())
+}
+
+/// Check that a tightly packed region from `offset` fits within `size` bytes.
+fn check_packed_range(
+    offset: size_t,
+    region: Region3,
+    element_size: size_t,
+    size: size_t,
+) -> Result<(), RectError> {
+    let end = region
+        .width
+        .checked_mul(region.height)
+        .and_then(|n| n.checked_mul(region.depth))
+        .and_then(|n| n.checked_mul(element_size))
+        .and_then(|n| n.checked_add(offset))
+        .ok_or(RectError::Overflow)?;
+    if end > size {
+        Err(RectError::OutOfBounds { end, size })
+    } else {
+        Ok(())
+    }
+}
+
+/// The pixels, rows and slices of an image: the images of a 1D image array
+/// are its rows and the images of a 2D image array are its slices.
+fn image_extent(image: cl_mem, image_type: cl_mem_object_type) -> Result<[size_t; 3], cl_int> {
+    let size = |param_name| get_image_info(image, param_name).map(InfoType::to_size);
+    let width = size(CL_IMAGE_WIDTH)?;
+    Ok(match image_type {
+        CL_MEM_OBJECT_IMAGE1D_ARRAY => [width, size(CL_IMAGE_ARRAY_SIZE)?, 1],
+        CL_MEM_OBJECT_IMAGE2D => [width, size(CL_IMAGE_HEIGHT)?, 1],
+        CL_MEM_OBJECT_IMAGE2D_ARRAY => [width, size(CL_IMAGE_HEIGHT)?, size(CL_IMAGE_ARRAY_SIZE)?],
+        CL_MEM_OBJECT_IMAGE3D => [width, size(CL_IMAGE_HEIGHT)?, size(CL_IMAGE_DEPTH)?],
+        _ => [width, 1, 1],
+    })
+}
+
+/// Check a region against the dimensions of an image.
+///
+/// returns a Result containing the image type and element size or a
+/// `RectError`.
+fn check_image_region(
+    image: cl_mem,
+    origin: Origin3,
+    region: Region3,
+) -> Result<(cl_mem_object_type, size_t), RectError> {
+    let image_type = get_mem_object_info(image, CL_MEM_TYPE)?.to_uint();
+    check_extent(origin, region, image_extent(image, image_type)?)?;
+    let element_size = get_image_info(image, CL_IMAGE_ELEMENT_SIZE)?.to_size();
+    Ok((image_type, element_size))
+}
+
+/// The host row and slice pitches of an image transfer: the images of a 1D
+/// image array are separated by the slice pitch and the slice pitch of a 1D
+/// or 2D image must be zero.
+const fn image_pitches(image_type: cl_mem_object_type, rect: ByteRect) -> (size_t, size_t) {
+    match image_type {
+        CL_MEM_OBJECT_IMAGE1D_ARRAY => (rect.row_pitch, rect.row_pitch),
+        CL_MEM_OBJECT_IMAGE2D_ARRAY | CL_MEM_OBJECT_IMAGE3D => (rect.row_pitch, rect.slice_pitch),
+        _ => (rect.row_pitch, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::{create_command_queue, release_command_queue};
+    use crate::context::{create_context, release_context};
+    use crate::device::{CL_DEVICE_TYPE_GPU, get_device_ids};
+    use crate::memory::{CL_MEM_READ_WRITE, create_buffer, release_mem_object};
+    use crate::platform::get_platform_ids;
+    use crate::types::{CL_BLOCKING, cl_uint};
+    use opencl_sys::{CL_MEM_OBJECT_IMAGE1D, CL_MEM_OBJECT_IMAGE1D_BUFFER};
+    use std::ptr;
+
+    #[test]
+    fn test_rect_layout() {
+        let region = Region3::d2(4, 3);
+        let unit = mem::size_of::<f32>();
+
+        // A tightly packed 4x3 region of f32 is 48 bytes.
+        let rect = RectLayout::default()
+            .resolve(unit, 16, region, 48, true)
+            .unwrap();
+        assert_eq!(
+            ByteRect {
+                offset: 0,
+                row_pitch: 16,
+                slice_pitch: 48
+            },
+            rect
+        );
+        assert_eq!(
+            Err(RectError::OutOfBounds { end: 48, size: 47 }),
+            RectLayout::default().resolve(unit, 16, region, 47, true)
+        );
+
+        // A tile at (2, 1) of an 8 wide image: the last row ends at
+        // 3 * 32 + 8 + 16 bytes.
+        let layout = RectLayout::new(Origin3::new(2, 1, 0)).row_pitch(8);
+        let rect = layout.resolve(unit, 16, region, 120, true).unwrap();
+        assert_eq!(40, rect.offset);
+        assert_eq!(32, rect.row_pitch);
+        assert_eq!(96, rect.slice_pitch);
+        assert_eq!(
+            Err(RectError::OutOfBounds {
+                end: 120,
+                size: 119
+            }),
+            layout.resolve(unit, 16, region, 119, true)
+        );
+        assert_eq!([8, 1, 0], layout.byte_origin(unit).unwrap());
+
+        assert_eq!(
+            Err(RectError::InvalidPitch {
+                pitch: 12,
+                min: 16,
+                multiple: 0
+            }),
+            RectLayout::default()
+                .row_pitch(3)
+                .resolve(unit, 16, region, 1024, true)
+        );
+        assert_eq!(
+            Err(RectError::ZeroRegion),
+            RectLayout::default().resolve(unit, 16, Region3::d2(4, 0), 1024, true)
+        );
+        assert_eq!(
+            Err(RectError::Overflow),
+            RectLayout::new(Origin3::new(0, size_t::MAX, 0)).resolve(unit, 16, region, 1024, true)
+        );
+
+        assert_eq!(
+            Ok(()),
+            check_extent(Origin3::new(4, 4, 0), region, [8, 8, 1])
+        );
+        assert_eq!(
+            Err(RectError::OutOfBounds { end: 9, size: 8 }),
+            check_extent(Origin3::new(5, 0, 0), region, [8, 8, 1])
+        );
+        assert_eq!(
+            Err(RectError::OutOfBounds { end: 2, size: 1 }),
+            check_extent(Origin3::new(0, 0, 1), region, [8, 8, 1])
+        );
+        assert_eq!(
+            Err(RectError::OutOfBounds { end: 52, size: 51 }),
+            check_packed_range(4, region, 4, 51)
+        );
+    }
+
+    #[test]
+    fn test_rect_slice_pitch() {
+        let region = Region3::new(4, 3, 2);
+        let unit = mem::size_of::<f32>();
+
+        // A buffer slice pitch must be a multiple of the row pitch.
+        let layout = RectLayout::default().row_pitch(8).slice_pitch(26);
+        assert_eq!(
+            Err(RectError::InvalidPitch {
+                pitch: 104,
+                min: 96,
+                multiple: 32
+            }),
+            layout.resolve(unit, 16, region, 1024, true)
+        );
+        let rect = layout.resolve(unit, 16, region, 1024, false).unwrap();
+        assert_eq!(104, rect.slice_pitch);
+
+        let rect = layout
+            .slice_pitch(32)
+            .resolve(unit, 16, region, 1024, true)
+            .unwrap();
+        assert_eq!(128, rect.slice_pitch);
+        assert_eq!(
+            Err(RectError::InvalidPitch {
+                pitch: 64,
+                min: 96,
+                multiple: 32
+            }),
+            layout.slice_pitch(16).resolve(unit, 16, region, 1024, true)
+        );
+        assert_eq!(CL_INVALID_VALUE, RectError::Overflow.error_code());
+    }
+
+    #[test]
+    fn test_image_pitches() {
+        let rect = ByteRect {
+            offset: 0,
+            row_pitch: 16,
+            slice_pitch: 48,
+        };
+        for image_type in [
+            CL_MEM_OBJECT_IMAGE1D,
+            CL_MEM_OBJECT_IMAGE1D_BUFFER,
+            CL_MEM_OBJECT_IMAGE2D,
+        ] {
+            assert_eq!((16, 0), image_pitches(image_type, rect));
+        }
+        assert_eq!((16, 16), image_pitches(CL_MEM_OBJECT_IMAGE1D_ARRAY, rect));
+        assert_eq!((16, 48), image_pitches(CL_MEM_OBJECT_IMAGE2D_ARRAY, rect));
+        assert_eq!((16, 48), image_pitches(CL_MEM_OBJECT_IMAGE3D, rect));
+    }
+
+    #[test]
+    fn test_rect_transfers() {
+        let platform_ids = get_platform_ids().unwrap();
+
+        // Choose the first platform
+        let platform_id = platform_ids[0];
+
+        let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(!device_ids.is_empty());
+
+        let device_id = device_ids[0];
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = unsafe { create_command_queue(context, device_id, 0).unwrap() };
+
+        // An 8x4 buffer of cl_uint.
+        let size = 32 * mem::size_of::<cl_uint>();
+        let buffer =
+            unsafe { create_buffer(context, CL_MEM_READ_WRITE, size, ptr::null_mut()).unwrap() };
+        let buffer_layout = RectLayout::new(Origin3::new(2, 1, 0)).row_pitch(8);
+        let region = Region3::d2(3, 2);
+
+        // Write a 3x2 tile from a 4 wide host view and read it back packed.
+        let data: Vec<cl_uint> = (0..8).collect();
+        let host = PitchedView::new(&data).row_pitch(4);
+        let mut tile = [0; 6];
+        unsafe {
+            let _ = write_buffer_rect(
+                queue,
+                buffer,
+                CL_BLOCKING,
+                buffer_layout,
+                region,
+                &host,
+                &[],
+                false,
+            )
+            .unwrap();
+            let _ = read_buffer_rect(
+                queue,
+                buffer,
+                CL_BLOCKING,
+                buffer_layout,
+                region,
+                &mut PitchedViewMut::new(&mut tile),
+                &[],
+                false,
+            )
+            .unwrap();
+        }
+        assert_eq!([0, 1, 2, 4, 5, 6], tile);
+
+        // The tile must fit in the buffer and the host view.
+        let result = unsafe {
+            read_buffer_rect(
+                queue,
+                buffer,
+                CL_BLOCKING,
+                RectLayout::new(Origin3::new(6, 3, 0)).row_pitch(8),
+                region,
+                &mut PitchedViewMut::new(&mut tile),
+                &[],
+                false,
+            )
+        };
+        assert_eq!(
+            Err(RectError::OutOfBounds {
+                end: 164,
+                size: 128
+            }),
+            result
+        );
+        let result = unsafe {
+            read_buffer_rect(
+                queue,
+                buffer,
+                CL_BLOCKING,
+                buffer_layout,
+                region,
+                &mut PitchedViewMut::new(&mut tile[..5]),
+                &[],
+                false,
+            )
+        };
+        assert_eq!(Err(RectError::OutOfBounds { end: 24, size: 20 }), result);
+
+        unsafe {
+            release_mem_object(buffer).unwrap();
+            release_command_queue(queue).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+
+    #[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+    #[test]
+    fn test_rect_image_transfers() {
+        use crate::device::{CL_DEVICE_IMAGE_SUPPORT, get_device_info};
+        use crate::image::{CL_RGBA, CL_UNSIGNED_INT8, ImageDesc, image_format};
+
+        let platform_ids = get_platform_ids().unwrap();
+
+        // Choose the first platform
+        let platform_id = platform_ids[0];
+
+        let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(!device_ids.is_empty());
+
+        let device_id = device_ids[0];
+        if get_device_info(device_id, CL_DEVICE_IMAGE_SUPPORT)
+            .unwrap()
+            .to_uint()
+            == 0
+        {
+            println!("images are not supported");
+            return;
+        }
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = unsafe { create_command_queue(context, device_id, 0).unwrap() };
+
+        let format = image_format(CL_RGBA, CL_UNSIGNED_INT8);
+        let image = unsafe {
+            ImageDesc::image2d(4, 4)
+                .create(context, CL_MEM_READ_WRITE, &format, ptr::null_mut())
+                .unwrap()
+        };
+
+        // Write a 2x2 tile from a 4 pixel wide host view and read it back.
+        let pixels: Vec<[u8; 4]> = (0..8).map(|i| [i; 4]).collect();
+        let mut tile = [[0; 4]; 4];
+        unsafe {
+            let _ = write_image(
+                queue,
+                image,
+                CL_BLOCKING,
+                Origin3::new(1, 1, 0),
+                Region3::d2(2, 2),
+                &PitchedView::new(&pixels).row_pitch(4),
+                &[],
+                false,
+            )
+            .unwrap();
+            let _ = read_image(
+                queue,
+                image,
+                CL_BLOCKING,
+                Origin3::new(1, 1, 0),
+                Region3::d2(2, 2),
+                &mut PitchedViewMut::new(&mut tile),
+                &[],
+                false,
+            )
+            .unwrap();
+        }
+        assert_eq!([[0; 4], [1; 4], [4; 4], [5; 4]], tile);
+
+        let result = unsafe {
+            read_image(
+                queue,
+                image,
+                CL_BLOCKING,
+                Origin3::new(3, 0, 0),
+                Region3::d2(2, 2),
+                &mut PitchedViewMut::new(&mut tile),
+                &[],
+                false,
+            )
+        };
+        assert_eq!(Err(RectError::OutOfBounds { end: 5, size: 4 }), result);
+
+        unsafe {
+            release_mem_object(image).unwrap();
+            release_command_queue(queue).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}
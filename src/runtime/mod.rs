@@ -1035,7 +1035,7 @@ pub struct OpenCl {
 
     clUnloadCompiler: Option<fn() -> cl_int>,
 
-    clGetExtensionFunctionAddress: Option<fn(func_name: *const c_char)>,
+    clGetExtensionFunctionAddress: Option<fn(func_name: *const c_char) -> *mut c_void>,
 
     // Deprecated OpenCL 2.0 APIs
     clCreateCommandQueue: Option<
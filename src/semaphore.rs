@@ -0,0 +1,346 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `cl_khr_semaphore` semaphores with Linux sync file import and export.
+//!
+//! A [`Semaphore`] owns a `cl_semaphore_khr` created from typed
+//! [`SemaphoreProperties`], see the [`properties`](super::properties) module.
+//! Semaphores are waited for and signalled with
+//! slices of semaphores and events.
+//!
+//! On unix, with `cl_khr_external_semaphore_sync_fd`, a binary semaphore can
+//! be imported from and exported to a Linux sync file as an [`OwnedFd`], e.g.
+//! to synchronise with a DRM or V4L2 pipeline. Importing a sync file
+//! transfers the ownership of its file descriptor to `OpenCL`.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub use opencl_sys::{
+    CL_SEMAPHORE_DEVICE_HANDLE_LIST_END_KHR, CL_SEMAPHORE_DEVICE_HANDLE_LIST_KHR,
+    CL_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR, CL_SEMAPHORE_EXPORT_HANDLE_TYPES_LIST_END_KHR,
+    CL_SEMAPHORE_HANDLE_OPAQUE_FD_KHR, CL_SEMAPHORE_HANDLE_SYNC_FD_KHR, CL_SEMAPHORE_PAYLOAD_KHR,
+    CL_SEMAPHORE_TYPE_BINARY_KHR, CL_SEMAPHORE_TYPE_KHR, cl_command_queue, cl_context,
+    cl_device_id, cl_event, cl_external_semaphore_handle_type_khr, cl_int, cl_semaphore_khr,
+    cl_semaphore_payload_khr, cl_semaphore_properties_khr, cl_semaphore_type_khr, cl_uint,
+};
+
+pub use super::properties::{Properties, SemaphoreProperty};
+
+use super::enqueue::{enqueue_signal_semaphores_khr, enqueue_wait_semaphores_khr};
+#[cfg(all(
+    unix,
+    any(
+        all(
+            feature = "cl_khr_external_semaphore",
+            feature = "cl_khr_external_semaphore_sync_fd"
+        ),
+        feature = "dynamic"
+    )
+))]
+use super::ext::get_semaphore_sync_fd_khr;
+use super::ext::{
+    create_semaphore_with_properties_khr, get_semaphore_info_khr, release_semaphore_khr,
+};
+#[cfg(all(
+    unix,
+    any(
+        all(
+            feature = "cl_khr_external_semaphore",
+            feature = "cl_khr_external_semaphore_sync_fd"
+        ),
+        feature = "dynamic"
+    )
+))]
+use std::os::fd::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd};
+
+/// The properties to create a [`Semaphore`] with, e.g.
+/// `SemaphoreProperties::new().with(SemaphoreProperty::Type(CL_SEMAPHORE_TYPE_BINARY_KHR))`.
+pub type SemaphoreProperties = Properties<SemaphoreProperty>;
+
+/// An owned `cl_khr_semaphore` semaphore, see the module documentation.
+/// The semaphore is released when it is dropped.
+#[derive(Debug)]
+pub struct Semaphore {
+    semaphore: cl_semaphore_khr,
+}
+
+impl Semaphore {
+    /// Create a semaphore.
+    /// Calls `clCreateSemaphoreWithPropertiesKHR`.
+    ///
+    /// returns a Result containing the new `Semaphore`
+    /// or the error code from the `OpenCL` C API function.
+    pub fn new(context: cl_context, properties: &SemaphoreProperties) -> Result<Self, cl_int> {
+        let properties = properties.to_vec();
+        let semaphore = create_semaphore_with_properties_khr(context, properties.as_ptr())?;
+        Ok(Self { semaphore })
+    }
+
+    /// Create a semaphore that imports a Linux sync file.
+    /// Calls `clCreateSemaphoreWithPropertiesKHR` with `CL_SEMAPHORE_HANDLE_SYNC_FD_KHR`.
+    /// `cl_khr_external_semaphore_sync_fd`
+    ///
+    /// * `fd` - the sync file, it is owned by `OpenCL` if the import succeeds
+    ///   and closed if it fails.
+    ///
+    /// returns a Result containing the new `Semaphore`
+    /// or the error code from the `OpenCL` C API function.
+    #[cfg(all(
+        unix,
+        any(
+            all(
+                feature = "cl_khr_external_semaphore",
+                feature = "cl_khr_external_semaphore_sync_fd"
+            ),
+            feature = "dynamic"
+        )
+    ))]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn import_sync_fd(
+        context: cl_context,
+        properties: &SemaphoreProperties,
+        fd: OwnedFd,
+    ) -> Result<Self, cl_int> {
+        let properties = properties
+            .clone()
+            .with(SemaphoreProperty::Other(
+                CL_SEMAPHORE_HANDLE_SYNC_FD_KHR.into(),
+                fd.as_raw_fd() as cl_semaphore_properties_khr,
+            ))
+            .to_vec();
+        let semaphore = create_semaphore_with_properties_khr(context, properties.as_ptr())?;
+        // OpenCL owns the file descriptor now.
+        let _ = fd.into_raw_fd();
+        Ok(Self { semaphore })
+    }
+
+    /// The underlying `OpenCL` semaphore.
+    #[must_use]
+    pub const fn get(&self) -> cl_semaphore_khr {
+        self.semaphore
+    }
+
+    /// The `CL_SEMAPHORE_TYPE_KHR` of the semaphore.
+    pub fn semaphore_type(&self) -> Result<cl_semaphore_type_khr, cl_int> {
+        let data = get_semaphore_info_khr(self.semaphore, CL_SEMAPHORE_TYPE_KHR)?;
+        data.get(..size_of::<cl_semaphore_type_khr>())
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(cl_semaphore_type_khr::from_ne_bytes)
+            .ok_or(opencl_sys::CL_INVALID_VALUE)
+    }
+
+    /// The `CL_SEMAPHORE_PAYLOAD_KHR` of the semaphore,
+    /// e.g. 0 or 1 for a binary semaphore.
+    pub fn payload(&self) -> Result<cl_semaphore_payload_khr, cl_int> {
+        let data = get_semaphore_info_khr(self.semaphore, CL_SEMAPHORE_PAYLOAD_KHR)?;
+        data.get(..size_of::<cl_semaphore_payload_khr>())
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(cl_semaphore_payload_khr::from_ne_bytes)
+            .ok_or(opencl_sys::CL_INVALID_VALUE)
+    }
+
+    /// Enqueue a wait for the semaphore, see [`wait_semaphores`].
+    ///
+    /// # Safety
+    ///
+    /// See [`wait_semaphores`].
+    pub unsafe fn wait(
+        &self,
+        command_queue: cl_command_queue,
+        event_wait_list: &[cl_event],
+        return_event: bool,
+    ) -> Result<Option<cl_event>, cl_int> {
+        unsafe { wait_semaphores(command_queue, &[self], event_wait_list, return_event) }
+    }
+
+    /// Enqueue a signal of the semaphore, see [`signal_semaphores`].
+    ///
+    /// # Safety
+    ///
+    /// See [`signal_semaphores`].
+    pub unsafe fn signal(
+        &self,
+        command_queue: cl_command_queue,
+        event_wait_list: &[cl_event],
+        return_event: bool,
+    ) -> Result<Option<cl_event>, cl_int> {
+        unsafe { signal_semaphores(command_queue, &[self], event_wait_list, return_event) }
+    }
+
+    /// Replace the payload of the semaphore with a Linux sync file.
+    /// Calls `clReImportSemaphoreSyncFdKHR`.
+    /// `cl_khr_external_semaphore_sync_fd`
+    ///
+    /// * `fd` - the sync file, it is owned by `OpenCL` if the import succeeds
+    ///   and closed if it fails.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    #[cfg(all(
+        unix,
+        any(
+            all(
+                feature = "cl_khr_external_semaphore",
+                feature = "cl_khr_external_semaphore_sync_fd"
+            ),
+            feature = "dynamic"
+        )
+    ))]
+    pub fn reimport_sync_fd(&self, fd: OwnedFd) -> Result<(), cl_int> {
+        unsafe {
+            super::ext::reimport_semaphore_sync_fd(
+                self.semaphore,
+                std::ptr::null_mut(),
+                fd.as_raw_fd(),
+            )
+        }?;
+        // OpenCL owns the file descriptor now.
+        let _ = fd.into_raw_fd();
+        Ok(())
+    }
+
+    /// Export the semaphore as a Linux sync file.
+    /// Calls `clGetSemaphoreHandleForTypeKHR` with `CL_SEMAPHORE_HANDLE_SYNC_FD_KHR`.
+    /// `cl_khr_external_semaphore_sync_fd`
+    ///
+    /// The semaphore must have been created with the
+    /// `CL_SEMAPHORE_HANDLE_SYNC_FD_KHR` export handle type.
+    ///
+    /// * `device` - the device to export the semaphore for.
+    ///
+    /// returns a Result containing the sync file, or None if the semaphore
+    /// is already signalled, or the error code from the `OpenCL` C API function.
+    #[cfg(all(
+        unix,
+        any(
+            all(
+                feature = "cl_khr_external_semaphore",
+                feature = "cl_khr_external_semaphore_sync_fd"
+            ),
+            feature = "dynamic"
+        )
+    ))]
+    pub fn export_sync_fd(&self, device: cl_device_id) -> Result<Option<OwnedFd>, cl_int> {
+        let fd = get_semaphore_sync_fd_khr(self.semaphore, device)?;
+        // A sync file of -1 represents a signalled fence.
+        Ok((0 <= fd).then(|| unsafe { OwnedFd::from_raw_fd(fd) }))
+    }
+}
+
+impl Drop for Semaphore {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = release_semaphore_khr(self.semaphore);
+        }
+    }
+}
+
+/// Enqueue a wait for binary semaphores.
+/// Calls `clEnqueueWaitSemaphoresKHR`.
+///
+/// * `command_queue` - the `OpenCL` command-queue.
+/// * `semaphores` - the semaphores to wait for.
+/// * `event_wait_list` - the events that must complete before the command.
+/// * `return_event` - whether to create an event for the command.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+pub unsafe fn wait_semaphores(
+    command_queue: cl_command_queue,
+    semaphores: &[&Semaphore],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let sema_objects: Vec<cl_semaphore_khr> = semaphores.iter().map(|s| s.get()).collect();
+    unsafe {
+        enqueue_wait_semaphores_khr(
+            command_queue,
+            &sema_objects,
+            &[],
+            event_wait_list,
+            return_event,
+        )
+    }
+}
+
+/// Enqueue a signal of binary semaphores.
+/// Calls `clEnqueueSignalSemaphoresKHR`.
+///
+/// * `command_queue` - the `OpenCL` command-queue.
+/// * `semaphores` - the semaphores to signal.
+/// * `event_wait_list` - the events that must complete before the command.
+/// * `return_event` - whether to create an event for the command.
+///
+/// returns a Result containing the new event if `return_event` is true,
+/// or the error code from the `OpenCL` C API function.
+///
+/// # Safety
+///
+/// `command_queue` and the events in `event_wait_list` must be valid `OpenCL`
+/// objects.
+pub unsafe fn signal_semaphores(
+    command_queue: cl_command_queue,
+    semaphores: &[&Semaphore],
+    event_wait_list: &[cl_event],
+    return_event: bool,
+) -> Result<Option<cl_event>, cl_int> {
+    let sema_objects: Vec<cl_semaphore_khr> = semaphores.iter().map(|s| s.get()).collect();
+    unsafe {
+        enqueue_signal_semaphores_khr(
+            command_queue,
+            &sema_objects,
+            &[],
+            event_wait_list,
+            return_event,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn test_semaphore_properties() {
+        assert_eq!(vec![0], SemaphoreProperties::new().to_vec());
+
+        let device = ptr::without_provenance_mut::<libc::c_void>(0x1234).cast();
+        let properties = SemaphoreProperties::new()
+            .with(SemaphoreProperty::Type(CL_SEMAPHORE_TYPE_BINARY_KHR))
+            .with(SemaphoreProperty::DeviceHandleList(vec![device]))
+            .with(SemaphoreProperty::ExportHandleTypes(vec![
+                CL_SEMAPHORE_HANDLE_SYNC_FD_KHR,
+            ]));
+        assert_eq!(
+            vec![
+                CL_SEMAPHORE_TYPE_KHR.into(),
+                CL_SEMAPHORE_TYPE_BINARY_KHR.into(),
+                CL_SEMAPHORE_DEVICE_HANDLE_LIST_KHR.into(),
+                0x1234,
+                CL_SEMAPHORE_DEVICE_HANDLE_LIST_END_KHR.into(),
+                CL_SEMAPHORE_EXPORT_HANDLE_TYPES_KHR,
+                CL_SEMAPHORE_HANDLE_SYNC_FD_KHR.into(),
+                CL_SEMAPHORE_EXPORT_HANDLE_TYPES_LIST_END_KHR,
+                0
+            ],
+            properties.to_vec()
+        );
+    }
+}
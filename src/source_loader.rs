@@ -0,0 +1,614 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `OpenCL` C source loading with `#include` resolution.
+//!
+//! A [`SourceLoader`] scans `OpenCL` C source for `#include` directives and
+//! resolves them against embedded virtual files and include directories.
+//! The resolved headers can then be passed to `clCompileProgram` as header
+//! programs, so that the compiler does not need access to the file system.
+//!
+//! Includes are resolved in the following order:
+//! * the virtual files added to the loader,
+//! * for `#include "name"`, the directory of the including file,
+//! * the include directories, in the order they were added.
+//!
+//! Note: the source is not preprocessed, so includes within inactive
+//! conditional blocks are still resolved.
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{CL_PROGRAM_BUILD_LOG, cl_context, cl_device_id, cl_int, cl_program};
+
+use super::build_log::LineMap;
+use super::error_codes::{ClError, error_text};
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+use super::program::{
+    compile_program, create_program_with_source, get_program_build_info, link_program,
+    release_program,
+};
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// The reasons that a [`SourceLoader`] may fail to load or build a program.
+#[derive(Debug, Error)]
+pub enum SourceLoaderError {
+    #[error("include file \"{name}\" not found, included from {included_from}")]
+    NotFound { name: String, included_from: String },
+    #[error("include name \"{name}\" refers to both {first} and {second}")]
+    Conflict {
+        name: String,
+        first: String,
+        second: String,
+    },
+    #[error("include name \"{0}\" contains a null character")]
+    InvalidName(String),
+    #[error("failed to read {}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("program compilation failed: {}\n{log}", error_text(*status))]
+    Build { status: cl_int, log: String },
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+/// Implement the From trait
+impl From<cl_int> for SourceLoaderError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<SourceLoaderError> for ClError {
+    fn from(error: SourceLoaderError) -> Self {
+        match error {
+            SourceLoaderError::Build { status, .. } | SourceLoaderError::Cl(status) => Self(status),
+            _ => Self(opencl_sys::CL_INVALID_VALUE),
+        }
+    }
+}
+
+/// Where an included header was found.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Dependency {
+    /// A file, with its canonical path.
+    File(PathBuf),
+    /// A virtual file added to the loader, with its name.
+    Virtual(String),
+}
+
+impl Dependency {
+    fn describe(&self) -> String {
+        match self {
+            Self::File(path) => path.display().to_string(),
+            Self::Virtual(name) => format!("virtual file \"{name}\""),
+        }
+    }
+
+    /// The file name of the dependency for diagnostics: its path or virtual file name.
+    fn file_name(&self) -> String {
+        match self {
+            Self::File(path) => path.display().to_string(),
+            Self::Virtual(name) => name.clone(),
+        }
+    }
+}
+
+/// A header included by a program source.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncludeHeader {
+    /// The name of the header as written in the `#include` directive.
+    pub name: String,
+    /// Where the header was found.
+    pub dependency: Dependency,
+    /// The contents of the header.
+    pub source: String,
+}
+
+/// A program source together with all of the headers that it includes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResolvedSource {
+    /// The program source.
+    pub source: String,
+    /// The included headers, in the order that they were first included.
+    pub headers: Vec<IncludeHeader>,
+}
+
+impl ResolvedSource {
+    /// The dependencies of the source, e.g. to invalidate a program cache
+    /// when a header changes.
+    #[must_use]
+    pub fn dependencies(&self) -> Vec<&Dependency> {
+        self.headers
+            .iter()
+            .map(|header| &header.dependency)
+            .collect()
+    }
+
+    /// The header include names as null terminated strings, for `compile_program`.
+    ///
+    /// returns a Result containing the include names or a `SourceLoaderError`
+    /// if a name contains a null character.
+    pub fn include_names(&self) -> Result<Vec<CString>, SourceLoaderError> {
+        self.headers
+            .iter()
+            .map(|header| {
+                CString::new(header.name.as_str())
+                    .map_err(|_| SourceLoaderError::InvalidName(header.name.clone()))
+            })
+            .collect()
+    }
+
+    /// Expand the includes of the source into a single source, e.g. to build
+    /// the program with `build_program` on `OpenCL` 1.1 devices.
+    ///
+    /// Each header is expanded where it is first included and later includes
+    /// of it are removed, as if the headers had include guards.
+    ///
+    /// * `name` - the file name of the program source for diagnostics.
+    ///
+    /// returns the expanded source and a `LineMap` to map build log
+    /// diagnostics back to the original files.
+    #[must_use]
+    pub fn expand(&self, name: &str) -> (String, LineMap) {
+        let mut expansion = Expansion::default();
+        expansion.expand(self, &self.source, name);
+        (expansion.source, expansion.line_map)
+    }
+}
+
+/// The state of a [`ResolvedSource::expand`].
+#[derive(Default)]
+struct Expansion<'a> {
+    source: String,
+    line_map: LineMap,
+    lines: u32,
+    expanded: BTreeSet<&'a str>,
+}
+
+impl<'a> Expansion<'a> {
+    fn expand(&mut self, resolved: &'a ResolvedSource, text: &str, file: &str) {
+        let mut includes = scan_includes(text).into_iter().peekable();
+        self.line_map.push(self.lines + 1, file, 1);
+        for (index, line) in text.lines().enumerate() {
+            let include = includes
+                .next_if(|(include_index, _, _)| *include_index == index)
+                .and_then(|(_, _, name)| {
+                    resolved.headers.iter().find(|header| header.name == name)
+                });
+            // Replace an include directive with an empty line, then the header.
+            if include.is_none() {
+                self.source.push_str(line);
+            }
+            self.source.push('\n');
+            self.lines += 1;
+            if let Some(header) = include
+                && self.expanded.insert(&header.name)
+            {
+                self.expand(resolved, &header.source, &header.dependency.file_name());
+                let next = u32::try_from(index + 2).unwrap_or(u32::MAX);
+                self.line_map.push(self.lines + 1, file, next);
+            }
+        }
+    }
+}
+
+/// The delimiter of an `#include` directive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IncludeKind {
+    /// `#include "name"`
+    Quoted,
+    /// `#include <name>`
+    Angled,
+}
+
+/// Find the `#include` directives in `OpenCL` C source, ignoring comments.
+fn scan_includes(source: &str) -> Vec<(usize, IncludeKind, String)> {
+    let mut includes = Vec::new();
+    let mut in_block_comment = false;
+    for (index, line) in source.lines().enumerate() {
+        // Remove comments from the line, tracking block comments across lines.
+        let mut code = String::new();
+        let mut rest = line;
+        loop {
+            if in_block_comment {
+                match rest.find("*/") {
+                    Some(end) => {
+                        rest = &rest[end + 2..];
+                        in_block_comment = false;
+                        code.push(' ');
+                    }
+                    None => break,
+                }
+            } else {
+                match (rest.find("/*"), rest.find("//")) {
+                    (Some(block), Some(line)) if line < block => {
+                        code.push_str(&rest[..line]);
+                        break;
+                    }
+                    (Some(block), _) => {
+                        code.push_str(&rest[..block]);
+                        rest = &rest[block + 2..];
+                        in_block_comment = true;
+                    }
+                    (None, Some(line)) => {
+                        code.push_str(&rest[..line]);
+                        break;
+                    }
+                    (None, None) => {
+                        code.push_str(rest);
+                        break;
+                    }
+                }
+            }
+        }
+
+        let Some(directive) = code.trim_start().strip_prefix('#') else {
+            continue;
+        };
+        let Some(operand) = directive.trim_start().strip_prefix("include") else {
+            continue;
+        };
+        let operand = operand.trim();
+        let (kind, close) = match operand.chars().next() {
+            Some('"') => (IncludeKind::Quoted, '"'),
+            Some('<') => (IncludeKind::Angled, '>'),
+            _ => continue,
+        };
+        if let Some(end) = operand[1..].find(close) {
+            includes.push((index, kind, operand[1..=end].to_owned()));
+        }
+    }
+    includes
+}
+
+/// Loads `OpenCL` C source and resolves its `#include` directives,
+/// see the module documentation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceLoader {
+    include_dirs: Vec<PathBuf>,
+    virtual_files: BTreeMap<String, String>,
+}
+
+impl SourceLoader {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a directory to search for included files.
+    #[must_use]
+    pub fn include_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.include_dirs.push(dir.into());
+        self
+    }
+
+    /// Add a virtual file, e.g. a header embedded with `include_str!`.
+    ///
+    /// * `name` - the name of the file in `#include` directives.
+    /// * `source` - the contents of the file.
+    #[must_use]
+    pub fn virtual_file(mut self, name: impl Into<String>, source: impl Into<String>) -> Self {
+        self.virtual_files.insert(name.into(), source.into());
+        self
+    }
+
+    /// The include directories.
+    #[must_use]
+    pub fn include_dirs(&self) -> &[PathBuf] {
+        &self.include_dirs
+    }
+
+    /// Read a source file and resolve its includes.
+    ///
+    /// * `path` - the path of the source file.
+    ///
+    /// returns a Result containing the `ResolvedSource` or a `SourceLoaderError`.
+    pub fn load(&self, path: impl AsRef<Path>) -> Result<ResolvedSource, SourceLoaderError> {
+        let path = path.as_ref();
+        let source = read(path)?;
+        self.resolve(source, path.parent())
+    }
+
+    /// Resolve the includes of a program source.
+    ///
+    /// * `source` - the program source.
+    /// * `source_dir` - the directory of the source, to resolve quoted includes.
+    ///
+    /// returns a Result containing the `ResolvedSource` or a `SourceLoaderError`.
+    pub fn resolve(
+        &self,
+        source: impl Into<String>,
+        source_dir: Option<&Path>,
+    ) -> Result<ResolvedSource, SourceLoaderError> {
+        let source = source.into();
+        let mut headers: Vec<IncludeHeader> = Vec::new();
+
+        // The files to scan: their source, directory and description.
+        let mut pending = vec![(
+            source.clone(),
+            source_dir.map(Path::to_path_buf),
+            String::from("the program source"),
+        )];
+        while let Some((text, dir, description)) = pending.pop() {
+            for (_, kind, name) in scan_includes(&text) {
+                let name = name.as_str();
+                let dependency = self.find(kind, name, dir.as_deref()).ok_or_else(|| {
+                    SourceLoaderError::NotFound {
+                        name: name.to_owned(),
+                        included_from: description.clone(),
+                    }
+                })?;
+
+                if let Some(header) = headers.iter().find(|header| header.name == name) {
+                    if header.dependency != dependency {
+                        return Err(SourceLoaderError::Conflict {
+                            name: name.to_owned(),
+                            first: header.dependency.describe(),
+                            second: dependency.describe(),
+                        });
+                    }
+                    continue;
+                }
+
+                let (header_source, header_dir) = match &dependency {
+                    Dependency::File(path) => (read(path)?, path.parent().map(Path::to_path_buf)),
+                    Dependency::Virtual(name) => (self.virtual_files[name].clone(), None),
+                };
+                pending.push((header_source.clone(), header_dir, dependency.describe()));
+                headers.push(IncludeHeader {
+                    name: name.to_owned(),
+                    dependency,
+                    source: header_source,
+                });
+            }
+        }
+
+        Ok(ResolvedSource { source, headers })
+    }
+
+    /// Find an included file.
+    fn find(&self, kind: IncludeKind, name: &str, dir: Option<&Path>) -> Option<Dependency> {
+        if self.virtual_files.contains_key(name) {
+            return Some(Dependency::Virtual(name.to_owned()));
+        }
+
+        let local_dir = dir.filter(|_| kind == IncludeKind::Quoted);
+        local_dir
+            .into_iter()
+            .chain(self.include_dirs.iter().map(PathBuf::as_path))
+            .map(|dir| dir.join(name))
+            .find(|path| path.is_file())
+            .map(|path| Dependency::File(fs::canonicalize(&path).unwrap_or(path)))
+    }
+}
+
+/// Compile a program source with its included headers and link it.
+///
+/// Creates a header program for each of the headers and calls
+/// `compile_program` with them, then calls `link_program`.
+/// `CL_VERSION_1_2`
+///
+/// * `context` - a valid `OpenCL` context.
+/// * `devices` - a slice of devices that are in context.
+/// * `resolved` - the program source and headers, from [`SourceLoader::resolve`]
+///   or [`SourceLoader::load`].
+/// * `compile_options` - the compilation options.
+/// * `link_options` - the link options.
+///
+/// returns a Result containing the linked program or a `SourceLoaderError`.
+/// The build log of a failed compilation is that of the first device.
+///
+/// # Safety
+///
+/// This is unsafe when a device is not a member of context.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+pub unsafe fn compile_and_link(
+    context: cl_context,
+    devices: &[cl_device_id],
+    resolved: &ResolvedSource,
+    compile_options: &CStr,
+    link_options: &CStr,
+) -> Result<cl_program, SourceLoaderError> {
+    let include_names = resolved.include_names()?;
+    let include_names: Vec<&CStr> = include_names.iter().map(CString::as_c_str).collect();
+
+    let mut header_programs = Vec::with_capacity(resolved.headers.len());
+    for header in &resolved.headers {
+        match create_program_with_source(context, &[&header.source]) {
+            Ok(program) => header_programs.push(program),
+            Err(status) => {
+                release_programs(&header_programs);
+                return Err(status.into());
+            }
+        }
+    }
+
+    let compiled = create_program_with_source(context, &[&resolved.source]);
+    let result = compiled
+        .map_err(SourceLoaderError::from)
+        .and_then(|program| {
+            let status = compile_program(
+                program,
+                devices,
+                compile_options,
+                &header_programs,
+                &include_names,
+                None,
+                std::ptr::null_mut(),
+            );
+            let result = status
+                .map_err(|status| {
+                    let log = devices
+                        .first()
+                        .and_then(|&device| {
+                            get_program_build_info(program, device, CL_PROGRAM_BUILD_LOG).ok()
+                        })
+                        .map(String::from)
+                        .unwrap_or_default();
+                    SourceLoaderError::Build { status, log }
+                })
+                .and_then(|()| unsafe {
+                    link_program(
+                        context,
+                        devices,
+                        link_options,
+                        &[program],
+                        None,
+                        std::ptr::null_mut(),
+                    )
+                    .map_err(SourceLoaderError::from)
+                });
+            release_programs(&[program]);
+            result
+        });
+    release_programs(&header_programs);
+    result
+}
+
+/// Release programs, ignoring errors.
+#[cfg(any(feature = "CL_VERSION_1_2", feature = "dynamic"))]
+fn release_programs(programs: &[cl_program]) {
+    for &program in programs {
+        unsafe {
+            let _ = release_program(program);
+        }
+    }
+}
+
+/// Read a source file.
+fn read(path: &Path) -> Result<String, SourceLoaderError> {
+    fs::read_to_string(path).map_err(|source| SourceLoaderError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    #[test]
+    fn test_scan_includes() {
+        let source = r#"
+#include "common.h"
+  #  include <math/constants.h>
+// #include "commented.h"
+/* #include "block.h"
+#include "still_block.h" */
+#define INCLUDE 1
+#include "trailing.h" // comment
+kernel void noop() {}
+"#;
+        let includes = scan_includes(source);
+        assert_eq!(
+            vec![
+                (1, IncludeKind::Quoted, String::from("common.h")),
+                (2, IncludeKind::Angled, String::from("math/constants.h")),
+                (7, IncludeKind::Quoted, String::from("trailing.h")),
+            ],
+            includes
+        );
+    }
+
+    #[test]
+    fn test_resolve_virtual_files() {
+        let loader = SourceLoader::new()
+            .virtual_file("types.h", "#include \"config.h\"\ntypedef float real;\n")
+            .virtual_file("config.h", "#define SIZE 16\n")
+            .virtual_file("unused.h", "");
+        let resolved = loader
+            .resolve(
+                "#include \"types.h\"\n#include <config.h>\nkernel void noop() {}\n",
+                None,
+            )
+            .unwrap();
+        let names: Vec<&str> = resolved.headers.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(vec!["types.h", "config.h"], names);
+        assert_eq!(
+            vec![
+                &Dependency::Virtual(String::from("types.h")),
+                &Dependency::Virtual(String::from("config.h"))
+            ],
+            resolved.dependencies()
+        );
+        assert_eq!(2, resolved.include_names().unwrap().len());
+
+        let (source, line_map) = resolved.expand("main.cl");
+        assert_eq!(
+            "\n\n#define SIZE 16\ntypedef float real;\n\nkernel void noop() {}\n",
+            source
+        );
+        assert_eq!(Some(("config.h", 1)), line_map.map(3));
+        assert_eq!(Some(("types.h", 2)), line_map.map(4));
+        assert_eq!(Some(("main.cl", 2)), line_map.map(5));
+        assert_eq!(Some(("main.cl", 3)), line_map.map(6));
+
+        let error = loader
+            .resolve("#include \"missing.h\"\n", None)
+            .unwrap_err();
+        assert!(matches!(error, SourceLoaderError::NotFound { name, .. } if name == "missing.h"));
+    }
+
+    #[test]
+    fn test_resolve_files() {
+        let root = std::env::temp_dir().join(format!("cl3_source_loader_{}", process::id()));
+        let include = root.join("include");
+        let other = root.join("other");
+        fs::create_dir_all(&include).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        fs::write(
+            root.join("main.cl"),
+            "#include \"local.h\"\n#include <lib.h>\n",
+        )
+        .unwrap();
+        fs::write(root.join("local.h"), "#define LOCAL 1\n").unwrap();
+        fs::write(include.join("lib.h"), "#include \"detail.h\"\n").unwrap();
+        fs::write(include.join("detail.h"), "#define DETAIL 1\n").unwrap();
+        fs::write(other.join("local.h"), "#define OTHER 1\n").unwrap();
+
+        let loader = SourceLoader::new().include_dir(&include);
+        let resolved = loader.load(root.join("main.cl")).unwrap();
+        let names: Vec<&str> = resolved.headers.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(vec!["local.h", "lib.h", "detail.h"], names);
+        assert_eq!("#define LOCAL 1\n", resolved.headers[0].source);
+        assert_eq!(
+            Dependency::File(fs::canonicalize(include.join("detail.h")).unwrap()),
+            resolved.headers[2].dependency
+        );
+
+        // Angled includes are not resolved relative to the including file
+        let error = loader
+            .resolve("#include <local.h>\n", Some(&root))
+            .unwrap_err();
+        assert!(matches!(error, SourceLoaderError::NotFound { .. }));
+
+        // The same include name can't refer to different files
+        fs::write(other.join("lib.h"), "#include \"local.h\"\n").unwrap();
+        let loader = SourceLoader::new().include_dir(&other);
+        let error = loader
+            .resolve("#include \"local.h\"\n#include <lib.h>\n", Some(&root))
+            .unwrap_err();
+        assert!(matches!(error, SourceLoaderError::Conflict { name, .. } if name == "local.h"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,397 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed SPIR-V specialization constants.
+//!
+//! [`SpecConstants`] holds the specialization constants of a SPIR-V module,
+//! see [`SpirvModule`], and sets them from typed [`SpecValue`]s with
+//! `clSetProgramSpecializationConstant`, checking the type and size of each
+//! value against the `OpSpecConstant` declaration of its `SpecId`.
+//!
+//! Constants can be set by `SpecId` or by name, using the `OpName` debug
+//! names of the module and any names added with [`SpecConstants::name`].
+//!
+//! Note: `OpenCL` SPIR-V modules declare all integer types as unsigned,
+//! so integer values are checked by width only.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub use super::spirv::{SpecConstant, SpecConstantType, SpirvModule};
+pub use opencl_sys::{CL_INVALID_SPEC_ID, CL_INVALID_VALUE, cl_int, cl_program, cl_uint};
+
+use super::error_codes::{ClError, error_text};
+#[cfg(any(feature = "CL_VERSION_2_2", feature = "dynamic"))]
+use super::program::set_program_specialization_constant;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// A typed specialization constant value.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpecValue {
+    Bool(bool),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    /// A half precision float, as its IEEE 754 bits.
+    F16(u16),
+    F32(f32),
+    F64(f64),
+}
+
+impl SpecValue {
+    /// The SPIR-V type of the value.
+    #[must_use]
+    pub const fn ty(self) -> SpecConstantType {
+        match self {
+            Self::Bool(_) => SpecConstantType::Bool,
+            Self::I8(_) => SpecConstantType::Int {
+                width: 8,
+                signed: true,
+            },
+            Self::U8(_) => SpecConstantType::Int {
+                width: 8,
+                signed: false,
+            },
+            Self::I16(_) => SpecConstantType::Int {
+                width: 16,
+                signed: true,
+            },
+            Self::U16(_) => SpecConstantType::Int {
+                width: 16,
+                signed: false,
+            },
+            Self::I32(_) => SpecConstantType::Int {
+                width: 32,
+                signed: true,
+            },
+            Self::U32(_) => SpecConstantType::Int {
+                width: 32,
+                signed: false,
+            },
+            Self::I64(_) => SpecConstantType::Int {
+                width: 64,
+                signed: true,
+            },
+            Self::U64(_) => SpecConstantType::Int {
+                width: 64,
+                signed: false,
+            },
+            Self::F16(_) => SpecConstantType::Float { width: 16 },
+            Self::F32(_) => SpecConstantType::Float { width: 32 },
+            Self::F64(_) => SpecConstantType::Float { width: 64 },
+        }
+    }
+
+    /// The value in host byte order, as `clSetProgramSpecializationConstant` expects.
+    /// A `bool` is a single byte: 0 for false, 1 for true.
+    #[must_use]
+    pub fn to_ne_bytes(self) -> Vec<u8> {
+        match self {
+            Self::Bool(value) => vec![u8::from(value)],
+            Self::I8(value) => value.to_ne_bytes().to_vec(),
+            Self::U8(value) => value.to_ne_bytes().to_vec(),
+            Self::I16(value) => value.to_ne_bytes().to_vec(),
+            Self::U16(value) | Self::F16(value) => value.to_ne_bytes().to_vec(),
+            Self::I32(value) => value.to_ne_bytes().to_vec(),
+            Self::U32(value) => value.to_ne_bytes().to_vec(),
+            Self::I64(value) => value.to_ne_bytes().to_vec(),
+            Self::U64(value) => value.to_ne_bytes().to_vec(),
+            Self::F32(value) => value.to_ne_bytes().to_vec(),
+            Self::F64(value) => value.to_ne_bytes().to_vec(),
+        }
+    }
+
+    /// Whether the value can set a constant of the given type.
+    #[must_use]
+    pub const fn matches(self, ty: SpecConstantType) -> bool {
+        match (self.ty(), ty) {
+            (SpecConstantType::Bool, SpecConstantType::Bool) => true,
+            (SpecConstantType::Int { width: a, .. }, SpecConstantType::Int { width: b, .. })
+            | (SpecConstantType::Float { width: a }, SpecConstantType::Float { width: b }) => {
+                a == b
+            }
+            _ => false,
+        }
+    }
+}
+
+macro_rules! spec_value_from {
+    ($type:ty, $variant:ident) => {
+        impl From<$type> for SpecValue {
+            fn from(value: $type) -> Self {
+                Self::$variant(value)
+            }
+        }
+    };
+}
+
+spec_value_from!(bool, Bool);
+spec_value_from!(i8, I8);
+spec_value_from!(u8, U8);
+spec_value_from!(i16, I16);
+spec_value_from!(u16, U16);
+spec_value_from!(i32, I32);
+spec_value_from!(u32, U32);
+spec_value_from!(i64, I64);
+spec_value_from!(u64, U64);
+spec_value_from!(f32, F32);
+spec_value_from!(f64, F64);
+
+/// The reasons that a specialization constant may not be set.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum SpecConstantError {
+    #[error("no specialization constant with SpecId {0}")]
+    UnknownSpecId(cl_uint),
+    #[error("no specialization constant named {0}")]
+    UnknownName(String),
+    #[error("specialization constant {spec_id} has type {expected}, the value has type {found}")]
+    TypeMismatch {
+        spec_id: cl_uint,
+        expected: SpecConstantType,
+        found: SpecConstantType,
+    },
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl SpecConstantError {
+    /// The `OpenCL` error code that `clSetProgramSpecializationConstant`
+    /// would have returned for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::UnknownSpecId(_) | Self::UnknownName(_) => CL_INVALID_SPEC_ID,
+            Self::TypeMismatch { .. } => CL_INVALID_VALUE,
+            Self::Cl(error) => *error,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for SpecConstantError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<SpecConstantError> for ClError {
+    fn from(error: SpecConstantError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// The specialization constants of a SPIR-V module, see the module documentation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpecConstants {
+    constants: Vec<SpecConstant>,
+    names: HashMap<String, cl_uint>,
+}
+
+impl SpecConstants {
+    /// Get the specialization constants of a SPIR-V module.
+    #[must_use]
+    pub fn new(module: &SpirvModule) -> Self {
+        Self::from(module.spec_constants.clone())
+    }
+
+    /// Add a name for a `SpecId`, e.g. for a module without debug names.
+    /// Added names take precedence over the `OpName` debug names.
+    #[must_use]
+    pub fn name(mut self, name: impl Into<String>, spec_id: cl_uint) -> Self {
+        self.names.insert(name.into(), spec_id);
+        self
+    }
+
+    /// The specialization constants, in `SpecId` order.
+    #[must_use]
+    pub fn constants(&self) -> &[SpecConstant] {
+        &self.constants
+    }
+
+    /// Get the `SpecId` of a named specialization constant.
+    #[must_use]
+    pub fn spec_id(&self, name: &str) -> Option<cl_uint> {
+        self.names.get(name).copied().or_else(|| {
+            self.constants
+                .iter()
+                .find(|constant| constant.name.as_deref() == Some(name))
+                .map(|constant| constant.spec_id)
+        })
+    }
+
+    /// Check that a value can set a specialization constant.
+    ///
+    /// * `spec_id` - the `SpecId` of the specialization constant.
+    /// * `value` - the value.
+    ///
+    /// returns an empty Result or a `SpecConstantError`.
+    pub fn check(&self, spec_id: cl_uint, value: SpecValue) -> Result<(), SpecConstantError> {
+        let constant = self
+            .constants
+            .iter()
+            .find(|constant| constant.spec_id == spec_id)
+            .ok_or(SpecConstantError::UnknownSpecId(spec_id))?;
+        if value.matches(constant.ty) {
+            Ok(())
+        } else {
+            Err(SpecConstantError::TypeMismatch {
+                spec_id,
+                expected: constant.ty,
+                found: value.ty(),
+            })
+        }
+    }
+
+    /// Set the value of a specialization constant.
+    /// Calls `clSetProgramSpecializationConstant` if the value matches the
+    /// type of the constant.
+    /// `CL_VERSION_2_2`
+    ///
+    /// * `program` - a program created from the SPIR-V module.
+    /// * `spec_id` - the `SpecId` of the specialization constant.
+    /// * `value` - the value.
+    ///
+    /// returns an empty Result or a `SpecConstantError`.
+    #[cfg(any(feature = "CL_VERSION_2_2", feature = "dynamic"))]
+    pub fn set(
+        &self,
+        program: cl_program,
+        spec_id: cl_uint,
+        value: impl Into<SpecValue>,
+    ) -> Result<(), SpecConstantError> {
+        let value = value.into();
+        self.check(spec_id, value)?;
+        let bytes = value.to_ne_bytes();
+        Ok(unsafe {
+            set_program_specialization_constant(
+                program,
+                spec_id,
+                bytes.len(),
+                bytes.as_ptr().cast(),
+            )
+        }?)
+    }
+
+    /// Set the value of a named specialization constant, see [`Self::set`].
+    /// `CL_VERSION_2_2`
+    ///
+    /// * `program` - a program created from the SPIR-V module.
+    /// * `name` - the name of the specialization constant.
+    /// * `value` - the value.
+    ///
+    /// returns an empty Result or a `SpecConstantError`.
+    #[cfg(any(feature = "CL_VERSION_2_2", feature = "dynamic"))]
+    pub fn set_by_name(
+        &self,
+        program: cl_program,
+        name: &str,
+        value: impl Into<SpecValue>,
+    ) -> Result<(), SpecConstantError> {
+        let spec_id = self
+            .spec_id(name)
+            .ok_or_else(|| SpecConstantError::UnknownName(name.to_owned()))?;
+        self.set(program, spec_id, value)
+    }
+}
+
+impl From<Vec<SpecConstant>> for SpecConstants {
+    fn from(constants: Vec<SpecConstant>) -> Self {
+        Self {
+            constants,
+            names: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec_constants() -> SpecConstants {
+        SpecConstants::from(vec![
+            SpecConstant {
+                spec_id: 0,
+                name: Some(String::from("USE_FAST_PATH")),
+                ty: SpecConstantType::Bool,
+            },
+            SpecConstant {
+                spec_id: 1,
+                name: Some(String::from("TILE_SIZE")),
+                ty: SpecConstantType::Int {
+                    width: 32,
+                    signed: false,
+                },
+            },
+            SpecConstant {
+                spec_id: 2,
+                name: None,
+                ty: SpecConstantType::Float { width: 32 },
+            },
+        ])
+        .name("SCALE", 2)
+    }
+
+    #[test]
+    fn test_spec_value() {
+        assert_eq!(vec![1], SpecValue::from(true).to_ne_bytes());
+        assert_eq!(
+            16_u32.to_ne_bytes().to_vec(),
+            SpecValue::from(16_u32).to_ne_bytes()
+        );
+        assert_eq!(8, SpecValue::from(1.0_f64).to_ne_bytes().len());
+        assert_eq!(
+            Some(SpecValue::F16(0x3c00).to_ne_bytes().len()),
+            SpecConstantType::Float { width: 16 }.size()
+        );
+    }
+
+    #[test]
+    fn test_check_spec_constants() {
+        let constants = spec_constants();
+        assert_eq!(Some(1), constants.spec_id("TILE_SIZE"));
+        assert_eq!(Some(2), constants.spec_id("SCALE"));
+        assert_eq!(None, constants.spec_id("MISSING"));
+
+        assert_eq!(Ok(()), constants.check(0, SpecValue::from(false)));
+        // Integer signedness is not checked
+        assert_eq!(Ok(()), constants.check(1, SpecValue::from(16_i32)));
+        assert_eq!(Ok(()), constants.check(2, SpecValue::from(0.5_f32)));
+
+        let error = constants.check(1, SpecValue::from(16_u64)).unwrap_err();
+        assert_eq!(
+            "specialization constant 1 has type u32, the value has type u64",
+            error.to_string()
+        );
+        assert_eq!(CL_INVALID_VALUE, error.error_code());
+        assert_eq!(
+            Err(SpecConstantError::TypeMismatch {
+                spec_id: 2,
+                expected: SpecConstantType::Float { width: 32 },
+                found: SpecConstantType::Float { width: 64 },
+            }),
+            constants.check(2, SpecValue::from(0.5_f64))
+        );
+        assert_eq!(
+            Err(SpecConstantError::UnknownSpecId(3)),
+            constants.check(3, SpecValue::from(true))
+        );
+    }
+}
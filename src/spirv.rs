@@ -0,0 +1,883 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! SPIR-V module inspection.
+//!
+//! [`SpirvModule::parse`] reads the header and the module level instructions
+//! of a SPIR-V binary: capabilities, extensions, the memory model,
+//! entry points and specialization constants.
+//!
+//! [`SpirvModule::check`] checks a module against the SPIR-V support of a
+//! device, see [`DeviceSpirvSupport`], so that an incompatible module can be
+//! rejected with a clear reason before it is passed to `clCreateProgramWithIL`.
+//!
+//! See: [SPIR-V Specification](https://registry.khronos.org/SPIR-V/specs/unified1/SPIRV.html)
+//! and [OpenCL SPIR-V Environment](https://registry.khronos.org/OpenCL/specs/3.0-unified/html/OpenCL_Env.html).
+
+#![allow(non_camel_case_types)]
+
+pub use opencl_sys::{
+    CL_DEVICE_ADDRESS_BITS, CL_DEVICE_IL_VERSION, CL_DEVICE_ILS_WITH_VERSION,
+    CL_DEVICE_SPIRV_CAPABILITIES_KHR, CL_DEVICE_SPIRV_EXTENSIONS_KHR, CL_INVALID_VALUE, cl_context,
+    cl_device_id, cl_int, cl_program, cl_version,
+};
+
+use super::device::{get_device_data, get_device_info};
+use super::error_codes::{ClError, error_text};
+#[cfg(any(feature = "cl_khr_il_program", feature = "dynamic"))]
+use super::ext::create_program_with_il_khr;
+#[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+use super::program::create_program_with_il;
+use libc::c_char;
+use opencl_sys::{make_version, version_major, version_minor};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::fmt;
+use std::mem;
+use thiserror::Error;
+
+/// The SPIR-V magic number.
+pub const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+// SPIR-V opcodes of the module level instructions.
+const OP_NAME: u32 = 5;
+const OP_EXTENSION: u32 = 10;
+const OP_EXT_INST_IMPORT: u32 = 11;
+const OP_MEMORY_MODEL: u32 = 14;
+const OP_ENTRY_POINT: u32 = 15;
+const OP_CAPABILITY: u32 = 17;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_SPEC_CONSTANT_TRUE: u32 = 48;
+const OP_SPEC_CONSTANT_FALSE: u32 = 49;
+const OP_SPEC_CONSTANT: u32 = 50;
+const OP_SPEC_CONSTANT_COMPOSITE: u32 = 51;
+const OP_DECORATE: u32 = 71;
+
+/// The `SpecId` decoration.
+const DECORATION_SPEC_ID: u32 = 1;
+
+/// The names of the SPIR-V capabilities, for error messages.
+const CAPABILITY_NAMES: &[(u32, &str)] = &[
+    (0, "Matrix"),
+    (1, "Shader"),
+    (2, "Geometry"),
+    (3, "Tessellation"),
+    (4, "Addresses"),
+    (5, "Linkage"),
+    (6, "Kernel"),
+    (7, "Vector16"),
+    (8, "Float16Buffer"),
+    (9, "Float16"),
+    (10, "Float64"),
+    (11, "Int64"),
+    (12, "Int64Atomics"),
+    (13, "ImageBasic"),
+    (14, "ImageReadWrite"),
+    (15, "ImageMipmap"),
+    (17, "Pipes"),
+    (18, "Groups"),
+    (19, "DeviceEnqueue"),
+    (20, "LiteralSampler"),
+    (21, "AtomicStorage"),
+    (22, "Int16"),
+    (38, "GenericPointer"),
+    (39, "Int8"),
+    (43, "Sampled1D"),
+    (44, "Image1D"),
+    (46, "SampledBuffer"),
+    (47, "ImageBuffer"),
+    (58, "SubgroupDispatch"),
+    (59, "NamedBarrier"),
+    (60, "PipeStorage"),
+    (61, "GroupNonUniform"),
+    (62, "GroupNonUniformVote"),
+    (63, "GroupNonUniformArithmetic"),
+    (64, "GroupNonUniformBallot"),
+    (65, "GroupNonUniformShuffle"),
+    (66, "GroupNonUniformShuffleRelative"),
+    (67, "GroupNonUniformClustered"),
+];
+
+/// Get the name of a SPIR-V capability.
+#[must_use]
+pub fn capability_name(capability: u32) -> Option<&'static str> {
+    CAPABILITY_NAMES
+        .iter()
+        .find(|(value, _)| *value == capability)
+        .map(|(_, name)| *name)
+}
+
+/// Format a capability by name if it is known, otherwise by value.
+fn capability_text(capability: u32) -> String {
+    capability_name(capability).map_or_else(|| capability.to_string(), str::to_owned)
+}
+
+/// Format a SPIR-V version as "major.minor".
+fn version_text(version: cl_version) -> String {
+    format!("{}.{}", version_major(version), version_minor(version))
+}
+
+/// Format a list of SPIR-V versions.
+fn versions_text(versions: &[cl_version]) -> String {
+    versions
+        .iter()
+        .map(|&version| version_text(version))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A SPIR-V execution model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExecutionModel {
+    Vertex,
+    TessellationControl,
+    TessellationEvaluation,
+    Geometry,
+    Fragment,
+    GLCompute,
+    Kernel,
+    Other(u32),
+}
+
+impl From<u32> for ExecutionModel {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::Vertex,
+            1 => Self::TessellationControl,
+            2 => Self::TessellationEvaluation,
+            3 => Self::Geometry,
+            4 => Self::Fragment,
+            5 => Self::GLCompute,
+            6 => Self::Kernel,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+impl fmt::Display for ExecutionModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Other(value) => write!(f, "ExecutionModel({value})"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+
+/// A SPIR-V addressing model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AddressingModel {
+    Logical,
+    Physical32,
+    Physical64,
+    PhysicalStorageBuffer64,
+    Other(u32),
+}
+
+impl AddressingModel {
+    /// The address bits required by the addressing model, if any.
+    #[must_use]
+    pub const fn address_bits(self) -> Option<u32> {
+        match self {
+            Self::Physical32 => Some(32),
+            Self::Physical64 | Self::PhysicalStorageBuffer64 => Some(64),
+            Self::Logical | Self::Other(_) => None,
+        }
+    }
+}
+
+impl From<u32> for AddressingModel {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::Logical,
+            1 => Self::Physical32,
+            2 => Self::Physical64,
+            5348 => Self::PhysicalStorageBuffer64,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+impl fmt::Display for AddressingModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Other(value) => write!(f, "AddressingModel({value})"),
+            _ => write!(f, "{self:?}"),
+        }
+    }
+}
+
+/// An entry point of a SPIR-V module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryPoint {
+    pub execution_model: ExecutionModel,
+    pub name: String,
+}
+
+/// The type of a SPIR-V specialization constant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SpecConstantType {
+    Bool,
+    Int {
+        width: u32,
+        signed: bool,
+    },
+    Float {
+        width: u32,
+    },
+    /// A composite or another type that cannot be set directly.
+    Other,
+}
+
+impl SpecConstantType {
+    /// The size in bytes of a value of the type for
+    /// `set_program_specialization_constant`.
+    #[must_use]
+    pub const fn size(self) -> Option<usize> {
+        match self {
+            Self::Bool => Some(1),
+            Self::Int { width, .. } | Self::Float { width } => Some(width as usize / 8),
+            Self::Other => None,
+        }
+    }
+}
+
+impl fmt::Display for SpecConstantType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Bool => write!(f, "bool"),
+            Self::Int {
+                width,
+                signed: true,
+            } => write!(f, "i{width}"),
+            Self::Int {
+                width,
+                signed: false,
+            } => write!(f, "u{width}"),
+            Self::Float { width } => write!(f, "f{width}"),
+            Self::Other => write!(f, "composite"),
+        }
+    }
+}
+
+/// A specialization constant of a SPIR-V module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpecConstant {
+    /// The `SpecId` decoration of the constant.
+    pub spec_id: u32,
+    /// The debug name of the constant, if the module has one.
+    pub name: Option<String>,
+    pub ty: SpecConstantType,
+}
+
+/// The reasons that a SPIR-V module may be invalid or not supported by a device.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum SpirvError {
+    #[error("not a SPIR-V module: invalid magic number")]
+    InvalidMagic,
+    #[error("invalid SPIR-V module: {0}")]
+    InvalidModule(&'static str),
+    #[error("SPIR-V {} is not supported by the device, supported versions: {}", version_text(*version), versions_text(supported))]
+    UnsupportedVersion {
+        version: cl_version,
+        supported: Vec<cl_version>,
+    },
+    #[error("SPIR-V capability {} is not supported by the device", capability_text(*.0))]
+    UnsupportedCapability(u32),
+    #[error("SPIR-V extension {0} is not supported by the device")]
+    UnsupportedExtension(String),
+    #[error("addressing model {model} requires {} bit addresses, the device has {address_bits}", model.address_bits().unwrap_or_default())]
+    UnsupportedAddressingModel {
+        model: AddressingModel,
+        address_bits: u32,
+    },
+    #[error("entry point {name} has execution model {execution_model}, OpenCL requires Kernel")]
+    UnsupportedExecutionModel {
+        name: String,
+        execution_model: ExecutionModel,
+    },
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+/// Implement the From trait
+impl From<cl_int> for SpirvError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<SpirvError> for ClError {
+    fn from(error: SpirvError) -> Self {
+        match error {
+            SpirvError::Cl(error) => Self(error),
+            _ => Self(CL_INVALID_VALUE),
+        }
+    }
+}
+
+/// Decode a null terminated SPIR-V literal string.
+///
+/// returns the string and the number of words that it occupies.
+fn literal_string(words: &[u32]) -> Result<(String, usize), SpirvError> {
+    let mut bytes = Vec::new();
+    for (index, word) in words.iter().enumerate() {
+        for byte in word.to_le_bytes() {
+            if 0 == byte {
+                let text = String::from_utf8(bytes)
+                    .map_err(|_| SpirvError::InvalidModule("invalid UTF-8 string"))?;
+                return Ok((text, index + 1));
+            }
+            bytes.push(byte);
+        }
+    }
+    Err(SpirvError::InvalidModule("unterminated string"))
+}
+
+/// Decode a SPIR-V binary into words in host order, using the magic number
+/// to detect the byte order.
+fn decode_words(il: &[u8]) -> Result<Vec<u32>, SpirvError> {
+    if !il.len().is_multiple_of(4) {
+        return Err(SpirvError::InvalidModule("length is not a multiple of 4"));
+    }
+    let mut words: Vec<u32> = il
+        .chunks_exact(4)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect();
+    match words.first() {
+        Some(&SPIRV_MAGIC) => Ok(words),
+        Some(magic) if SPIRV_MAGIC.swap_bytes() == *magic => {
+            for word in &mut words {
+                *word = word.swap_bytes();
+            }
+            Ok(words)
+        }
+        _ => Err(SpirvError::InvalidMagic),
+    }
+}
+
+/// The module level information of a SPIR-V module, see the module documentation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SpirvModule {
+    /// The SPIR-V version of the module.
+    pub version: cl_version,
+    /// The generator magic number.
+    pub generator: u32,
+    /// The bound of the result ids.
+    pub bound: u32,
+    pub capabilities: Vec<u32>,
+    pub extensions: Vec<String>,
+    /// The extended instruction sets, e.g. "OpenCL.std".
+    pub ext_inst_imports: Vec<String>,
+    pub addressing_model: Option<AddressingModel>,
+    pub memory_model: Option<u32>,
+    pub entry_points: Vec<EntryPoint>,
+    /// The specialization constants, in `SpecId` order.
+    pub spec_constants: Vec<SpecConstant>,
+}
+
+impl SpirvModule {
+    /// Parse a SPIR-V binary in either byte order.
+    ///
+    /// * `il` - the SPIR-V binary.
+    ///
+    /// returns a Result containing the `SpirvModule`
+    /// or a `SpirvError` if the binary is not a valid SPIR-V module.
+    pub fn parse(il: &[u8]) -> Result<Self, SpirvError> {
+        let words = decode_words(il)?;
+        if words.len() < 5 {
+            return Err(SpirvError::InvalidModule("truncated header"));
+        }
+
+        let mut module = Self {
+            version: make_version((words[1] >> 16) & 0xff, (words[1] >> 8) & 0xff, 0),
+            generator: words[2],
+            bound: words[3],
+            ..Self::default()
+        };
+
+        let mut names: HashMap<u32, String> = HashMap::new();
+        let mut spec_ids: HashMap<u32, u32> = HashMap::new();
+        let mut types: HashMap<u32, SpecConstantType> = HashMap::new();
+        let mut constants: Vec<(u32, SpecConstantType)> = Vec::new();
+
+        let mut rest = &words[5..];
+        while let Some(&first) = rest.first() {
+            let count = (first >> 16) as usize;
+            if 0 == count || rest.len() < count {
+                return Err(SpirvError::InvalidModule("truncated instruction"));
+            }
+            let (instruction, next) = rest.split_at(count);
+            rest = next;
+            let operands = &instruction[1..];
+            let operand = |index: usize| {
+                operands
+                    .get(index)
+                    .copied()
+                    .ok_or(SpirvError::InvalidModule("missing operand"))
+            };
+
+            match first & 0xffff {
+                OP_CAPABILITY => module.capabilities.push(operand(0)?),
+                OP_EXTENSION => module.extensions.push(literal_string(operands)?.0),
+                OP_EXT_INST_IMPORT => {
+                    let strings = operands.get(1..).unwrap_or_default();
+                    module.ext_inst_imports.push(literal_string(strings)?.0);
+                }
+                OP_MEMORY_MODEL => {
+                    module.addressing_model = Some(AddressingModel::from(operand(0)?));
+                    module.memory_model = Some(operand(1)?);
+                }
+                OP_ENTRY_POINT => {
+                    let strings = operands.get(2..).unwrap_or_default();
+                    module.entry_points.push(EntryPoint {
+                        execution_model: ExecutionModel::from(operand(0)?),
+                        name: literal_string(strings)?.0,
+                    });
+                }
+                OP_NAME => {
+                    let strings = operands.get(1..).unwrap_or_default();
+                    names.insert(operand(0)?, literal_string(strings)?.0);
+                }
+                OP_DECORATE if DECORATION_SPEC_ID == operand(1)? => {
+                    spec_ids.insert(operand(0)?, operand(2)?);
+                }
+                OP_TYPE_BOOL => {
+                    types.insert(operand(0)?, SpecConstantType::Bool);
+                }
+                OP_TYPE_INT => {
+                    let ty = SpecConstantType::Int {
+                        width: operand(1)?,
+                        signed: 0 != operand(2)?,
+                    };
+                    types.insert(operand(0)?, ty);
+                }
+                OP_TYPE_FLOAT => {
+                    let ty = SpecConstantType::Float { width: operand(1)? };
+                    types.insert(operand(0)?, ty);
+                }
+                OP_SPEC_CONSTANT_TRUE | OP_SPEC_CONSTANT_FALSE | OP_SPEC_CONSTANT => {
+                    let ty = types
+                        .get(&operand(0)?)
+                        .copied()
+                        .unwrap_or(SpecConstantType::Other);
+                    constants.push((operand(1)?, ty));
+                }
+                OP_SPEC_CONSTANT_COMPOSITE => {
+                    constants.push((operand(1)?, SpecConstantType::Other));
+                }
+                _ => {}
+            }
+        }
+
+        module.spec_constants = constants
+            .into_iter()
+            .filter_map(|(id, ty)| {
+                spec_ids.get(&id).map(|&spec_id| SpecConstant {
+                    spec_id,
+                    name: names.get(&id).cloned(),
+                    ty,
+                })
+            })
+            .collect();
+        module
+            .spec_constants
+            .sort_by_key(|constant| constant.spec_id);
+        Ok(module)
+    }
+
+    /// Get a specialization constant by `SpecId`.
+    #[must_use]
+    pub fn spec_constant(&self, spec_id: u32) -> Option<&SpecConstant> {
+        self.spec_constants
+            .iter()
+            .find(|constant| constant.spec_id == spec_id)
+    }
+
+    /// The kernel names of the module.
+    #[must_use]
+    pub fn kernel_names(&self) -> Vec<&str> {
+        self.entry_points
+            .iter()
+            .filter(|entry_point| ExecutionModel::Kernel == entry_point.execution_model)
+            .map(|entry_point| entry_point.name.as_str())
+            .collect()
+    }
+
+    /// Check the module against the SPIR-V support of a device.
+    ///
+    /// * `support` - the SPIR-V support of the device.
+    ///
+    /// returns an empty Result or a `SpirvError` describing the first incompatibility.
+    pub fn check(&self, support: &DeviceSpirvSupport) -> Result<(), SpirvError> {
+        let same_version = |a: cl_version, b: cl_version| {
+            version_major(a) == version_major(b) && version_minor(a) == version_minor(b)
+        };
+        if !support
+            .versions
+            .iter()
+            .any(|&version| same_version(version, self.version))
+        {
+            return Err(SpirvError::UnsupportedVersion {
+                version: self.version,
+                supported: support.versions.clone(),
+            });
+        }
+
+        if let Some(entry_point) = self
+            .entry_points
+            .iter()
+            .find(|entry_point| ExecutionModel::Kernel != entry_point.execution_model)
+        {
+            return Err(SpirvError::UnsupportedExecutionModel {
+                name: entry_point.name.clone(),
+                execution_model: entry_point.execution_model,
+            });
+        }
+
+        if let Some(model) = self.addressing_model
+            && let Some(bits) = model.address_bits()
+            && bits != support.address_bits
+        {
+            return Err(SpirvError::UnsupportedAddressingModel {
+                model,
+                address_bits: support.address_bits,
+            });
+        }
+
+        if let Some(capabilities) = &support.capabilities
+            && let Some(&capability) = self
+                .capabilities
+                .iter()
+                .find(|capability| !capabilities.contains(capability))
+        {
+            return Err(SpirvError::UnsupportedCapability(capability));
+        }
+
+        if let Some(extensions) = &support.extensions
+            && let Some(extension) = self
+                .extensions
+                .iter()
+                .find(|extension| !extensions.contains(extension))
+        {
+            return Err(SpirvError::UnsupportedExtension(extension.clone()));
+        }
+        Ok(())
+    }
+
+    /// Check the module against the SPIR-V support of a device,
+    /// see [`Self::check`] and [`DeviceSpirvSupport::get`].
+    ///
+    /// * `device` - the `OpenCL` device.
+    ///
+    /// returns an empty Result or a `SpirvError`.
+    pub fn check_device(&self, device: cl_device_id) -> Result<(), SpirvError> {
+        self.check(&DeviceSpirvSupport::get(device)?)
+    }
+}
+
+/// The SPIR-V support of a device.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceSpirvSupport {
+    /// The supported SPIR-V versions.
+    pub versions: Vec<cl_version>,
+    /// The device address bits: `CL_DEVICE_ADDRESS_BITS`.
+    pub address_bits: u32,
+    /// The supported SPIR-V capabilities, if known.
+    pub capabilities: Option<Vec<u32>>,
+    /// The supported SPIR-V extensions, if known.
+    pub extensions: Option<Vec<String>>,
+}
+
+impl DeviceSpirvSupport {
+    /// Query the SPIR-V support of a device.
+    ///
+    /// The SPIR-V versions are read from `CL_DEVICE_ILS_WITH_VERSION`
+    /// (`CL_VERSION_3_0`) or `CL_DEVICE_IL_VERSION`.
+    /// The capabilities and extensions are only known if the device supports
+    /// `cl_khr_spirv_queries`, otherwise they are not checked.
+    ///
+    /// * `device` - the `OpenCL` device.
+    ///
+    /// returns a Result containing the `DeviceSpirvSupport`
+    /// or the error code from the `OpenCL` C API function.
+    pub fn get(device: cl_device_id) -> Result<Self, cl_int> {
+        let mut versions: Vec<cl_version> = get_device_info(device, CL_DEVICE_ILS_WITH_VERSION)
+            .map(|value| {
+                value
+                    .to_vec_name_version()
+                    .iter()
+                    .filter(|il| {
+                        CStr::from_bytes_until_nul(&il.name)
+                            .is_ok_and(|name| name.to_bytes() == b"SPIR-V")
+                    })
+                    .map(|il| il.version)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if versions.is_empty() {
+            let text: String = get_device_info(device, CL_DEVICE_IL_VERSION)?.into();
+            versions = parse_il_versions(&text);
+        }
+
+        let address_bits = get_device_info(device, CL_DEVICE_ADDRESS_BITS)?.to_uint();
+
+        let capabilities = get_device_data(device, CL_DEVICE_SPIRV_CAPABILITIES_KHR)
+            .ok()
+            .map(|bytes| {
+                bytes
+                    .chunks_exact(mem::size_of::<u32>())
+                    .map(|bytes| u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    .collect()
+            });
+
+        // An array of null terminated strings owned by the implementation.
+        let extensions = get_device_data(device, CL_DEVICE_SPIRV_EXTENSIONS_KHR)
+            .ok()
+            .map(|bytes| {
+                bytes
+                    .chunks_exact(mem::size_of::<*const c_char>())
+                    .filter_map(|bytes| {
+                        let address = usize::from_ne_bytes(bytes.try_into().ok()?);
+                        let name = address as *const c_char;
+                        (!name.is_null())
+                            .then(|| unsafe { CStr::from_ptr(name) }.to_string_lossy().into())
+                    })
+                    .collect()
+            });
+
+        Ok(Self {
+            versions,
+            address_bits,
+            capabilities,
+            extensions,
+        })
+    }
+}
+
+/// Parse the SPIR-V versions from a `CL_DEVICE_IL_VERSION` string,
+/// e.g. "SPIR-V_1.0 SPIR-V_1.1".
+fn parse_il_versions(text: &str) -> Vec<cl_version> {
+    text.split_whitespace()
+        .filter_map(|il| {
+            let (major, minor) = il.strip_prefix("SPIR-V_")?.split_once('.')?;
+            Some(make_version(major.parse().ok()?, minor.parse().ok()?, 0))
+        })
+        .collect()
+}
+
+/// Parse a SPIR-V module and check it against each of the devices.
+#[cfg(any(
+    feature = "CL_VERSION_2_1",
+    feature = "cl_khr_il_program",
+    feature = "dynamic"
+))]
+fn check_il(devices: &[cl_device_id], il: &[u8]) -> Result<(), SpirvError> {
+    let module = SpirvModule::parse(il)?;
+    for &device in devices {
+        module.check_device(device)?;
+    }
+    Ok(())
+}
+
+/// Check a SPIR-V module against the devices and create a program from it.
+/// Calls `clCreateProgramWithIL` if the module is supported by all of the devices.
+/// `CL_VERSION_2_1`
+///
+/// * `context` - a valid `OpenCL` context.
+/// * `devices` - the devices that the program will be built for.
+/// * `il` - the SPIR-V binary.
+///
+/// returns a Result containing the new `OpenCL` program object
+/// or a `SpirvError`.
+#[cfg(any(feature = "CL_VERSION_2_1", feature = "dynamic"))]
+pub fn create_program_with_checked_il(
+    context: cl_context,
+    devices: &[cl_device_id],
+    il: &[u8],
+) -> Result<cl_program, SpirvError> {
+    check_il(devices, il)?;
+    Ok(create_program_with_il(context, il)?)
+}
+
+/// Check a SPIR-V module against the devices and create a program from it.
+/// Calls `clCreateProgramWithILKHR` if the module is supported by all of the devices.
+/// `cl_khr_il_program`
+///
+/// * `context` - a valid `OpenCL` context.
+/// * `devices` - the devices that the program will be built for.
+/// * `il` - the SPIR-V binary.
+///
+/// returns a Result containing the new `OpenCL` program object
+/// or a `SpirvError`.
+#[cfg(any(feature = "cl_khr_il_program", feature = "dynamic"))]
+pub fn create_program_with_checked_il_khr(
+    context: cl_context,
+    devices: &[cl_device_id],
+    il: &[u8],
+) -> Result<cl_program, SpirvError> {
+    check_il(devices, il)?;
+    Ok(create_program_with_il_khr(context, il)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Encode a SPIR-V instruction.
+    fn instruction(opcode: u32, operands: &[u32]) -> Vec<u32> {
+        let count = u32::try_from(operands.len() + 1).unwrap();
+        let mut words = vec![(count << 16) | opcode];
+        words.extend_from_slice(operands);
+        words
+    }
+
+    // Encode a SPIR-V literal string.
+    fn string(text: &str) -> Vec<u32> {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.resize((bytes.len() / 4 + 1) * 4, 0);
+        bytes
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    }
+
+    // A SPIR-V 1.0 kernel module with two specialization constants.
+    fn test_module() -> Vec<u32> {
+        let mut words = vec![SPIRV_MAGIC, 0x0001_0000, 0, 20, 0];
+        words.extend(instruction(OP_CAPABILITY, &[4]));
+        words.extend(instruction(OP_CAPABILITY, &[6]));
+        words.extend(instruction(OP_CAPABILITY, &[11]));
+        words.extend(instruction(
+            OP_EXTENSION,
+            &string("SPV_KHR_no_integer_wrap_decoration"),
+        ));
+        let mut import = vec![1];
+        import.extend(string("OpenCL.std"));
+        words.extend(instruction(OP_EXT_INST_IMPORT, &import));
+        words.extend(instruction(OP_MEMORY_MODEL, &[2, 2]));
+        let mut entry = vec![6, 10];
+        entry.extend(string("add"));
+        words.extend(instruction(OP_ENTRY_POINT, &entry));
+        let mut name = vec![12];
+        name.extend(string("TILE_SIZE"));
+        words.extend(instruction(OP_NAME, &name));
+        words.extend(instruction(OP_DECORATE, &[12, DECORATION_SPEC_ID, 7]));
+        words.extend(instruction(OP_DECORATE, &[13, DECORATION_SPEC_ID, 3]));
+        words.extend(instruction(OP_TYPE_BOOL, &[2]));
+        words.extend(instruction(OP_TYPE_INT, &[3, 32, 0]));
+        words.extend(instruction(OP_SPEC_CONSTANT, &[3, 12, 16]));
+        words.extend(instruction(OP_SPEC_CONSTANT_TRUE, &[2, 13]));
+        words
+    }
+
+    fn to_bytes(words: &[u32], swap: bool) -> Vec<u8> {
+        words
+            .iter()
+            .flat_map(|word| {
+                if swap {
+                    word.to_be_bytes()
+                } else {
+                    word.to_le_bytes()
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_spirv_module() {
+        let words = test_module();
+        for swap in [false, true] {
+            let module = SpirvModule::parse(&to_bytes(&words, swap)).unwrap();
+            assert_eq!(make_version(1, 0, 0), module.version);
+            assert_eq!(vec![4, 6, 11], module.capabilities);
+            assert_eq!(
+                vec![String::from("SPV_KHR_no_integer_wrap_decoration")],
+                module.extensions
+            );
+            assert_eq!(vec![String::from("OpenCL.std")], module.ext_inst_imports);
+            assert_eq!(Some(AddressingModel::Physical64), module.addressing_model);
+            assert_eq!(vec!["add"], module.kernel_names());
+            assert_eq!(
+                vec![
+                    SpecConstant {
+                        spec_id: 3,
+                        name: None,
+                        ty: SpecConstantType::Bool
+                    },
+                    SpecConstant {
+                        spec_id: 7,
+                        name: Some(String::from("TILE_SIZE")),
+                        ty: SpecConstantType::Int {
+                            width: 32,
+                            signed: false
+                        }
+                    },
+                ],
+                module.spec_constants
+            );
+        }
+
+        assert_eq!(
+            Err(SpirvError::InvalidMagic),
+            SpirvModule::parse(&[0, 0, 0, 0])
+        );
+        let truncated = to_bytes(&words[..words.len() - 1], false);
+        assert_eq!(
+            Err(SpirvError::InvalidModule("truncated instruction")),
+            SpirvModule::parse(&truncated)
+        );
+    }
+
+    #[test]
+    fn test_check_spirv_module() {
+        let module = SpirvModule::parse(&to_bytes(&test_module(), false)).unwrap();
+        let mut support = DeviceSpirvSupport {
+            versions: parse_il_versions("SPIR-V_1.0 SPIR-V_1.1"),
+            address_bits: 64,
+            capabilities: None,
+            extensions: None,
+        };
+        assert_eq!(Ok(()), module.check(&support));
+
+        support.capabilities = Some(vec![4, 5, 6]);
+        let error = module.check(&support).unwrap_err();
+        assert_eq!(SpirvError::UnsupportedCapability(11), error);
+        assert_eq!(
+            "SPIR-V capability Int64 is not supported by the device",
+            error.to_string()
+        );
+
+        support.capabilities = None;
+        support.extensions = Some(Vec::new());
+        assert!(matches!(
+            module.check(&support),
+            Err(SpirvError::UnsupportedExtension(_))
+        ));
+
+        support.address_bits = 32;
+        assert!(matches!(
+            module.check(&support),
+            Err(SpirvError::UnsupportedAddressingModel { .. })
+        ));
+
+        support.versions = vec![make_version(1, 2, 0)];
+        assert_eq!(
+            "SPIR-V 1.0 is not supported by the device, supported versions: 1.2",
+            module.check(&support).unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,529 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Shared virtual memory (SVM) vectors owned by Rust, `CL_VERSION_2_0`.
+//!
+//! An [`SvmVec`] allocates coarse or fine grained SVM, depending upon the
+//! `CL_DEVICE_SVM_CAPABILITIES` of the device of a command-queue.
+//!
+//! The elements are [`Pod`], since kernels may write any bit pattern to them.
+//!
+//! The host must map coarse grained SVM before accessing it: [`SvmVec::map`]
+//! returns an [`SvmMapGuard`] that dereferences to the elements and unmaps
+//! them when it is dropped. The map is enqueued on the command-queue that the
+//! vector was created on, after the commands that were enqueued before it.
+//! Fine grained SVM is accessed directly with [`SvmVec::as_slice`] and
+//! [`SvmVec::as_mut_slice`], which are unsafe since running kernels may access
+//! the elements at the same time.
+//!
+//! The SVM is freed by `clSVMFree` when the vector is dropped. If the vector
+//! has been set as a kernel argument, the drop first waits for the commands
+//! on the command-queue that the vector was created on to complete, so
+//! kernels that use the vector must be enqueued on that command-queue.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub use opencl_sys::{
+    CL_DEVICE_SVM_ATOMICS, CL_DEVICE_SVM_CAPABILITIES, CL_DEVICE_SVM_COARSE_GRAIN_BUFFER,
+    CL_DEVICE_SVM_FINE_GRAIN_BUFFER, CL_MAP_READ, CL_MAP_WRITE, CL_MAP_WRITE_INVALIDATE_REGION,
+    CL_MEM_READ_ONLY, CL_MEM_READ_WRITE, CL_MEM_SVM_ATOMICS, CL_MEM_SVM_FINE_GRAIN_BUFFER,
+    CL_MEM_WRITE_ONLY, cl_command_queue, cl_context, cl_device_id, cl_device_svm_capabilities,
+    cl_int, cl_kernel, cl_map_flags, cl_svm_mem_flags, cl_uint,
+};
+
+use super::command_queue::{
+    CL_QUEUE_CONTEXT, CL_QUEUE_DEVICE, finish, get_command_queue_info, release_command_queue,
+    retain_command_queue,
+};
+use super::device::get_device_info;
+use super::enqueue::{enqueue_svm_map, enqueue_svm_unmap};
+use super::error_codes::{ClError, error_text};
+use super::event::{release_event, wait_for_events};
+use super::kernel::set_kernel_arg_svm_pointer;
+use super::memory::{svm_alloc, svm_free};
+use super::types::Pod;
+use libc::c_void;
+use opencl_sys::{CL_BLOCKING, CL_INVALID_ARG_VALUE, CL_INVALID_OPERATION, CL_INVALID_VALUE};
+use std::cell::Cell;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+use std::slice;
+use thiserror::Error;
+
+/// The granularity of SVM: whether the host must map it before access.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SvmGranularity {
+    /// Coarse grained buffer SVM, accessed by the host through maps.
+    Coarse,
+    /// Fine grained buffer SVM, accessed directly by the host.
+    Fine,
+    /// Fine grained buffer SVM with atomics shared by the host and device.
+    FineAtomics,
+}
+
+impl SvmGranularity {
+    /// The finest SVM granularity in a `cl_device_svm_capabilities`.
+    ///
+    /// returns the granularity or None if buffer SVM is not supported.
+    #[must_use]
+    pub const fn from_capabilities(capabilities: cl_device_svm_capabilities) -> Option<Self> {
+        if capabilities & CL_DEVICE_SVM_FINE_GRAIN_BUFFER != 0 {
+            if capabilities & CL_DEVICE_SVM_ATOMICS != 0 {
+                Some(Self::FineAtomics)
+            } else {
+                Some(Self::Fine)
+            }
+        } else if capabilities & CL_DEVICE_SVM_COARSE_GRAIN_BUFFER != 0 {
+            Some(Self::Coarse)
+        } else {
+            None
+        }
+    }
+
+    /// The finest SVM granularity supported by a device:
+    /// `CL_DEVICE_SVM_CAPABILITIES`.
+    ///
+    /// * `device` - the `OpenCL` device.
+    ///
+    /// returns a Result containing the granularity, `SvmError::Unsupported`
+    /// if the device does not support buffer SVM,
+    /// or the error code from the `OpenCL` C API function.
+    pub fn for_device(device: cl_device_id) -> Result<Self, SvmError> {
+        let capabilities = get_device_info(device, CL_DEVICE_SVM_CAPABILITIES)?.to_ulong();
+        Self::from_capabilities(capabilities).ok_or(SvmError::Unsupported)
+    }
+
+    /// The `cl_svm_mem_flags` to allocate SVM of this granularity.
+    #[must_use]
+    pub const fn mem_flags(self) -> cl_svm_mem_flags {
+        match self {
+            Self::Coarse => 0,
+            Self::Fine => CL_MEM_SVM_FINE_GRAIN_BUFFER,
+            Self::FineAtomics => CL_MEM_SVM_FINE_GRAIN_BUFFER | CL_MEM_SVM_ATOMICS,
+        }
+    }
+
+    /// Whether the host must map SVM of this granularity before access.
+    #[must_use]
+    pub const fn requires_map(self) -> bool {
+        matches!(self, Self::Coarse)
+    }
+}
+
+/// The reasons that an [`SvmVec`] operation may fail.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum SvmError {
+    #[error("the device does not support buffer SVM")]
+    Unsupported,
+    #[error("coarse grained SVM must be mapped for host access")]
+    CoarseGrained,
+    #[error("an empty vector can't be a kernel argument")]
+    Empty,
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl SvmError {
+    /// The `OpenCL` error code that the `OpenCL` SVM functions would have
+    /// returned for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::Unsupported => CL_INVALID_OPERATION,
+            Self::CoarseGrained => CL_INVALID_VALUE,
+            Self::Empty => CL_INVALID_ARG_VALUE,
+            Self::Cl(error) => *error,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for SvmError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<SvmError> for ClError {
+    fn from(error: SvmError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// An owned SVM allocation of `len` elements, see the module documentation.
+#[derive(Debug)]
+pub struct SvmVec<T> {
+    context: cl_context,
+    /// The command-queue that the vector was created on, it is retained.
+    command_queue: cl_command_queue,
+    ptr: *mut T,
+    len: usize,
+    granularity: SvmGranularity,
+    /// Whether kernels that use the vector may be outstanding.
+    enqueued: Cell<bool>,
+}
+
+/// Unmap SVM and wait for the unmap to complete.
+fn unmap_svm(command_queue: cl_command_queue, svm_ptr: *mut c_void) -> Result<(), SvmError> {
+    let event = unsafe { enqueue_svm_unmap(command_queue, svm_ptr, &[], true)? };
+    if let Some(event) = event {
+        let result = wait_for_events(&[event]);
+        unsafe {
+            let _ = release_event(event);
+        }
+        result?;
+    }
+    Ok(())
+}
+
+impl<T: Pod> SvmVec<T> {
+    /// Allocate SVM for `len` elements. The elements are uninitialised and
+    /// an empty vector does not allocate.
+    fn alloc(
+        context: cl_context,
+        command_queue: cl_command_queue,
+        granularity: SvmGranularity,
+        len: usize,
+        flags: cl_svm_mem_flags,
+    ) -> Result<Self, SvmError> {
+        let size = mem::size_of::<T>()
+            .checked_mul(len)
+            .ok_or(SvmError::Cl(CL_INVALID_VALUE))?;
+        #[allow(clippy::cast_possible_truncation)]
+        let alignment = mem::align_of::<T>() as cl_uint;
+        let ptr: *mut T = if size == 0 {
+            NonNull::dangling().as_ptr()
+        } else {
+            unsafe { svm_alloc(context, flags | granularity.mem_flags(), size, alignment)? }.cast()
+        };
+        if let Err(error) = unsafe { retain_command_queue(command_queue) } {
+            if 0 < size {
+                unsafe {
+                    let _ = svm_free(context, ptr.cast::<c_void>());
+                }
+            }
+            return Err(error.into());
+        }
+        Ok(Self {
+            context,
+            command_queue,
+            ptr,
+            len,
+            granularity,
+            enqueued: Cell::new(false),
+        })
+    }
+
+    /// Create a vector in the context of a command-queue, with the finest
+    /// SVM granularity of its device.
+    /// Calls `clSVMAlloc` and initialises the elements on `command_queue`.
+    ///
+    /// * `command_queue` - the `OpenCL` command-queue.
+    /// * `len` - the number of elements.
+    /// * `value` - the initial value of the elements.
+    /// * `flags` - the device access flags, e.g. `CL_MEM_READ_WRITE`.
+    ///
+    /// returns a Result containing the new vector
+    /// or an `SvmError`.
+    pub fn new(
+        command_queue: cl_command_queue,
+        len: usize,
+        value: T,
+        flags: cl_svm_mem_flags,
+    ) -> Result<Self, SvmError> {
+        Self::from_fn(command_queue, len, flags, |_| value)
+    }
+
+    /// Create a vector from a slice, see [`SvmVec::new`].
+    ///
+    /// * `command_queue` - the `OpenCL` command-queue.
+    /// * `data` - the initial elements.
+    /// * `flags` - the device access flags, e.g. `CL_MEM_READ_ONLY`.
+    ///
+    /// returns a Result containing the new vector
+    /// or an `SvmError`.
+    pub fn from_slice(
+        command_queue: cl_command_queue,
+        data: &[T],
+        flags: cl_svm_mem_flags,
+    ) -> Result<Self, SvmError> {
+        Self::from_fn(command_queue, data.len(), flags, |index| data[index])
+    }
+
+    fn from_fn<F>(
+        command_queue: cl_command_queue,
+        len: usize,
+        flags: cl_svm_mem_flags,
+        f: F,
+    ) -> Result<Self, SvmError>
+    where
+        F: Fn(usize) -> T,
+    {
+        let context = get_command_queue_info(command_queue, CL_QUEUE_CONTEXT)?.to_ptr();
+        let device = get_command_queue_info(command_queue, CL_QUEUE_DEVICE)?.to_ptr();
+        let granularity = SvmGranularity::for_device(device as cl_device_id)?;
+        let vec = Self::alloc(
+            context as cl_context,
+            command_queue,
+            granularity,
+            len,
+            flags,
+        )?;
+
+        if granularity.requires_map() && 0 < vec.size() {
+            unsafe {
+                enqueue_svm_map(
+                    command_queue,
+                    CL_BLOCKING,
+                    CL_MAP_WRITE_INVALIDATE_REGION,
+                    vec.ptr.cast::<c_void>(),
+                    vec.size(),
+                    &[],
+                    false,
+                )?;
+            }
+        }
+        for index in 0..len {
+            unsafe { vec.ptr.add(index).write(f(index)) };
+        }
+        if granularity.requires_map() && 0 < vec.size() {
+            unmap_svm(command_queue, vec.ptr.cast::<c_void>())?;
+        }
+        Ok(vec)
+    }
+
+    #[must_use]
+    pub const fn context(&self) -> cl_context {
+        self.context
+    }
+
+    #[must_use]
+    pub const fn granularity(&self) -> SvmGranularity {
+        self.granularity
+    }
+
+    /// The number of elements.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The size in bytes of the vector.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        mem::size_of::<T>() * self.len
+    }
+
+    /// The SVM pointer, e.g. for `enqueue_svm_mem_cpy`.
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    #[must_use]
+    pub const fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// The elements of fine grained SVM.
+    ///
+    /// # Safety
+    ///
+    /// No enqueued commands may write to the vector while the slice is
+    /// borrowed.
+    ///
+    /// returns a Result containing the elements
+    /// or `SvmError::CoarseGrained` if they must be mapped.
+    pub const unsafe fn as_slice(&self) -> Result<&[T], SvmError> {
+        if self.granularity.requires_map() {
+            Err(SvmError::CoarseGrained)
+        } else {
+            Ok(unsafe { slice::from_raw_parts(self.ptr, self.len) })
+        }
+    }
+
+    /// The mutable elements of fine grained SVM.
+    ///
+    /// # Safety
+    ///
+    /// No enqueued commands may access the vector while the slice is
+    /// borrowed.
+    ///
+    /// returns a Result containing the elements
+    /// or `SvmError::CoarseGrained` if they must be mapped.
+    pub const unsafe fn as_mut_slice(&mut self) -> Result<&mut [T], SvmError> {
+        if self.granularity.requires_map() {
+            Err(SvmError::CoarseGrained)
+        } else {
+            Ok(unsafe { slice::from_raw_parts_mut(self.ptr, self.len) })
+        }
+    }
+
+    /// Map the vector for host access and wait for the map to complete.
+    /// Calls `clEnqueueSVMMap` with blocking set on the command-queue that the
+    /// vector was created on.
+    ///
+    /// If the vector has been set as a kernel argument, the map first waits
+    /// for the commands on the command-queue to complete, so that the host
+    /// does not access the vector while kernels may be using it.
+    /// The map is not required for fine grained SVM, but it is valid.
+    ///
+    /// * `flags` - the host access flags, e.g. `CL_MAP_READ | CL_MAP_WRITE`.
+    ///
+    /// returns a Result containing a guard that unmaps the vector when it is
+    /// dropped or the error code from the `OpenCL` C API function.
+    pub fn map(&mut self, flags: cl_map_flags) -> Result<SvmMapGuard<'_, T>, SvmError> {
+        if self.enqueued.get() {
+            finish(self.command_queue)?;
+        }
+        if 0 < self.size() {
+            unsafe {
+                enqueue_svm_map(
+                    self.command_queue,
+                    CL_BLOCKING,
+                    flags,
+                    self.ptr.cast::<c_void>(),
+                    self.size(),
+                    &[],
+                    false,
+                )?;
+            }
+        }
+        Ok(SvmMapGuard { vec: self })
+    }
+
+    /// Set the vector as a kernel argument.
+    /// Calls `clSetKernelArgSVMPointer`.
+    ///
+    /// The kernel must be enqueued on the command-queue that the vector was
+    /// created on, since the vector waits for that command-queue to finish
+    /// before it is freed.
+    ///
+    /// * `kernel` - the `OpenCL` kernel.
+    /// * `arg_index` - the index of the kernel argument.
+    ///
+    /// returns an empty Result, `SvmError::Empty` if the vector is empty,
+    /// or the error code from the `OpenCL` C API function.
+    pub fn set_kernel_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), SvmError> {
+        if 0 == self.size() {
+            return Err(SvmError::Empty);
+        }
+        unsafe { set_kernel_arg_svm_pointer(kernel, arg_index, self.ptr.cast::<c_void>())? };
+        self.enqueued.set(true);
+        Ok(())
+    }
+}
+
+impl<T> Drop for SvmVec<T> {
+    fn drop(&mut self) {
+        if 0 < mem::size_of::<T>() * self.len {
+            if self.enqueued.get() {
+                // Kernels may still be using the vector
+                let _ = finish(self.command_queue);
+            }
+            unsafe {
+                let _ = svm_free(self.context, self.ptr.cast::<c_void>());
+            }
+        }
+        unsafe {
+            let _ = release_command_queue(self.command_queue);
+        }
+    }
+}
+
+/// Host access to a mapped [`SvmVec`]: the vector is unmapped when the guard
+/// is dropped, and the drop waits for the unmap to complete.
+#[derive(Debug)]
+pub struct SvmMapGuard<'a, T: Pod> {
+    vec: &'a mut SvmVec<T>,
+}
+
+impl<T: Pod> SvmMapGuard<'_, T> {
+    /// Unmap the vector and wait for the unmap to complete, returning any
+    /// error from `clEnqueueSVMUnmap`.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    pub fn unmap(self) -> Result<(), SvmError> {
+        let result = self.enqueue_unmap();
+        mem::forget(self);
+        result
+    }
+
+    fn enqueue_unmap(&self) -> Result<(), SvmError> {
+        if 0 < self.vec.size() {
+            unmap_svm(self.vec.command_queue, self.vec.ptr.cast::<c_void>())?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Pod> Deref for SvmMapGuard<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vec.ptr, self.vec.len) }
+    }
+}
+
+impl<T: Pod> DerefMut for SvmMapGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.vec.ptr, self.vec.len) }
+    }
+}
+
+impl<T: Pod> Drop for SvmMapGuard<'_, T> {
+    fn drop(&mut self) {
+        let _ = self.enqueue_unmap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svm_granularity() {
+        assert_eq!(None, SvmGranularity::from_capabilities(0));
+        assert_eq!(
+            Some(SvmGranularity::Coarse),
+            SvmGranularity::from_capabilities(CL_DEVICE_SVM_COARSE_GRAIN_BUFFER)
+        );
+        assert_eq!(
+            Some(SvmGranularity::Fine),
+            SvmGranularity::from_capabilities(
+                CL_DEVICE_SVM_COARSE_GRAIN_BUFFER | CL_DEVICE_SVM_FINE_GRAIN_BUFFER
+            )
+        );
+        assert_eq!(
+            Some(SvmGranularity::FineAtomics),
+            SvmGranularity::from_capabilities(
+                CL_DEVICE_SVM_COARSE_GRAIN_BUFFER
+                    | CL_DEVICE_SVM_FINE_GRAIN_BUFFER
+                    | CL_DEVICE_SVM_ATOMICS
+            )
+        );
+        assert!(SvmGranularity::Coarse.requires_map());
+        assert!(!SvmGranularity::Fine.requires_map());
+        assert_eq!(0, SvmGranularity::Coarse.mem_flags());
+        assert_eq!(CL_INVALID_ARG_VALUE, SvmError::Empty.error_code());
+    }
+}
@@ -35,3 +35,24 @@ pub use opencl_sys::{
     cl_queue_properties, cl_sampler, cl_sampler_info, cl_sampler_properties, cl_short,
     cl_svm_mem_flags, cl_uchar, cl_uint, cl_ulong, cl_ushort, cl_version,
 };
+
+/// Plain old data: `Copy` types that are valid for any bit pattern and have
+/// no padding, so that device memory can be read into them and their bytes
+/// written to it.
+///
+/// # Safety
+///
+/// A type may only implement `Pod` if every bit pattern of its size is a
+/// valid value, e.g. not `bool`, `char`, references or enums, and it has no
+/// padding bytes.
+pub unsafe trait Pod: Copy {}
+
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(u8, i8, u16, i16, u32, i32, u64, i64, usize, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
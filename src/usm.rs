@@ -0,0 +1,753 @@
+// Copyright (c) 2025 Via Technology Ltd.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//    http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `cl_intel_unified_shared_memory` allocations owned by Rust.
+//!
+//! A [`UsmBox<[T]>`](UsmBox) owns a host, device or shared unified shared
+//! memory (USM) allocation of `len` elements of `T`, created with typed
+//! [`UsmAllocProperties`]. Copies, fills, memory advice and migrations are
+//! enqueued on a command-queue with bounds checked offsets.
+//!
+//! An allocation is freed when it is dropped. If commands that use the
+//! allocation have been enqueued, it is freed with `clMemBlockingFreeINTEL`,
+//! which waits for the commands to complete.
+
+#![allow(non_camel_case_types)]
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+pub use opencl_sys::{
+    CL_MEM_ALLOC_BUFFER_LOCATION_INTEL, CL_MEM_ALLOC_DEVICE_INTEL, CL_MEM_ALLOC_FLAGS_INTEL,
+    CL_MEM_ALLOC_INITIAL_PLACEMENT_DEVICE_INTEL, CL_MEM_ALLOC_INITIAL_PLACEMENT_HOST_INTEL,
+    CL_MEM_ALLOC_TYPE_INTEL, CL_MEM_ALLOC_WRITE_COMBINED_INTEL, CL_MEM_TYPE_DEVICE_INTEL,
+    CL_MEM_TYPE_HOST_INTEL, CL_MEM_TYPE_SHARED_INTEL, CL_MEM_TYPE_UNKNOWN_INTEL, cl_command_queue,
+    cl_context, cl_device_id, cl_event, cl_int, cl_kernel, cl_mem_advice_intel,
+    cl_mem_alloc_flags_intel, cl_mem_migration_flags, cl_mem_properties_intel, cl_uint,
+    cl_unified_shared_memory_type_intel,
+};
+
+use super::enqueue::wait_list_parts;
+use super::error_codes::{ClError, error_text};
+use super::ext::{
+    device_mem_alloc_intel, enqueue_mem_advise_intel, enqueue_mem_copy_intel,
+    enqueue_mem_fill_intel, enqueue_migrate_mem_intel, get_mem_alloc_info_intel,
+    host_mem_alloc_intel, mem_blocking_free_intel, mem_free_intel,
+    set_kernel_arg_mem_pointer_intel, shared_mem_alloc_intel,
+};
+use super::types::Pod;
+use libc::c_void;
+use opencl_sys::{CL_FALSE, CL_INVALID_MEM_OBJECT, CL_INVALID_VALUE, CL_TRUE};
+use std::cell::Cell;
+use std::mem;
+use std::ptr;
+use thiserror::Error;
+
+/// The kind of a unified shared memory allocation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UsmKind {
+    /// Host memory that devices access over a bus: `CL_MEM_TYPE_HOST_INTEL`.
+    Host,
+    /// Memory owned by a device, not accessible from the host:
+    /// `CL_MEM_TYPE_DEVICE_INTEL`.
+    Device,
+    /// Memory that migrates between the host and devices:
+    /// `CL_MEM_TYPE_SHARED_INTEL`.
+    Shared,
+}
+
+impl UsmKind {
+    /// The `cl_unified_shared_memory_type_intel` of the kind.
+    #[must_use]
+    pub const fn mem_type(self) -> cl_unified_shared_memory_type_intel {
+        match self {
+            Self::Host => CL_MEM_TYPE_HOST_INTEL,
+            Self::Device => CL_MEM_TYPE_DEVICE_INTEL,
+            Self::Shared => CL_MEM_TYPE_SHARED_INTEL,
+        }
+    }
+
+    /// The kind of a `cl_unified_shared_memory_type_intel`,
+    /// None for `CL_MEM_TYPE_UNKNOWN_INTEL`.
+    #[must_use]
+    pub const fn from_mem_type(mem_type: cl_unified_shared_memory_type_intel) -> Option<Self> {
+        match mem_type {
+            CL_MEM_TYPE_HOST_INTEL => Some(Self::Host),
+            CL_MEM_TYPE_DEVICE_INTEL => Some(Self::Device),
+            CL_MEM_TYPE_SHARED_INTEL => Some(Self::Shared),
+            _ => None,
+        }
+    }
+
+    /// Whether the host can access memory of this kind.
+    #[must_use]
+    pub const fn is_host_accessible(self) -> bool {
+        !matches!(self, Self::Device)
+    }
+}
+
+/// The reasons that a [`UsmBox`] operation may fail.
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+pub enum UsmError {
+    #[error("{0:?} memory is not accessible from the host")]
+    NotHostAccessible(UsmKind),
+    #[error("elements {offset}..{end} are out of range of an allocation of {len} elements")]
+    OutOfRange {
+        offset: usize,
+        end: usize,
+        len: usize,
+    },
+    #[error("{}", error_text(*.0))]
+    Cl(cl_int),
+}
+
+impl UsmError {
+    /// The `OpenCL` error code that the `cl_intel_unified_shared_memory`
+    /// functions would have returned for this error.
+    #[must_use]
+    pub const fn error_code(&self) -> cl_int {
+        match self {
+            Self::NotHostAccessible(_) => CL_INVALID_MEM_OBJECT,
+            Self::OutOfRange { .. } => CL_INVALID_VALUE,
+            Self::Cl(error) => *error,
+        }
+    }
+}
+
+/// Implement the From trait
+impl From<cl_int> for UsmError {
+    fn from(error: cl_int) -> Self {
+        Self::Cl(error)
+    }
+}
+
+/// Implement the From trait for `ClError`
+impl From<UsmError> for ClError {
+    fn from(error: UsmError) -> Self {
+        Self(error.error_code())
+    }
+}
+
+/// The properties to allocate a [`UsmBox`] with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UsmAllocProperties {
+    flags: cl_mem_alloc_flags_intel,
+    alignment: cl_uint,
+}
+
+impl UsmAllocProperties {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add allocation flags: `CL_MEM_ALLOC_FLAGS_INTEL`.
+    #[must_use]
+    pub const fn flags(mut self, flags: cl_mem_alloc_flags_intel) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    /// Allocate write combined memory: `CL_MEM_ALLOC_WRITE_COMBINED_INTEL`.
+    #[must_use]
+    pub const fn write_combined(self) -> Self {
+        self.flags(CL_MEM_ALLOC_WRITE_COMBINED_INTEL)
+    }
+
+    /// Place a shared allocation on the device before it is first accessed:
+    /// `CL_MEM_ALLOC_INITIAL_PLACEMENT_DEVICE_INTEL`.
+    #[must_use]
+    pub const fn initial_placement_device(self) -> Self {
+        self.flags(CL_MEM_ALLOC_INITIAL_PLACEMENT_DEVICE_INTEL)
+    }
+
+    /// Place a shared allocation on the host before it is first accessed:
+    /// `CL_MEM_ALLOC_INITIAL_PLACEMENT_HOST_INTEL`.
+    #[must_use]
+    pub const fn initial_placement_host(self) -> Self {
+        self.flags(CL_MEM_ALLOC_INITIAL_PLACEMENT_HOST_INTEL)
+    }
+
+    /// The minimum alignment in bytes of the allocation, a power of two.
+    ///
+    /// The default is the alignment of the element type.
+    #[must_use]
+    pub const fn alignment(mut self, alignment: cl_uint) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// The zero terminated `cl_mem_properties_intel` list.
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<cl_mem_properties_intel> {
+        let mut properties = Vec::new();
+        if self.flags != 0 {
+            properties.push(CL_MEM_ALLOC_FLAGS_INTEL);
+            properties.push(self.flags);
+        }
+        properties.push(0);
+        properties
+    }
+
+    /// The alignment of an allocation of `T`.
+    #[allow(clippy::cast_possible_truncation)]
+    const fn alignment_of<T>(&self) -> cl_uint {
+        if self.alignment == 0 {
+            mem::align_of::<T>() as cl_uint
+        } else {
+            self.alignment
+        }
+    }
+}
+
+/// An owned unified shared memory allocation, see the module documentation.
+#[derive(Debug)]
+pub struct UsmBox<T: ?Sized> {
+    context: cl_context,
+    ptr: *mut T,
+    /// Whether commands that use the allocation may be outstanding.
+    enqueued: Cell<bool>,
+}
+
+impl<T: Copy> UsmBox<[T]> {
+    /// Allocate with one of the `cl_intel_unified_shared_memory` allocation functions.
+    fn alloc<F>(context: cl_context, len: usize, alloc: F) -> Result<Self, UsmError>
+    where
+        F: FnOnce(usize) -> Result<*mut c_void, cl_int>,
+    {
+        let size = mem::size_of::<T>()
+            .checked_mul(len)
+            .ok_or(UsmError::Cl(CL_INVALID_VALUE))?;
+        let data = alloc(size)?;
+        Ok(Self {
+            context,
+            ptr: ptr::slice_from_raw_parts_mut(data.cast::<T>(), len),
+            enqueued: Cell::new(false),
+        })
+    }
+
+    /// Allocate host memory for `len` elements.
+    /// Calls `clHostMemAllocINTEL`.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `len` - the number of elements.
+    /// * `properties` - the allocation properties.
+    ///
+    /// returns a Result containing the allocation
+    /// or the error code from the `OpenCL` C API function.
+    pub fn host(
+        context: cl_context,
+        len: usize,
+        properties: &UsmAllocProperties,
+    ) -> Result<Self, UsmError> {
+        let list = properties.to_vec();
+        let alignment = properties.alignment_of::<T>();
+        Self::alloc(context, len, |size| unsafe {
+            host_mem_alloc_intel(context, list.as_ptr(), size, alignment)
+        })
+    }
+
+    /// Allocate device memory for `len` elements.
+    /// Calls `clDeviceMemAllocINTEL`.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `device` - the device that owns the memory.
+    /// * `len` - the number of elements.
+    /// * `properties` - the allocation properties.
+    ///
+    /// returns a Result containing the allocation
+    /// or the error code from the `OpenCL` C API function.
+    pub fn device(
+        context: cl_context,
+        device: cl_device_id,
+        len: usize,
+        properties: &UsmAllocProperties,
+    ) -> Result<Self, UsmError> {
+        let list = properties.to_vec();
+        let alignment = properties.alignment_of::<T>();
+        Self::alloc(context, len, |size| unsafe {
+            device_mem_alloc_intel(context, device, list.as_ptr(), size, alignment)
+        })
+    }
+
+    /// Allocate shared memory for `len` elements.
+    /// Calls `clSharedMemAllocINTEL`.
+    ///
+    /// * `context` - a valid `OpenCL` context.
+    /// * `device` - the device that the memory is associated with,
+    ///   or null for none.
+    /// * `len` - the number of elements.
+    /// * `properties` - the allocation properties.
+    ///
+    /// returns a Result containing the allocation
+    /// or the error code from the `OpenCL` C API function.
+    pub fn shared(
+        context: cl_context,
+        device: cl_device_id,
+        len: usize,
+        properties: &UsmAllocProperties,
+    ) -> Result<Self, UsmError> {
+        let list = properties.to_vec();
+        let alignment = properties.alignment_of::<T>();
+        Self::alloc(context, len, |size| unsafe {
+            shared_mem_alloc_intel(context, device, list.as_ptr(), size, alignment)
+        })
+    }
+
+    #[must_use]
+    pub const fn context(&self) -> cl_context {
+        self.context
+    }
+
+    /// The number of elements.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.ptr.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The size in bytes of the allocation.
+    #[must_use]
+    pub const fn size(&self) -> usize {
+        mem::size_of::<T>() * self.len()
+    }
+
+    #[must_use]
+    pub const fn as_ptr(&self) -> *const T {
+        self.ptr.cast::<T>()
+    }
+
+    #[must_use]
+    pub const fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr.cast::<T>()
+    }
+
+    /// The kind of the allocation reported by `OpenCL`:
+    /// `CL_MEM_ALLOC_TYPE_INTEL`.
+    ///
+    /// returns a Result containing the kind of the allocation
+    /// or the error code from the `OpenCL` C API function.
+    pub fn kind(&self) -> Result<UsmKind, UsmError> {
+        let mem_type = get_mem_alloc_info_intel(
+            self.context,
+            self.as_ptr().cast::<c_void>(),
+            CL_MEM_ALLOC_TYPE_INTEL,
+        )?
+        .to_uint();
+        UsmKind::from_mem_type(mem_type).ok_or(UsmError::Cl(CL_INVALID_MEM_OBJECT))
+    }
+
+    /// The device that the allocation is associated with:
+    /// `CL_MEM_ALLOC_DEVICE_INTEL`, null for host allocations.
+    ///
+    /// returns a Result containing the device
+    /// or the error code from the `OpenCL` C API function.
+    pub fn device_id(&self) -> Result<cl_device_id, UsmError> {
+        let device = get_mem_alloc_info_intel(
+            self.context,
+            self.as_ptr().cast::<c_void>(),
+            CL_MEM_ALLOC_DEVICE_INTEL,
+        )?
+        .to_ptr();
+        Ok(device as cl_device_id)
+    }
+
+    /// The elements of a host or shared allocation.
+    ///
+    /// # Safety
+    ///
+    /// No enqueued commands may write to the allocation while the slice is
+    /// borrowed.
+    ///
+    /// returns a Result containing the elements
+    /// or `UsmError::NotHostAccessible` for device allocations.
+    pub unsafe fn as_slice(&self) -> Result<&[T], UsmError> {
+        self.check_host_accessible()?;
+        Ok(unsafe { &*self.ptr })
+    }
+
+    /// The mutable elements of a host or shared allocation.
+    ///
+    /// # Safety
+    ///
+    /// No enqueued commands may access the allocation while the slice is
+    /// borrowed.
+    ///
+    /// returns a Result containing the elements
+    /// or `UsmError::NotHostAccessible` for device allocations.
+    pub unsafe fn as_mut_slice(&mut self) -> Result<&mut [T], UsmError> {
+        self.check_host_accessible()?;
+        Ok(unsafe { &mut *self.ptr })
+    }
+
+    fn check_host_accessible(&self) -> Result<(), UsmError> {
+        let kind = self.kind()?;
+        if kind.is_host_accessible() {
+            Ok(())
+        } else {
+            Err(UsmError::NotHostAccessible(kind))
+        }
+    }
+
+    /// A pointer to `count` elements from `offset`, checked against the length.
+    const fn element_ptr(&self, offset: usize, count: usize) -> Result<*mut T, UsmError> {
+        let len = self.len();
+        match offset.checked_add(count) {
+            Some(end) if end <= len => Ok(unsafe { self.ptr.cast::<T>().add(offset) }),
+            _ => Err(UsmError::OutOfRange {
+                offset,
+                end: offset.saturating_add(count),
+                len,
+            }),
+        }
+    }
+
+    /// Set the allocation as a kernel argument.
+    /// Calls `clSetKernelArgMemPointerINTEL`.
+    ///
+    /// The allocation is freed with a blocking free, since the kernel may
+    /// still be using it.
+    ///
+    /// * `kernel` - the `OpenCL` kernel.
+    /// * `arg_index` - the index of the kernel argument.
+    ///
+    /// returns an empty Result or the error code from the `OpenCL` C API function.
+    pub fn set_kernel_arg(&self, kernel: cl_kernel, arg_index: cl_uint) -> Result<(), UsmError> {
+        unsafe {
+            set_kernel_arg_mem_pointer_intel(kernel, arg_index, self.as_ptr().cast::<c_void>())?;
+        }
+        self.enqueued.set(true);
+        Ok(())
+    }
+
+    /// Write host data into the allocation and wait for the write to complete.
+    /// Calls `clEnqueueMemcpyINTEL` with blocking set.
+    ///
+    /// * `command_queue` - the `OpenCL` command-queue.
+    /// * `offset` - the first element to write.
+    /// * `data` - the data to write.
+    /// * `event_wait_list` - the events that must complete before the write.
+    ///
+    /// returns a Result containing the event of the write
+    /// or a `UsmError`.
+    pub fn write(
+        &mut self,
+        command_queue: cl_command_queue,
+        offset: usize,
+        data: &[T],
+        event_wait_list: &[cl_event],
+    ) -> Result<cl_event, UsmError> {
+        let dst = self.element_ptr(offset, data.len())?;
+        let (num_events, events) = wait_list_parts(event_wait_list);
+        Ok(unsafe {
+            enqueue_mem_copy_intel(
+                command_queue,
+                CL_TRUE,
+                dst.cast::<c_void>(),
+                data.as_ptr().cast::<c_void>(),
+                mem::size_of_val(data),
+                num_events,
+                events,
+            )?
+        })
+    }
+
+    /// Read data from the allocation and wait for the read to complete.
+    /// Calls `clEnqueueMemcpyINTEL` with blocking set.
+    ///
+    /// `T` must be [`Pod`], since the allocation may hold any bit pattern,
+    /// e.g. uninitialised memory or values written by a kernel.
+    ///
+    /// * `command_queue` - the `OpenCL` command-queue.
+    /// * `offset` - the first element to read.
+    /// * `data` - the destination of the data.
+    /// * `event_wait_list` - the events that must complete before the read.
+    ///
+    /// returns a Result containing the event of the read
+    /// or a `UsmError`.
+    pub fn read(
+        &self,
+        command_queue: cl_command_queue,
+        offset: usize,
+        data: &mut [T],
+        event_wait_list: &[cl_event],
+    ) -> Result<cl_event, UsmError>
+    where
+        T: Pod,
+    {
+        let src = self.element_ptr(offset, data.len())?;
+        let (num_events, events) = wait_list_parts(event_wait_list);
+        Ok(unsafe {
+            enqueue_mem_copy_intel(
+                command_queue,
+                CL_TRUE,
+                data.as_mut_ptr().cast::<c_void>(),
+                src.cast::<c_void>(),
+                mem::size_of_val(data),
+                num_events,
+                events,
+            )?
+        })
+    }
+
+    /// Enqueue a copy of all of the elements of another allocation.
+    /// Calls `clEnqueueMemcpyINTEL`.
+    ///
+    /// * `command_queue` - the `OpenCL` command-queue.
+    /// * `offset` - the first element to copy to.
+    /// * `src` - the allocation to copy from.
+    /// * `event_wait_list` - the events that must complete before the copy.
+    ///
+    /// returns a Result containing the event of the copy
+    /// or a `UsmError`.
+    pub fn copy_from(
+        &mut self,
+        command_queue: cl_command_queue,
+        offset: usize,
+        src: &Self,
+        event_wait_list: &[cl_event],
+    ) -> Result<cl_event, UsmError> {
+        let dst = self.element_ptr(offset, src.len())?;
+        let (num_events, events) = wait_list_parts(event_wait_list);
+        let event = unsafe {
+            enqueue_mem_copy_intel(
+                command_queue,
+                CL_FALSE,
+                dst.cast::<c_void>(),
+                src.as_ptr().cast::<c_void>(),
+                src.size(),
+                num_events,
+                events,
+            )?
+        };
+        self.enqueued.set(true);
+        src.enqueued.set(true);
+        Ok(event)
+    }
+
+    /// Enqueue a fill of `count` elements from `offset` with a value.
+    /// Calls `clEnqueueMemFillINTEL`.
+    ///
+    /// * `command_queue` - the `OpenCL` command-queue.
+    /// * `value` - the value to fill the elements with.
+    /// * `offset` - the first element to fill.
+    /// * `count` - the number of elements to fill.
+    /// * `event_wait_list` - the events that must complete before the fill.
+    ///
+    /// returns a Result containing the event of the fill
+    /// or a `UsmError`.
+    pub fn fill(
+        &mut self,
+        command_queue: cl_command_queue,
+        value: T,
+        offset: usize,
+        count: usize,
+        event_wait_list: &[cl_event],
+    ) -> Result<cl_event, UsmError> {
+        let dst = self.element_ptr(offset, count)?;
+        let (num_events, events) = wait_list_parts(event_wait_list);
+        let event = unsafe {
+            enqueue_mem_fill_intel(
+                command_queue,
+                dst.cast::<c_void>(),
+                ptr::from_ref(&value).cast::<c_void>(),
+                mem::size_of::<T>(),
+                mem::size_of::<T>() * count,
+                num_events,
+                events,
+            )?
+        };
+        self.enqueued.set(true);
+        Ok(event)
+    }
+
+    /// Enqueue memory advice for `count` elements from `offset`.
+    /// Calls `clEnqueueMemAdviseINTEL`.
+    ///
+    /// * `command_queue` - the `OpenCL` command-queue.
+    /// * `advice` - the device specific advice.
+    /// * `offset` - the first element of the advice.
+    /// * `count` - the number of elements of the advice.
+    /// * `event_wait_list` - the events that must complete before the advice.
+    ///
+    /// returns a Result containing the event of the advice
+    /// or a `UsmError`.
+    pub fn advise(
+        &self,
+        command_queue: cl_command_queue,
+        advice: cl_mem_advice_intel,
+        offset: usize,
+        count: usize,
+        event_wait_list: &[cl_event],
+    ) -> Result<cl_event, UsmError> {
+        let data = self.element_ptr(offset, count)?;
+        let (num_events, events) = wait_list_parts(event_wait_list);
+        let event = unsafe {
+            enqueue_mem_advise_intel(
+                command_queue,
+                data.cast::<c_void>(),
+                mem::size_of::<T>() * count,
+                advice,
+                num_events,
+                events,
+            )?
+        };
+        self.enqueued.set(true);
+        Ok(event)
+    }
+
+    /// Enqueue a migration of `count` elements from `offset` of a shared
+    /// allocation. Calls `clEnqueueMigrateMemINTEL`.
+    ///
+    /// * `command_queue` - the `OpenCL` command-queue, the elements are
+    ///   migrated to its device unless `CL_MIGRATE_MEM_OBJECT_HOST` is set.
+    /// * `flags` - the migration flags.
+    /// * `offset` - the first element to migrate.
+    /// * `count` - the number of elements to migrate.
+    /// * `event_wait_list` - the events that must complete before the migration.
+    ///
+    /// returns a Result containing the event of the migration
+    /// or a `UsmError`.
+    pub fn migrate(
+        &self,
+        command_queue: cl_command_queue,
+        flags: cl_mem_migration_flags,
+        offset: usize,
+        count: usize,
+        event_wait_list: &[cl_event],
+    ) -> Result<cl_event, UsmError> {
+        let data = self.element_ptr(offset, count)?;
+        let (num_events, events) = wait_list_parts(event_wait_list);
+        let event = unsafe {
+            enqueue_migrate_mem_intel(
+                command_queue,
+                data.cast::<c_void>(),
+                mem::size_of::<T>() * count,
+                flags,
+                num_events,
+                events,
+            )?
+        };
+        self.enqueued.set(true);
+        Ok(event)
+    }
+}
+
+impl<T: ?Sized> Drop for UsmBox<T> {
+    fn drop(&mut self) {
+        let data = self.ptr.cast::<c_void>();
+        unsafe {
+            let _ = if self.enqueued.get() {
+                mem_blocking_free_intel(self.context, data)
+            } else {
+                mem_free_intel(self.context, data)
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_queue::{create_command_queue, release_command_queue};
+    use crate::context::{create_context, release_context};
+    use crate::device::{
+        CL_DEVICE_EXTENSIONS, CL_DEVICE_TYPE_GPU, get_device_ids, get_device_info,
+    };
+    use crate::event::release_event;
+    use crate::platform::get_platform_ids;
+
+    #[test]
+    fn test_usm_alloc_properties() {
+        assert_eq!(vec![0], UsmAllocProperties::new().to_vec());
+        let properties = UsmAllocProperties::new()
+            .write_combined()
+            .initial_placement_device()
+            .alignment(64);
+        assert_eq!(
+            vec![
+                CL_MEM_ALLOC_FLAGS_INTEL,
+                CL_MEM_ALLOC_WRITE_COMBINED_INTEL | CL_MEM_ALLOC_INITIAL_PLACEMENT_DEVICE_INTEL,
+                0
+            ],
+            properties.to_vec()
+        );
+        assert_eq!(64, properties.alignment_of::<u8>());
+        assert_eq!(8, UsmAllocProperties::new().alignment_of::<f64>());
+
+        for kind in [UsmKind::Host, UsmKind::Device, UsmKind::Shared] {
+            assert_eq!(Some(kind), UsmKind::from_mem_type(kind.mem_type()));
+        }
+        assert_eq!(None, UsmKind::from_mem_type(CL_MEM_TYPE_UNKNOWN_INTEL));
+        assert!(!UsmKind::Device.is_host_accessible());
+    }
+
+    #[test]
+    fn test_usm_box() {
+        let platform_ids = get_platform_ids().unwrap();
+
+        // Choose the first platform
+        let platform_id = platform_ids[0];
+
+        let device_ids = get_device_ids(platform_id, CL_DEVICE_TYPE_GPU).unwrap();
+        assert!(!device_ids.is_empty());
+
+        let device_id = device_ids[0];
+        let extensions = String::from(get_device_info(device_id, CL_DEVICE_EXTENSIONS).unwrap());
+        if !extensions.contains("cl_intel_unified_shared_memory") {
+            println!("cl_intel_unified_shared_memory is not supported");
+            return;
+        }
+
+        let context = create_context(&device_ids, ptr::null(), None, ptr::null_mut()).unwrap();
+        let queue = unsafe { create_command_queue(context, device_id, 0).unwrap() };
+
+        {
+            let mut device =
+                UsmBox::<[cl_uint]>::device(context, device_id, 4, &UsmAllocProperties::new())
+                    .unwrap();
+            assert_eq!(4, device.len());
+            assert_eq!(16, device.size());
+            assert_eq!(UsmKind::Device, device.kind().unwrap());
+            assert_eq!(device_id, device.device_id().unwrap());
+
+            let write_event = device.write(queue, 0, &[1, 2, 3, 4], &[]).unwrap();
+            let mut data = [0; 2];
+            let read_event = device.read(queue, 1, &mut data, &[]).unwrap();
+            assert_eq!([2, 3], data);
+            unsafe {
+                release_event(write_event).unwrap();
+                release_event(read_event).unwrap();
+            }
+            assert!(matches!(
+                device.read(queue, 3, &mut data, &[]),
+                Err(UsmError::OutOfRange {
+                    offset: 3,
+                    end: 5,
+                    len: 4
+                })
+            ));
+
+            let host = UsmBox::<[cl_uint]>::host(context, 4, &UsmAllocProperties::new()).unwrap();
+            assert_eq!(UsmKind::Host, host.kind().unwrap());
+        }
+
+        unsafe {
+            release_command_queue(queue).unwrap();
+            release_context(context).unwrap();
+        }
+    }
+}